@@ -1,12 +1,44 @@
 import test from 'ava';
+import fs from 'fs';
+import os from 'os';
 import path from 'path';
+import { spawnSync } from 'child_process';
 import { fileURLToPath } from 'url';
+import { Worker } from 'worker_threads';
 
 const __filename = fileURLToPath(import.meta.url);
 
 const __dirname = path.dirname(__filename);
 
-import { resolve } from '../index.js';
+import {
+  buildImportGraph,
+  bumpGeneration,
+  clearCache,
+  createResolver,
+  doctor,
+  dumpConfig,
+  explain,
+  getCacheStats,
+  getEffectivePaths,
+  getProjectInfo,
+  invalidate,
+  isCoreModule,
+  loadManifest,
+  resolve,
+  resolveAsync,
+  resolveBatch,
+  resolvePath,
+  Resolver,
+  registerProtocolHandler,
+  ResolverPlugin,
+  saveCache,
+  setCacheDir,
+  setLogger,
+  setStatCacheTtl,
+  unloadManifest,
+  warm,
+  writeManifest,
+} from '../index.js';
 
 test('resolve buildins', (t) => {
   t.deepEqual(resolve('inspector', '/some-dir', { project: ['tsconfig.json'] }), {
@@ -22,10 +54,9 @@ test('resolve buildins when no project provide', (t) => {
   });
 });
 
-test('resolve buildins with wrong tsconfig', (t) => {
-  t.deepEqual(resolve('inspector', '/some-dir', { project: ['tsconfig.dummy.json'] }), {
-    found: true,
-    path: '',
+test('throw on a missing tsconfig', (t) => {
+  t.throws(() => resolve('inspector', '/some-dir', { project: ['tsconfig.dummy.json'] }), {
+    message: /Project "tsconfig.dummy.json" not found/,
   });
 });
 
@@ -38,3 +69,2100 @@ test('resolve relative path with tsconfig', (t) => {
     },
   );
 });
+
+test('resolve with default extension order', (t) => {
+  t.deepEqual(
+    resolve('./importee', path.join(__dirname, '../fixtures/extensionOrder/index.ts'), { project: ['tsconfig.json'] }),
+    {
+      found: true,
+      path: path.resolve(path.join(__dirname, '../fixtures/extensionOrder/importee.js')),
+    },
+  );
+});
+
+test('resolve with typescript-first extension order preset', (t) => {
+  t.deepEqual(
+    resolve('./importee', path.join(__dirname, '../fixtures/extensionOrder/index.ts'), {
+      project: ['tsconfig.json'],
+      extensionOrderPreset: 'typescript-first',
+    }),
+    {
+      found: true,
+      path: path.resolve(path.join(__dirname, '../fixtures/extensionOrder/importee.ts')),
+    },
+  );
+});
+
+test('resolve with explicit extensions', (t) => {
+  t.deepEqual(
+    resolve('./importee', path.join(__dirname, '../fixtures/extensionOrder/index.ts'), {
+      project: ['tsconfig.json'],
+      extensions: ['.ts'],
+    }),
+    {
+      found: true,
+      path: path.resolve(path.join(__dirname, '../fixtures/extensionOrder/importee.ts')),
+    },
+  );
+});
+
+test('resolve vue single-file component with extension', (t) => {
+  t.deepEqual(
+    resolve('./Component.vue', path.join(__dirname, '../fixtures/withVue/index.ts'), { project: ['tsconfig.json'] }),
+    {
+      found: true,
+      path: path.resolve(path.join(__dirname, '../fixtures/withVue/Component.vue')),
+    },
+  );
+});
+
+test('resolve extensionless vue single-file component only with extra extensions', (t) => {
+  const file = path.join(__dirname, '../fixtures/withVue/index.ts');
+  t.like(resolve('./Component', file, { project: ['tsconfig.json'] }), {
+    found: false,
+    path: '',
+    reason: 'ExtensionMissing',
+  });
+  t.deepEqual(resolve('./Component', file, { project: ['tsconfig.json'], extraExtensions: ['.vue'] }), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withVue/Component.vue')),
+  });
+  t.deepEqual(resolve('./components', file, { project: ['tsconfig.json'], extraExtensions: ['.vue'] }), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withVue/components/index.vue')),
+  });
+});
+
+test('resolve svelte and astro components with frameworks preset', (t) => {
+  const file = path.join(__dirname, '../fixtures/withSvelteAndAstro/index.ts');
+  const options = { project: ['tsconfig.json'], frameworks: ['svelte', 'astro'] };
+  t.deepEqual(resolve('./Button', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withSvelteAndAstro/Button.svelte')),
+  });
+  t.deepEqual(resolve('./Layout', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withSvelteAndAstro/Layout.astro')),
+  });
+  t.deepEqual(resolve('./dist/Card.svelte', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withSvelteAndAstro/dist/Card.svelte.d.ts')),
+  });
+});
+
+test('resolve style sheets by file existence', (t) => {
+  const file = path.join(__dirname, '../fixtures/withStyles/index.ts');
+  const options = { project: ['tsconfig.json'], styleExtensions: ['.css', '.scss', '.less'] };
+  t.deepEqual(resolve('./styles.module.scss', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withStyles/styles.module.scss')),
+  });
+  t.deepEqual(resolve('./theme.css', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withStyles/theme.css')),
+  });
+  t.like(resolve('./missing.css', file, options), {
+    found: false,
+    path: '',
+    reason: 'NotFound',
+  });
+});
+
+test('resolve assets by file existence', (t) => {
+  const file = path.join(__dirname, '../fixtures/withAssets/index.ts');
+  const options = { project: ['tsconfig.json'], assetExtensions: ['.svg', '.woff2'] };
+  t.deepEqual(resolve('./fonts/inter.woff2', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withAssets/fonts/inter.woff2')),
+  });
+  t.like(resolve('./missing.svg', file, options), {
+    found: false,
+    path: '',
+    reason: 'NotFound',
+  });
+});
+
+test('resolve missing assets when always found', (t) => {
+  const file = path.join(__dirname, '../fixtures/withAssets/index.ts');
+  const options = { project: ['tsconfig.json'], assetExtensions: ['.svg'], assetsAlwaysFound: true };
+  t.deepEqual(resolve('./generated/icon.svg?react', file, options), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withAssets/generated/icon.svg')),
+  });
+  t.like(resolve('./missing.ts', file, options), {
+    found: false,
+    path: '',
+    reason: 'NotFound',
+  });
+});
+
+test('resolve react native platform specific files', (t) => {
+  const file = path.join(__dirname, '../fixtures/withReactNative/index.ts');
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withReactNative', part));
+  t.deepEqual(resolve('./styles', file, { project: ['tsconfig.json'], platforms: ['android', 'native'] }), {
+    found: true,
+    path: fixture('styles.android.ts'),
+  });
+  t.deepEqual(resolve('./button', file, { project: ['tsconfig.json'], platforms: ['android', 'native'] }), {
+    found: true,
+    path: fixture('button/index.native.tsx'),
+  });
+  t.deepEqual(resolve('./styles', file, { project: ['tsconfig.json'] }), {
+    found: true,
+    path: fixture('styles.ts'),
+  });
+  t.deepEqual(resolve('rn-lib', file, { project: ['fixtures/withReactNative/tsconfig.json'], platforms: ['ios'] }), {
+    found: true,
+    path: fixture('node_modules/rn-lib/src/index.ts'),
+  });
+});
+
+test('resolve flow declaration sidecars when enabled', (t) => {
+  const file = path.join(__dirname, '../fixtures/withFlow/index.ts');
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withFlow', part));
+  t.deepEqual(resolve('./typed', file, { project: ['tsconfig.json'] }), {
+    found: true,
+    path: fixture('typed.js'),
+  });
+  t.deepEqual(resolve('./typed', file, { project: ['tsconfig.json'], flowDeclarations: true }), {
+    found: true,
+    path: fixture('typed.js.flow'),
+  });
+  t.deepEqual(resolve('./untyped', file, { project: ['tsconfig.json'], flowDeclarations: true }), {
+    found: true,
+    path: fixture('untyped.js'),
+  });
+});
+
+test('resolve declaration maps back to original sources when enabled', (t) => {
+  const file = path.join(__dirname, '../fixtures/withDeclarationMaps/index.ts');
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withDeclarationMaps', part));
+  t.deepEqual(resolve('./dist/foo', file, { project: ['tsconfig.json'] }), {
+    found: true,
+    path: fixture('dist/foo.d.ts'),
+  });
+  t.deepEqual(resolve('./dist/foo', file, { project: ['tsconfig.json'], followDeclarationMaps: true }), {
+    found: true,
+    path: fixture('src/foo.ts'),
+  });
+  t.deepEqual(resolve('./dist/bar', file, { project: ['tsconfig.json'], followDeclarationMaps: true }), {
+    found: true,
+    path: fixture('dist/bar.d.ts'),
+  });
+});
+
+test('resolve implementation of declaration files by preference', (t) => {
+  const file = path.join(__dirname, '../fixtures/withImplementation/index.ts');
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withImplementation', part));
+  const options = { project: ['tsconfig.json'], extensions: ['.d.ts', '.js'] };
+  t.deepEqual(resolve('./lib', file, options), {
+    found: true,
+    path: fixture('lib.d.ts'),
+  });
+  t.deepEqual(resolve('./lib', file, { ...options, declarationPreference: 'implementation' }), {
+    found: true,
+    path: fixture('lib.js'),
+  });
+  t.deepEqual(resolve('./lib', file, { ...options, declarationPreference: 'both' }), {
+    found: true,
+    path: fixture('lib.d.ts'),
+    implementationPath: fixture('lib.js'),
+  });
+});
+
+test('resolve with options', (t) => {
+  const file = path.join(__dirname, '../fixtures/withExports/index.ts');
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withExports', part));
+  const project = ['fixtures/withExports/tsconfig.json'];
+  t.deepEqual(resolve('cond-pkg', file, { project }), {
+    found: true,
+    path: fixture('node_modules/cond-pkg/index.d.ts'),
+  });
+  t.deepEqual(resolve('cond-pkg/feature', file, { project }), {
+    found: true,
+    path: fixture('node_modules/cond-pkg/feature.mjs'),
+  });
+  t.deepEqual(resolve('cond-pkg', file, { project, conditionNames: ['require'] }), {
+    found: true,
+    path: fixture('node_modules/cond-pkg/index.cjs'),
+  });
+  t.deepEqual(resolve('cond-pkg', file, { project, conditionNames: [] }), {
+    found: true,
+    path: fixture('node_modules/cond-pkg/index.js'),
+  });
+});
+
+test('resolve with persistent resolver', (t) => {
+  const resolver = new Resolver({ project: ['tsconfig.json'], extensionOrderPreset: 'typescript-first' });
+  t.deepEqual(resolver.resolve('./importee', path.join(__dirname, '../fixtures/extensionOrder/index.ts')), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/extensionOrder/importee.ts')),
+  });
+  t.deepEqual(resolver.resolve('inspector', '/some-dir'), {
+    found: true,
+    path: '',
+  });
+});
+
+test('resolve asynchronously', async (t) => {
+  t.deepEqual(
+    await resolveAsync('./tsImportee', path.join(__dirname, '../fixtures/withoutPaths/index.ts'), {
+      project: ['tsconfig.json'],
+    }),
+    {
+      found: true,
+      path: path.resolve(path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts')),
+    },
+  );
+});
+
+test('resolve after clearing and invalidating caches', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const expected = {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts')),
+  };
+  t.deepEqual(resolve('./tsImportee', file, { project: ['tsconfig.json'] }), expected);
+  invalidate(path.resolve('tsconfig.json'));
+  t.deepEqual(resolve('./tsImportee', file, { project: ['tsconfig.json'] }), expected);
+  clearCache();
+  t.deepEqual(resolve('./tsImportee', file, { project: ['tsconfig.json'] }), expected);
+});
+
+test('resolve through the import-resolver v3 interface', (t) => {
+  const resolver = new Resolver({ project: ['tsconfig.json'] });
+  t.is(resolver.interfaceVersion, 3);
+  t.is(resolver.name, 'eslint-import-resolver-typescript');
+  t.deepEqual(resolver.resolve('./tsImportee', path.join(__dirname, '../fixtures/withoutPaths/index.ts')), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts')),
+  });
+});
+
+test('create an import-resolver v3 resolver for import-x flat configs', (t) => {
+  const resolver = createResolver({ project: 'tsconfig.json', alias: { '@importee': './tsImportee' } });
+  t.like(resolver, { interfaceVersion: 3, name: 'eslint-import-resolver-typescript' });
+  t.deepEqual(resolver.resolve('@importee', path.join(__dirname, '../fixtures/withoutPaths/index.ts')), {
+    found: true,
+    path: path.resolve(path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts')),
+  });
+  t.throws(() => createResolver({ frameworks: ['react'] }), { message: /frameworks/ });
+});
+
+test('resolve failures with reasons and attempted paths', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const caseMismatch = resolve('./TsImportee', file, { project: ['tsconfig.json'] });
+  t.like(caseMismatch, { found: false, path: '', reason: 'CaseMismatch' });
+  t.true(caseMismatch.attemptedPaths.includes(path.join(__dirname, '../fixtures/withoutPaths/TsImportee.ts')));
+
+  const exportsFile = path.join(__dirname, '../fixtures/withExports/index.ts');
+  const project = ['fixtures/withExports/tsconfig.json'];
+  t.like(resolve('cond-pkg/missing', exportsFile, { project }), { found: false, reason: 'NotExported' });
+  t.like(resolve('cond-pkg/internal/secret', exportsFile, { project }), {
+    found: false,
+    reason: 'BlockedByExportsNull',
+  });
+});
+
+test('resolve with trace', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const importee = path.resolve(path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts'));
+  const resolved = resolve('./tsImportee', file, { project: ['tsconfig.json'], trace: true });
+  t.like(resolved, { found: true, path: importee });
+  t.true(resolved.trace.includes(`probe ${importee}: found`));
+  t.is(resolved.trace[resolved.trace.length - 1], `found ${importee}`);
+  t.is(resolve('./tsImportee', file, { project: ['tsconfig.json'] }).trace, undefined);
+});
+
+test('print resolutions and failure traces from the ts-resolve CLI', (t) => {
+  const cli = path.join(__dirname, '../bin/ts-resolve.js');
+  const run = (...args) => spawnSync(process.execPath, [cli, ...args], { encoding: 'utf8' });
+  const from = ['--from', 'fixtures/withoutPaths/index.ts', '--project', 'tsconfig.json'];
+
+  const found = run('./tsImportee', ...from);
+  t.is(found.status, 0);
+  t.is(found.stdout, `${path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts')}\n`);
+
+  const missing = run('./missing', ...from, '--trace');
+  t.is(missing.status, 1);
+  t.regex(missing.stderr, /^Cannot resolve "\.\/missing" from .*index\.ts: NotFound\n/);
+  t.regex(missing.stderr, /\n {2}probe .*missing\.ts: missing\n/);
+
+  t.regex(run('./tsImporte', ...from).stderr, /\nDid you mean "\.\/tsImportee"/);
+  const explained = run('./tsImportee', ...from, '--explain');
+  t.is(explained.status, 0);
+  t.regex(explained.stdout, /^Resolving "\.\/tsImportee" from .*\n {2}project /);
+  t.is(run('./tsImportee').status, 2);
+});
+
+test('check core modules', (t) => {
+  t.true(isCoreModule('fs'));
+  t.true(isCoreModule('node:fs'));
+  t.false(isCoreModule('./fs'));
+  t.false(isCoreModule('typescript'));
+});
+
+test('get effective paths after extends and ${configDir} expansion', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withConfigDir', part));
+  t.deepEqual(getEffectivePaths(fixture('app/tsconfig.json')), {
+    project: fixture('app/tsconfig.json'),
+    baseUrl: fixture('app'),
+    paths: { '@/*': [`${fixture('app')}/src/*`] },
+    references: [fixture('lib/tsconfig.json')],
+  });
+  t.deepEqual(resolve('@/foo', fixture('app/index.ts'), { project: [fixture('app/tsconfig.json')] }), {
+    found: true,
+    path: fixture('app/src/foo.ts'),
+  });
+});
+
+test('get project info of a file', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withConfigDir', part));
+  const project = [fixture('app/tsconfig.json'), fixture('lib/tsconfig.json')];
+  t.deepEqual(getProjectInfo(fixture('app/src/foo.ts'), { project }), {
+    project: fixture('app/tsconfig.json'),
+    baseDir: fixture('app'),
+    moduleResolution: 'node',
+  });
+  t.deepEqual(getProjectInfo(fixture('lib/index.ts')), {
+    project: fixture('lib/tsconfig.json'),
+    baseDir: fixture('lib'),
+  });
+});
+
+test('resolve with metadata', (t) => {
+  const exportsFixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withExports', part));
+  t.deepEqual(
+    resolve('cond-pkg', exportsFixture('index.ts'), { project: ['fixtures/withExports/tsconfig.json'], metadata: true }),
+    {
+      found: true,
+      path: exportsFixture('node_modules/cond-pkg/index.d.ts'),
+      packageName: 'cond-pkg',
+      packageVersion: '1.2.3',
+      isExternal: true,
+      isWorkspacePackage: false,
+      isDeclarationFile: true,
+    },
+  );
+  t.like(
+    resolve('folder/tsImportee', path.join(__dirname, '../fixtures/withPaths/index.ts'), {
+      project: ['fixtures/withPaths/tsconfig.json'],
+      metadata: true,
+    }),
+    { found: true, isExternal: false, isDeclarationFile: false, matchedPattern: 'folder/*' },
+  );
+  t.deepEqual(resolve('inspector', '/some-dir', { project: [], metadata: true }), { found: true, path: '' });
+});
+
+test('log exports mismatches to the registered logger', async (t) => {
+  const file = path.join(__dirname, '../fixtures/withExports/index.ts');
+  const event = new Promise((done) => {
+    setLogger((event) => {
+      if (event.kind === 'ExportsMismatch') done(event);
+    });
+  });
+  clearCache();
+  resolve('cond-pkg/missing', file, { project: ['fixtures/withExports/tsconfig.json'] });
+  t.like(await event, { kind: 'ExportsMismatch', path: file });
+  setLogger();
+});
+
+test('resolve custom protocols through registered handlers', async (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const icon = path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts');
+  const options = { project: ['tsconfig.json'] };
+  registerProtocolHandler('virtual:', () => ({ external: true }));
+  registerProtocolHandler('~icons/', (specifier) => (specifier === '~icons/home' ? icon : null));
+  t.like(resolve('virtual:routes', file, options), { found: true, isExternal: true });
+  t.deepEqual(resolve('~icons/home', file, options), { found: true, path: icon });
+  t.like(await resolveAsync('~icons/home', file, options), { found: true, path: icon });
+  t.false(resolve('~icons/missing', file, options).found);
+  registerProtocolHandler('virtual:');
+  registerProtocolHandler('~icons/', null);
+  t.false(resolve('virtual:routes', file, options).found);
+});
+
+test('report ignored specifiers as external without looking for them', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const options = { project: ['tsconfig.json'], ignore: ['^electron(/|$)', /^VSCODE$/i], probeCounts: true };
+  t.like(resolve('electron', file, options), {
+    found: true,
+    path: '',
+    isExternal: true,
+    probeCounts: { fileChecks: 0, packageJsonReads: 0 },
+  });
+  t.like(resolve('vscode', file, options), { found: true, isExternal: true });
+  t.false(resolve('electron-store', file, options).found);
+  t.throws(() => resolve('electron', file, { ignore: ['(?<=x)'] }), {
+    message: /Invalid regular expression "\(\?<=x\)" in `ignore`/,
+  });
+});
+
+test('leave specifiers not matching resolveOnly to other resolvers', (t) => {
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const options = { project: ['fixtures/withPaths/tsconfig.json'], resolveOnly: ['folder/**', './*'] };
+  t.like(resolve('./tsImportee', file, options), { found: true });
+  t.like(resolve('folder/subfolder/tsImportee', file, options), { found: true });
+  t.deepEqual(resolve('../withoutPaths/tsImportee', file, options), { found: false, path: '', reason: 'NotHandled' });
+  t.like(resolve('fs', file, options), { found: false, reason: 'NotHandled' });
+  t.throws(() => resolve('fs', file, { resolveOnly: ['[a'] }), {
+    message: /Invalid specifier pattern "\[a" in `resolveOnly`/,
+  });
+});
+
+test('resolve imports of relative files from their dir under cwd', (t) => {
+  const cwd = path.join(__dirname, '../fixtures/withoutPaths');
+  const options = { project: [path.join(cwd, 'tsconfig.json')], cwd };
+  t.deepEqual(resolve('../tsImportee', 'subfolder/index.ts', options), {
+    found: true,
+    path: path.join(cwd, 'tsImportee.ts'),
+  });
+  t.deepEqual(resolve('./tsImportee', 'index.ts', options), resolve('./tsImportee', path.join(cwd, 'index.ts'), options));
+});
+
+test('read projects and inline tsconfigs relative to cwd', (t) => {
+  const cwd = path.join(__dirname, '../fixtures/withPaths');
+  const file = path.join(cwd, 'index.ts');
+  const expected = { found: true, path: path.join(cwd, 'tsImportee.ts') };
+  t.like(resolve('folder/tsImportee', file, { project: ['tsconfig.json'], cwd }), expected);
+  t.false(resolve('folder/tsImportee', file, { project: ['tsconfig.json'] }).found);
+  const tsconfigContent = { compilerOptions: { paths: { '@app/*': ['./*'] } } };
+  t.like(resolve('@app/tsImportee', file, { tsconfigContent, cwd }), expected);
+  t.true(isCoreModule('fs', null, cwd));
+});
+
+test('follow extends of a relative project from cwd', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', JSON.stringify({ extends: './base.json' }));
+  write('base.json', JSON.stringify({ compilerOptions: { paths: { '@lib/*': ['./lib/*'] } } }));
+  write('lib/util.ts', 'export {}');
+  const file = path.join(dir, 'src/index.ts');
+  const expected = { found: true, path: path.join(dir, 'lib/util.ts') };
+  t.like(resolve('@lib/util', file, { project: ['tsconfig.json'], cwd: dir }), expected);
+  t.like(resolve('@lib/util', file, { cwd: dir }), expected);
+  t.false(resolve('@lib/util', file, { project: ['tsconfig.json'] }).found);
+  t.like(getProjectInfo(file, { project: ['tsconfig.json'], cwd: dir }), {
+    project: path.join(dir, 'tsconfig.json'),
+    baseDir: dir,
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('count cache hits', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  resolve('./tsImportee', file, { project: ['tsconfig.json'] });
+  const before = getCacheStats();
+  resolve('./tsImportee', file, { project: ['tsconfig.json'] });
+  const after = getCacheStats();
+  t.true(after.resolutions.hits > before.resolutions.hits);
+  t.true(after.tsconfigs.size > 0);
+});
+
+test('warm a project before resolving', async (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withExports', part));
+  await warm(fixture('tsconfig.json'), true);
+  t.true(getCacheStats().tsconfigs.size > 0);
+  t.deepEqual(resolve('cond-pkg', fixture('index.ts'), { project: [fixture('tsconfig.json')] }), {
+    found: true,
+    path: fixture('node_modules/cond-pkg/index.d.ts'),
+  });
+});
+
+test('dump the effective configuration', (t) => {
+  const config = dumpConfig({ platforms: ['ios'], conditionNames: ['import'] });
+  t.like(config, {
+    projects: [],
+    conditionNames: ['import'],
+    builtinsSource: path.resolve(path.join(__dirname, '../node_modules/@types/node')),
+    preserveSymlinks: false,
+  });
+  t.is(config.extensions[0], '.ios.js');
+  t.is(config.mainFields[0], 'react-native');
+  t.deepEqual(new Resolver({ conditionNames: ['import'] }).dumpConfig().conditionNames, ['import']);
+});
+
+test('resolve with inline tsconfig content', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withConfigDir', part));
+  const expected = { found: true, path: fixture('app/src/foo.ts') };
+  const tsconfigContent = {
+    compilerOptions: { baseUrl: 'fixtures/withConfigDir/app', paths: { '@/*': ['src/*'] } },
+  };
+  t.deepEqual(resolve('@/foo', fixture('app/index.ts'), { tsconfigContent }), expected);
+  t.deepEqual(
+    resolve('@/foo', fixture('app/index.ts'), {
+      tsconfigContent: `{
+        // JSONC is fine too
+        "compilerOptions": { "baseUrl": "fixtures/withConfigDir/app", "paths": { "@/*": ["src/*"], }, },
+      }`,
+    }),
+    expected,
+  );
+});
+
+test('resolve with given compiler options', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withConfigDir', part));
+  t.deepEqual(
+    resolve('@/foo', fixture('app/index.ts'), {
+      compilerOptions: { baseUrl: fixture('app'), paths: { '@/*': ['src/*'] } },
+    }),
+    { found: true, path: fixture('app/src/foo.ts') },
+  );
+
+  const exportsFixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withExports', part));
+  t.deepEqual(
+    resolve('cond-pkg/feature', exportsFixture('index.ts'), {
+      extensions: ['.ts'],
+      conditionNames: [],
+      compilerOptions: { baseUrl: exportsFixture('.'), customConditions: ['require'] },
+    }),
+    { found: true, path: exportsFixture('node_modules/cond-pkg/feature.cjs') },
+  );
+});
+
+test('throw on malformed project patterns', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  t.throws(() => resolve('./tsImportee', file, { project: ['fixtures/[withoutPaths/tsconfig.json'] }), {
+    message: /Invalid project pattern/,
+  });
+  t.like(resolve('./missing', '/', { project: [] }), { found: false });
+});
+
+test('throw on invalid options', (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const options = { project: ['tsconfig.json'] };
+  t.throws(() => resolve('./tsImportee', file, { ...options, conditionNames: ['improt'] }), {
+    message: 'Unknown condition name "improt" in `conditionNames`, did you mean "import"?',
+  });
+  t.throws(() => resolve('./tsImportee', file, { ...options, extensions: ['ts'] }), {
+    message: /Malformed extension "ts" in `extensions`/,
+  });
+  t.throws(() => new Resolver({ ...options, frameworks: ['react'] }), {
+    message: /Unknown value "react" for `frameworks`/,
+  });
+  t.like(resolve('./tsImportee', file, { ...options, conditionNames: ['my-custom-condition'] }), { found: true });
+});
+
+test('pick up tsconfig edits and tell projects apart', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  for (const target of ['a', 'b']) {
+    fs.mkdirSync(path.join(dir, target));
+    fs.writeFileSync(path.join(dir, target, 'foo.ts'), 'export default 1');
+  }
+  const tsconfig = path.join(dir, 'tsconfig.json');
+  const file = path.join(dir, 'index.ts');
+  fs.writeFileSync(tsconfig, JSON.stringify({ compilerOptions: { paths: { '@/*': ['./a/*'] } } }));
+  t.deepEqual(resolve('@/foo', file, { project: [tsconfig] }), { found: true, path: path.join(dir, 'a/foo.ts') });
+
+  fs.writeFileSync(tsconfig, JSON.stringify({ compilerOptions: { paths: { '@/*': ['./b/*'], '~/*': ['./b/*'] } } }));
+  bumpGeneration();
+  t.deepEqual(resolve('@/foo', file, { project: [tsconfig] }), { found: true, path: path.join(dir, 'b/foo.ts') });
+
+  const withPaths = path.join(__dirname, '../fixtures/withPaths');
+  const project = [path.join(withPaths, 'tsconfig.json')];
+  t.deepEqual(resolve('folder/tsImportee', path.join(withPaths, 'index.ts'), { project }), {
+    found: true,
+    path: path.join(withPaths, 'tsImportee.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('keep interleaved projects apart, however their paths are spelled', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  for (const name of ['a', 'b']) {
+    fs.mkdirSync(path.join(dir, name, 'src'), { recursive: true });
+    fs.writeFileSync(path.join(dir, name, 'src', `${name}.ts`), 'export default 1');
+    fs.writeFileSync(
+      path.join(dir, name, 'tsconfig.json'),
+      JSON.stringify({ compilerOptions: { baseUrl: 'src', paths: { '@/*': ['./*'] } } }),
+    );
+  }
+  for (let round = 0; round < 3; round++) {
+    for (const name of ['a', 'b']) {
+      const project = [path.join(dir, name, round === 1 ? './tsconfig.json' : 'tsconfig.json')];
+      const file = path.join(dir, name, 'index.ts');
+      t.deepEqual(resolve(`@/${name}`, file, { project }), {
+        found: true,
+        path: path.join(dir, name, 'src', `${name}.ts`),
+      });
+      t.false(resolve(`@/${name === 'a' ? 'b' : 'a'}`, file, { project }).found);
+      t.is(getEffectivePaths(project[0]).baseUrl, path.join(dir, name, 'src'));
+    }
+  }
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('prefer the paths pattern with the longest prefix like tsc', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  for (const target of ['src/legacy', 'legacy']) {
+    fs.mkdirSync(path.join(dir, target), { recursive: true });
+    fs.writeFileSync(path.join(dir, target, 'foo.ts'), 'export default 1');
+  }
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.writeFileSync(
+    project[0],
+    JSON.stringify({ compilerOptions: { paths: { '@app/*': ['./src/*'], '@app/legacy/*': ['./legacy/*'] } } }),
+  );
+  const file = path.join(dir, 'index.ts');
+  t.like(resolve('@app/legacy/foo', file, { project }), { found: true, path: path.join(dir, 'legacy/foo.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('break ties between equally specific paths patterns in declaration order', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  for (const target of ['a', 'b']) {
+    fs.mkdirSync(path.join(dir, target));
+    fs.writeFileSync(path.join(dir, target, 'foo.ts'), 'export default 1');
+  }
+  const file = path.join(dir, 'index.ts');
+  for (const [first, second] of [
+    ['a', 'b'],
+    ['b', 'a'],
+  ]) {
+    const project = [path.join(dir, `tsconfig.${first}.json`)];
+    const paths = { 'lib/*': [`./${first}/*`], 'lib/*o': [`./${second}/*o`] };
+    fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths } }));
+    for (let round = 0; round < 5; round++) {
+      t.like(resolve('lib/foo', file, { project }), { found: true, path: path.join(dir, first, 'foo.ts') });
+    }
+  }
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('fall through the targets of exact paths mappings', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.mkdirSync(path.join(dir, 'vendor/jquery/dist'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'vendor/jquery/dist/jquery.d.ts'), 'export {}');
+  fs.mkdirSync(path.join(dir, 'node_modules/lodash'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'node_modules/lodash/index.d.ts'), 'export {}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const paths = {
+    jquery: ['./vendor/missing/jquery', './vendor/jquery/dist/jquery'],
+    lodash: ['./vendor/missing/lodash'],
+  };
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths } }));
+  const file = path.join(dir, 'index.ts');
+  t.like(resolve('jquery', file, { project }), { found: true, path: path.join(dir, 'vendor/jquery/dist/jquery.d.ts') });
+  t.like(resolve('jquery/dist', file, { project }), { found: false });
+  t.like(resolve('lodash', file, { project }), { found: true, path: path.join(dir, 'node_modules/lodash/index.d.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('skip paths patterns and targets with more than one star', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.mkdirSync(path.join(dir, 'src/foo'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'src/foo/bar.ts'), 'export default 1');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const paths = { '@/*/*': ['./src/*/*'], '@/*': ['./other/*/*', './src/*'] };
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths } }));
+  const file = path.join(dir, 'index.ts');
+  t.deepEqual(getEffectivePaths(project[0]).paths, { '@/*': ['./src/*'] });
+  t.like(resolve('@/foo/bar', file, { project }), { found: true, path: path.join(dir, 'src/foo/bar.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve paths targets outside the base dir', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.mkdirSync(path.join(dir, 'shared/src'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'shared/src/foo.ts'), 'export default 1');
+  fs.mkdirSync(path.join(dir, 'app/src'), { recursive: true });
+  const project = [path.join(dir, 'app/tsconfig.json')];
+  const paths = { '@shared/*': ['../shared/src/*'] };
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths } }));
+  const file = path.join(dir, 'app/src/index.ts');
+  t.like(resolve('@shared/foo', file, { project }), { found: true, path: path.join(dir, 'shared/src/foo.ts') });
+  const { attemptedPaths } = resolve('@shared/missing', file, { project });
+  t.true(attemptedPaths.includes(path.join(dir, 'shared/src/missing.ts')));
+  t.false(attemptedPaths.some((attempted) => attempted.includes('..')));
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('map paths before node_modules unless packages are preferred', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  for (const target of [
+    'src/shadowed.ts',
+    'src/typed.ts',
+    'node_modules/shadowed/index.d.ts',
+    'node_modules/@types/typed/index.d.ts',
+  ]) {
+    fs.mkdirSync(path.dirname(path.join(dir, target)), { recursive: true });
+    fs.writeFileSync(path.join(dir, target), 'export {}');
+  }
+  const project = [path.join(dir, 'tsconfig.json')];
+  const paths = { shadowed: ['./src/shadowed'], typed: ['./src/typed'] };
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths } }));
+  const file = path.join(dir, 'index.ts');
+  t.like(resolve('shadowed', file, { project }), { found: true, path: path.join(dir, 'src/shadowed.ts') });
+  t.like(resolve('shadowed', file, { project, preferTsPaths: false }), {
+    found: true,
+    path: path.join(dir, 'node_modules/shadowed/index.d.ts'),
+  });
+  for (const preferTsPaths of [true, false]) {
+    t.like(resolve('typed', file, { project, preferTsPaths }), { found: true, path: path.join(dir, 'src/typed.ts') });
+  }
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve imports of dependencies without the project aliases', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const pkg = path.join(dir, 'node_modules/client');
+  for (const target of [
+    'src/dep.ts',
+    'node_modules/dep/index.d.ts',
+    'node_modules/client/node_modules/dep/index.d.ts',
+  ]) {
+    fs.mkdirSync(path.dirname(path.join(dir, target)), { recursive: true });
+    fs.writeFileSync(path.join(dir, target), 'export {}');
+  }
+  fs.mkdirSync(path.join(pkg, 'dist'));
+  fs.writeFileSync(path.join(pkg, 'dist/util.d.ts'), 'export {}');
+  fs.writeFileSync(
+    path.join(pkg, 'package.json'),
+    JSON.stringify({ name: 'client', exports: { './util': { types: './dist/util.d.ts' } } }),
+  );
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths: { dep: ['./src/dep'] } } }));
+
+  t.like(resolve('dep', path.join(dir, 'index.ts'), { project }), { found: true, path: path.join(dir, 'src/dep.ts') });
+  const file = path.join(pkg, 'dist/index.d.ts');
+  t.like(resolve('dep', file, { project }), { found: true, path: path.join(pkg, 'node_modules/dep/index.d.ts') });
+  t.like(resolve('client/util', file, { project }), { found: true, path: path.join(pkg, 'dist/util.d.ts') });
+  t.like(resolve('./util', file, { project }), { found: true, path: path.join(pkg, 'dist/util.d.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve paths targets naming packages through node_modules', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  for (const target of ['node_modules/lodash/index.d.ts', 'node_modules/some-pkg/icons/star.d.ts']) {
+    fs.mkdirSync(path.dirname(path.join(dir, target)), { recursive: true });
+    fs.writeFileSync(path.join(dir, target), 'export {}');
+  }
+  const project = [path.join(dir, 'tsconfig.json')];
+  const paths = { 'lodash-es': ['lodash'], '@app/icons/*': ['some-pkg/icons/*'] };
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths } }));
+  const file = path.join(dir, 'src/index.ts');
+  t.like(resolve('lodash-es', file, { project }), { found: true, path: path.join(dir, 'node_modules/lodash/index.d.ts') });
+  t.like(resolve('@app/icons/star', file, { project }), {
+    found: true,
+    path: path.join(dir, 'node_modules/some-pkg/icons/star.d.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('prefer TypeScript sources among mapped candidates', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.mkdirSync(path.join(dir, 'lib'));
+  for (const ext of ['.js', '.d.ts', '.ts']) {
+    fs.writeFileSync(path.join(dir, 'lib', `foo${ext}`), 'export {}');
+  }
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.writeFileSync(project[0], JSON.stringify({ compilerOptions: { paths: { '@lib/*': ['./lib/*'] } } }));
+  const file = path.join(dir, 'index.ts');
+  t.like(resolve('@lib/foo', file, { project }), { found: true, path: path.join(dir, 'lib/foo.ts') });
+  t.like(resolve('@lib/foo', file, { project, pathsExtensions: ['.d.ts', '.js'] }), {
+    found: true,
+    path: path.join(dir, 'lib/foo.d.ts'),
+  });
+  t.deepEqual(dumpConfig({}).pathsExtensions.slice(0, 3), ['.ts', '.tsx', '.d.ts']);
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('try the catch-all paths pattern after more specific ones', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withCatchAllPaths', part));
+  const project = [fixture('tsconfig.json')];
+  const resolveFixture = (source) => resolve(source, fixture('index.ts'), { project, metadata: true });
+  t.like(resolveFixture('@app/foo'), { found: true, path: fixture('app/foo.ts'), matchedPattern: '@app/*' });
+  t.like(resolveFixture('bar'), { found: true, path: fixture('src/bar.ts'), matchedPattern: '*' });
+  t.like(resolveFixture('baz'), { found: true, path: fixture('vendor/baz/index.d.ts'), matchedPattern: '*' });
+  t.like(resolveFixture('fs'), { found: true, path: '' });
+});
+
+test('accept the option names of webpack resolve configs', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write('node_modules/preact/compat/index.js', '');
+  write('node_modules/react/index.js', '');
+  write('web_modules/widget/index.js', '');
+  write('src/components/Button.ts', 'export {}');
+  const file = path.join(dir, 'src/index.ts');
+  const options = {
+    project: [path.join(dir, 'tsconfig.json')],
+    alias: { react$: 'preact/compat', '@components': path.join(dir, 'src/components'), canvas: false },
+    modules: ['web_modules', 'node_modules'],
+  };
+  t.like(resolve('react', file, options), { found: true, path: path.join(dir, 'node_modules/preact/compat/index.js') });
+  t.like(resolve('react/index', file, options), { found: true, path: path.join(dir, 'node_modules/react/index.js') });
+  t.like(resolve('@components/Button', file, options), {
+    found: true,
+    path: path.join(dir, 'src/components/Button.ts'),
+  });
+  t.like(resolve('canvas', file, options), { found: true, path: '' });
+  t.like(resolve('widget', file, options), { found: true, path: path.join(dir, 'web_modules/widget/index.js') });
+  t.like(resolve('widget', file, { ...options, modules: undefined }), { found: false });
+
+  const alias = [{ name: 'react', alias: ['missing', 'preact/compat'], onlyModule: true }];
+  t.like(resolve('react', file, { ...options, alias }), {
+    found: true,
+    path: path.join(dir, 'node_modules/preact/compat/index.js'),
+  });
+  t.true(dumpConfig({ symlinks: false }).preserveSymlinks);
+  t.deepEqual(dumpConfig({ modules: ['web_modules'] }).modules, ['web_modules']);
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('apply Vite aliases with string and RegExp finds before tsconfig paths', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths: { '@/*': ['./legacy/*'] } } }));
+  write('src/utils/date.ts', 'export {}');
+  write('legacy/utils/date.ts', 'export {}');
+  write('src/icons/arrow.svg', '');
+  write('node_modules/vue/dist/vue.esm-bundler.js', '');
+  const file = path.join(dir, 'src/main.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const alias = [
+    { find: /^@\/(.*)$/, replacement: `${dir}/src/$1` },
+    { find: /^~icons\/(?<name>.+)$/i, replacement: `${dir}/src/icons/$<name>.svg` },
+    { find: 'vue', replacement: 'vue/dist/vue.esm-bundler.js' },
+  ];
+  t.like(resolve('@/utils/date', file, { project, alias }), { found: true, path: path.join(dir, 'src/utils/date.ts') });
+  t.like(resolve('@/utils/date', file, { project }), { found: true, path: path.join(dir, 'legacy/utils/date.ts') });
+  t.like(resolve('~Icons/arrow', file, { project, alias }), {
+    found: true,
+    path: path.join(dir, 'src/icons/arrow.svg'),
+  });
+  t.like(resolve('vue', file, { project, alias }), {
+    found: true,
+    path: path.join(dir, 'node_modules/vue/dist/vue.esm-bundler.js'),
+  });
+  t.like(resolve('vue-router', file, { project, alias }), { found: false });
+  t.throws(() => resolve('@/utils/date', file, { project, alias: [{ find: /(?<=@)x/, replacement: '' }] }), {
+    message: /Invalid regular expression "\(\?<=@\)x" in `alias`/,
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('apply exact and prefix aliases before tsconfig paths', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  const paths = { vue: ['./shims/vue.ts'], '@app/*': ['./legacy/*'] };
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths } }));
+  write('shims/vue.ts', 'export {}');
+  write('src/utils/date.ts', 'export {}');
+  write('legacy/utils/date.ts', 'export {}');
+  write('node_modules/vue/dist/vue.esm-bundler.js', '');
+  write('node_modules/vue/dist/vue.runtime.js', '');
+  const file = path.join(dir, 'src/main.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const alias = { vue$: 'vue/dist/vue.esm-bundler.js', '@app': path.join(dir, 'src') };
+  t.like(resolve('vue', file, { project, alias }), {
+    found: true,
+    path: path.join(dir, 'node_modules/vue/dist/vue.esm-bundler.js'),
+  });
+  t.like(resolve('vue', file, { project }), { found: true, path: path.join(dir, 'shims/vue.ts') });
+  t.like(resolve('vue/dist/vue.runtime', file, { project, alias }), {
+    found: true,
+    path: path.join(dir, 'node_modules/vue/dist/vue.runtime.js'),
+  });
+  t.like(resolve('@app/utils/date', file, { project, alias }), {
+    found: true,
+    path: path.join(dir, 'src/utils/date.ts'),
+  });
+  t.like(resolve('@app/utils/date', file, { project }), { found: true, path: path.join(dir, 'legacy/utils/date.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve workspace siblings that are not linked into node_modules', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('package.json', JSON.stringify({ private: true, workspaces: ['packages/*'] }));
+  write('packages/ui/package.json', JSON.stringify({ name: '@org/ui', main: './src/index.ts' }));
+  write('packages/ui/src/index.ts', 'export {}');
+  write('packages/ui/src/button.ts', 'export {}');
+  write('packages/utils/package.json', JSON.stringify({ name: 'utils', exports: { '.': './lib/main.ts' } }));
+  write('packages/utils/lib/main.ts', 'export {}');
+  write('packages/app/src/index.ts', 'export {}');
+  const file = path.join(dir, 'packages/app/src/index.ts');
+  t.like(resolve('@org/ui', file), { found: true, path: path.join(dir, 'packages/ui/src/index.ts') });
+  t.like(resolve('@org/ui/src/button', file), { found: true, path: path.join(dir, 'packages/ui/src/button.ts') });
+  t.like(resolve('utils', file), { found: true, path: path.join(dir, 'packages/utils/lib/main.ts') });
+  t.like(resolve('@org/missing', file), { found: false });
+
+  fs.rmSync(path.join(dir, 'package.json'));
+  write('pnpm-workspace.yaml', "packages:\n  - 'packages/*'\n");
+  clearCache();
+  t.like(resolve('utils', file), { found: true, path: path.join(dir, 'packages/utils/lib/main.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve workspace: dependencies from the workspace, not node_modules', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('pnpm-workspace.yaml', "packages:\n  - 'packages/*'\n");
+  write('packages/ui/package.json', JSON.stringify({ name: '@org/ui', version: '0.0.0', main: './src/index.ts' }));
+  write('packages/ui/src/index.ts', 'export {}');
+  write('packages/app/package.json', JSON.stringify({ name: 'app', dependencies: { '@org/ui': 'workspace:*' } }));
+  write('packages/app/node_modules/@org/ui/package.json', JSON.stringify({ name: '@org/ui', main: 'index.js' }));
+  write('packages/app/node_modules/@org/ui/index.js', 'module.exports = {}');
+  const file = path.join(dir, 'packages/app/src/index.ts');
+  t.like(resolve('@org/ui', file, { metadata: true }), {
+    found: true,
+    path: path.join(dir, 'packages/ui/src/index.ts'),
+    packageName: '@org/ui',
+    isExternal: false,
+    isWorkspacePackage: true,
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('alias workspace packages to the sources their manifests point to when asked', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('package.json', JSON.stringify({ private: true, workspaces: ['packages/*'] }));
+  write('packages/ui/package.json', JSON.stringify({ name: '@org/ui', main: 'dist/index.js', source: 'src/index.ts' }));
+  write('packages/ui/src/index.ts', 'export {}');
+  write('packages/ui/src/button.ts', 'export {}');
+  write('node_modules/@org/ui/package.json', JSON.stringify({ name: '@org/ui', main: 'dist/index.js' }));
+  write('node_modules/@org/ui/dist/index.js', '');
+  write('tsconfig.json', '{}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'packages/app/src/index.ts');
+  const options = { project, workspaceAliases: true, metadata: true };
+  t.like(resolve('@org/ui', file, options), {
+    found: true,
+    path: path.join(dir, 'packages/ui/src/index.ts'),
+    matchedPattern: '@org/ui',
+  });
+  t.like(resolve('@org/ui/button', file, options), {
+    found: true,
+    path: path.join(dir, 'packages/ui/src/button.ts'),
+    matchedPattern: '@org/ui/*',
+  });
+  t.like(resolve('@org/ui', file, { project }), {
+    found: true,
+    path: path.join(dir, 'node_modules/@org/ui/dist/index.js'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('fail imports of undeclared dependencies with strictDependencies', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('package.json', JSON.stringify({ name: 'app', dependencies: { react: '*' }, devDependencies: { vitest: '*' } }));
+  write('src/index.ts', 'export {}');
+  for (const name of ['react', 'vitest', 'lodash']) {
+    write(`node_modules/${name}/package.json`, JSON.stringify({ name, main: 'index.js' }));
+    write(`node_modules/${name}/index.js`, '');
+  }
+  write('tsconfig.json', '{}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'src/index.ts');
+  const options = { project, strictDependencies: true };
+  t.like(resolve('react', file, options), { found: true, path: path.join(dir, 'node_modules/react/index.js') });
+  t.like(resolve('vitest', file, options), { found: true });
+  t.like(resolve('lodash', file, options), { found: false, reason: 'UndeclaredDependency' });
+  t.like(resolve('fs', file, options), { found: true, path: '' });
+  t.like(resolve('vitest', file, { ...options, dependencyFields: ['dependencies'] }), {
+    found: false,
+    reason: 'UndeclaredDependency',
+  });
+  t.like(resolve('lodash', file, { project }), { found: true });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve fallback targets only after everything else failed', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('src/index.ts', 'export {}');
+  write('shims/crypto.ts', 'export {}');
+  write('shims/optional.ts', 'export {}');
+  write('node_modules/optional/package.json', JSON.stringify({ name: 'optional', main: 'index.js' }));
+  write('node_modules/optional/index.js', '');
+  write('tsconfig.json', '{}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'src/index.ts');
+  const fallback = {
+    'crypto-browserify': path.join(dir, 'shims/crypto'),
+    optional: path.join(dir, 'shims/optional'),
+    canvas: false,
+  };
+  t.like(resolve('crypto-browserify', file, { project, fallback }), {
+    found: true,
+    path: path.join(dir, 'shims/crypto.ts'),
+    isFallback: true,
+  });
+  t.deepEqual(resolve('optional', file, { project, fallback }), {
+    found: true,
+    path: path.join(dir, 'node_modules/optional/index.js'),
+  });
+  t.like(resolve('canvas', file, { project, fallback }), { found: true, path: '', isFallback: true });
+  t.false(resolve('canvas', file, { project }).found);
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('try bare specifiers as relative ones first with preferRelative', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('src/utils.ts', 'export {}');
+  write('src/index.ts', 'export {}');
+  write('node_modules/utils/package.json', JSON.stringify({ name: 'utils', main: 'index.js' }));
+  write('node_modules/utils/index.js', '');
+  write('tsconfig.json', '{}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'src/index.ts');
+  t.like(resolve('utils', file, { project, preferRelative: true }), {
+    found: true,
+    path: path.join(dir, 'src/utils.ts'),
+  });
+  t.like(resolve('utils', file, { project }), { found: true, path: path.join(dir, 'node_modules/utils/index.js') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('require extensions of local files with fullySpecified', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('src/a.ts', 'export {}');
+  write('src/b.mts', 'export {}');
+  write('src/dir/index.ts', 'export {}');
+  write('src/index.ts', 'export {}');
+  write('tsconfig.json', '{}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'src/index.ts');
+  const options = { project, fullySpecified: true };
+  t.like(resolve('./a.js', file, options), { found: true, path: path.join(dir, 'src/a.ts') });
+  t.like(resolve('./a.ts', file, options), { found: true, path: path.join(dir, 'src/a.ts') });
+  t.like(resolve('./b.mjs', file, options), { found: true, path: path.join(dir, 'src/b.mts') });
+  t.like(resolve('./a', file, options), { found: false, reason: 'ExtensionMissing' });
+  t.like(resolve('./dir', file, options), { found: false });
+  t.like(resolve('./a', file, { project }), { found: true, path: path.join(dir, 'src/a.ts') });
+  t.like(resolve('./a.js', file, { project }), { found: true, path: path.join(dir, 'src/a.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('map ~/* and @/* to the source root with srcAlias', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('src/utils.ts', 'export {}');
+  write('app/utils.ts', 'export {}');
+  write('src/index.ts', 'export {}');
+  write('tsconfig.json', '{}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'src/index.ts');
+  t.like(resolve('@/utils', file, { project, srcAlias: true, metadata: true }), {
+    found: true,
+    path: path.join(dir, 'src/utils.ts'),
+    matchedPattern: '@/*',
+  });
+  t.like(resolve('~/utils', file, { project, srcAlias: 'app' }), { found: true, path: path.join(dir, 'app/utils.ts') });
+  t.false(resolve('~/utils', file, { project }).found);
+  // Declared `paths` win
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths: { '@/*': ['app/*'] } } }));
+  clearCache();
+  t.like(resolve('@/utils', file, { project, srcAlias: true }), { found: true, path: path.join(dir, 'app/utils.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('report injected copies of workspace packages as their sources when asked', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('pnpm-workspace.yaml', "packages:\n  - 'packages/*'\n");
+  write('packages/ui/package.json', JSON.stringify({ name: '@org/ui', main: 'index.js' }));
+  write('packages/ui/index.js', 'module.exports = {}');
+  const injected = 'node_modules/.pnpm/file+packages+ui/node_modules/@org/ui';
+  fs.mkdirSync(path.join(dir, injected), { recursive: true });
+  for (const file of ['package.json', 'index.js']) {
+    fs.linkSync(path.join(dir, 'packages/ui', file), path.join(dir, injected, file));
+  }
+  write('packages/app/tsconfig.json', '{}');
+  fs.mkdirSync(path.join(dir, 'packages/app/node_modules/@org'), { recursive: true });
+  fs.symlinkSync(path.join(dir, injected), path.join(dir, 'packages/app/node_modules/@org/ui'), 'dir');
+  const project = [path.join(dir, 'packages/app/tsconfig.json')];
+  const file = path.join(dir, 'packages/app/src/index.ts');
+  t.like(resolve('@org/ui', file, { project }), { found: true, path: path.join(dir, injected, 'index.js') });
+  t.like(resolve('@org/ui', file, { project, dedupeInjectedPackages: true }), {
+    found: true,
+    path: path.join(dir, 'packages/ui/index.js'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve outputs of referenced projects to their sources when asked', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('core/tsconfig.json', JSON.stringify({ compilerOptions: { composite: true, rootDir: 'src', outDir: 'dist' } }));
+  write('core/src/util/foo.ts', 'export {}');
+  write('core/dist/util/foo.d.ts', 'export {}');
+  write('app/tsconfig.json', JSON.stringify({ references: [{ path: '../core' }] }));
+  write('app/index.ts', 'export {}');
+  const project = [path.join(dir, 'app/tsconfig.json')];
+  const file = path.join(dir, 'app/index.ts');
+  t.like(resolve('../core/dist/util/foo', file, { project }), {
+    found: true,
+    path: path.join(dir, 'core/dist/util/foo.d.ts'),
+  });
+  t.like(resolve('../core/dist/util/foo', file, { project, useSourceOfProjectReferences: true }), {
+    found: true,
+    path: path.join(dir, 'core/src/util/foo.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve aliases of a shared tsconfig.base.json from the projects extending it', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  const paths = { '@org/lib': ['libs/lib/src/index.ts'], '@org/lib/*': ['libs/lib/src/*'] };
+  write('tsconfig.base.json', JSON.stringify({ compilerOptions: { baseUrl: '.', paths } }));
+  write('apps/web/tsconfig.json', JSON.stringify({ extends: '../../tsconfig.base.json' }));
+  write('apps/api/tsconfig.json', JSON.stringify({ extends: '../../tsconfig.base.json' }));
+  write('libs/lib/src/index.ts', 'export {}');
+  write('libs/lib/src/util.ts', 'export {}');
+  for (const app of ['web', 'api']) {
+    const project = [path.join(dir, 'apps', app, 'tsconfig.json')];
+    const file = path.join(dir, 'apps', app, 'src/main.ts');
+    t.like(resolve('@org/lib', file, { project }), { found: true, path: path.join(dir, 'libs/lib/src/index.ts') });
+    t.like(resolve('@org/lib/util', file, { project }), { found: true, path: path.join(dir, 'libs/lib/src/util.ts') });
+    t.is(getEffectivePaths(project[0]).baseUrl, dir);
+  }
+
+  // Without `baseUrl`, targets are relative to the config setting `paths`
+  write('tsconfig.base.json', JSON.stringify({ compilerOptions: { paths } }));
+  bumpGeneration();
+  const project = [path.join(dir, 'apps/web/tsconfig.json')];
+  t.like(resolve('@org/lib', path.join(dir, 'apps/web/src/main.ts'), { project }), {
+    found: true,
+    path: path.join(dir, 'libs/lib/src/index.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('layer the nearest package tsconfig over the root config', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths: { '@/*': ['shared/*'], '#shared/*': ['shared/*'] } } }));
+  write('shared/util.ts', 'export {}');
+  write('shared/button.ts', 'export {}');
+  write('packages/ui/tsconfig.json', JSON.stringify({ compilerOptions: { paths: { '@/*': ['./src/*'] } } }));
+  write('packages/ui/src/util.ts', 'export {}');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const file = path.join(dir, 'packages/ui/src/index.ts');
+  t.like(resolve('@/util', file, { project }), { found: true, path: path.join(dir, 'shared/util.ts') });
+  const options = { project, nearestTsconfigFirst: true };
+  t.like(resolve('@/util', file, options), { found: true, path: path.join(dir, 'packages/ui/src/util.ts') });
+  t.like(resolve('@/button', file, options), { found: true, path: path.join(dir, 'shared/button.ts') });
+  t.like(resolve('#shared/button', file, options), { found: true, path: path.join(dir, 'shared/button.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve from the node_modules and tsconfig.json of each root', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  const roots = [path.join(dir, 'app'), path.join(dir, 'vendor/lib')];
+  for (const root of ['app', 'vendor/lib']) {
+    write(`${root}/tsconfig.json`, JSON.stringify({ compilerOptions: { paths: { '~/*': ['./src/*'] } } }));
+    write(`${root}/src/util.ts`, 'export {}');
+    write(`${root}/node_modules/dep/package.json`, JSON.stringify({ name: 'dep', main: 'index.js' }));
+    write(`${root}/node_modules/dep/index.js`, '');
+  }
+  for (const root of roots) {
+    const file = path.join(root, 'src/index.ts');
+    t.like(resolve('~/util', file, { roots }), { found: true, path: path.join(root, 'src/util.ts') });
+    t.like(resolve('dep', file, { roots }), { found: true, path: path.join(root, 'node_modules/dep/index.js') });
+    t.is(getProjectInfo(file, { roots }).project, path.join(root, 'tsconfig.json'));
+  }
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('reuse resolutions until the generation is bumped', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const file = path.join(dir, 'index.ts');
+  fs.writeFileSync(path.join(dir, 'foo.ts'), 'export default 1');
+  t.like(resolve('./foo', file), { found: true, path: path.join(dir, 'foo.ts') });
+
+  fs.renameSync(path.join(dir, 'foo.ts'), path.join(dir, 'foo.tsx'));
+  t.like(resolve('./foo', file), { found: true, path: path.join(dir, 'foo.ts') });
+  const generation = bumpGeneration();
+  t.is(bumpGeneration(), generation + 1);
+  t.like(resolve('./foo', file), { found: true, path: path.join(dir, 'foo.tsx') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('reuse file checks until they expire or are invalidated', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const file = path.join(dir, 'index.ts');
+  t.false(resolve('./foo', file).found);
+
+  fs.writeFileSync(path.join(dir, 'foo.ts'), 'export default 1');
+  bumpGeneration();
+  t.like(resolve('./foo', file), { found: true, path: path.join(dir, 'foo.ts') });
+  const before = getCacheStats().fileStats;
+  bumpGeneration();
+  setStatCacheTtl(60000);
+  resolve('./foo', file);
+  // From another dir, so the resolution itself isn't reused
+  resolve('../foo', path.join(dir, 'sub/other.ts'));
+  t.true(getCacheStats().fileStats.hits > before.hits);
+
+  fs.renameSync(path.join(dir, 'foo.ts'), path.join(dir, 'foo.tsx'));
+  invalidate(path.join(dir, 'foo.ts'));
+  invalidate(path.join(dir, 'foo.tsx'));
+  t.like(resolve('./foo', file), { found: true, path: path.join(dir, 'foo.tsx') });
+  setStatCacheTtl(1000);
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('rule out missing extensions from one dir listing', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const file = path.join(dir, 'index.ts');
+  const options = { extensionOrderPreset: 'typescript-first' };
+  fs.writeFileSync(path.join(dir, 'foo.js'), 'export default 1');
+  const before = getCacheStats().fileStats;
+  t.like(resolve('./foo', file, options), { found: true, path: path.join(dir, 'foo.js') });
+  t.true(getCacheStats().fileStats.misses - before.misses < 3);
+
+  fs.writeFileSync(path.join(dir, 'foo.ts'), 'export default 1');
+  invalidate(path.join(dir, 'foo.ts'));
+  t.like(resolve('./foo', file, options), { found: true, path: path.join(dir, 'foo.ts') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('parse each package.json once while it is unchanged', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withExports', part));
+  const project = [fixture('tsconfig.json')];
+  resolve('cond-pkg', fixture('index.ts'), { project });
+  const before = getCacheStats().packageJsons;
+  t.like(resolve('cond-pkg/feature', fixture('index.ts'), { project }), {
+    found: true,
+    path: fixture('node_modules/cond-pkg/feature.mjs'),
+  });
+  t.true(getCacheStats().packageJsons.hits > before.hits);
+});
+
+test('remember unresolvable specifiers until the generation is bumped', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const file = path.join(dir, 'index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.writeFileSync(project[0], '{}');
+  t.like(resolve('missing-pkg', file, { project }), { found: false, reason: 'NotFound' });
+  const before = getCacheStats().resolutions;
+  t.like(resolve('missing-pkg', file, { project }), { found: false, reason: 'NotFound' });
+  t.is(getCacheStats().resolutions.hits, before.hits + 1);
+
+  fs.mkdirSync(path.join(dir, 'node_modules/missing-pkg'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'node_modules/missing-pkg/index.d.ts'), 'export {}');
+  bumpGeneration();
+  t.like(resolve('missing-pkg', file, { project }), {
+    found: true,
+    path: path.join(dir, 'node_modules/missing-pkg/index.d.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('notice packages added to an indexed node_modules dir once invalidated', async (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const file = path.join(dir, 'src/index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.mkdirSync(path.join(dir, 'node_modules/@scope/present'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'node_modules/@scope/present/index.d.ts'), 'export {}');
+  fs.writeFileSync(path.join(dir, 'node_modules/single.d.ts'), 'export {}');
+  fs.writeFileSync(project[0], '{}');
+  await warm(project[0]);
+  t.like(resolve('@scope/present', file, { project }), {
+    found: true,
+    path: path.join(dir, 'node_modules/@scope/present/index.d.ts'),
+  });
+  t.like(resolve('single', file, { project }), { found: true, path: path.join(dir, 'node_modules/single.d.ts') });
+  t.like(resolve('@scope/added', file, { project }), { found: false });
+
+  fs.mkdirSync(path.join(dir, 'node_modules/@scope/added'));
+  fs.writeFileSync(path.join(dir, 'node_modules/@scope/added/index.d.ts'), 'export {}');
+  invalidate(path.join(dir, 'node_modules/@scope/added'));
+  t.like(resolve('@scope/added', file, { project }), {
+    found: true,
+    path: path.join(dir, 'node_modules/@scope/added/index.d.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve a batch of imports in order', (t) => {
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const project = [path.join(__dirname, '../fixtures/withPaths/tsconfig.json')];
+  const requests = ['folder/tsImportee', './missing', 'folder/tsImportee'].map((source) => ({ source, file }));
+  const results = resolveBatch(requests, { project });
+  t.deepEqual(
+    results.map((result) => result.found),
+    [true, false, true],
+  );
+  t.deepEqual(results[0], resolve('folder/tsImportee', file, { project }));
+  t.deepEqual(new Resolver({ project }).resolveBatch(requests), results);
+});
+
+test('build the import graph of files and packages from entry globs', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write('package.json', JSON.stringify({ private: true, workspaces: ['packages/*'] }));
+  write('packages/app/package.json', JSON.stringify({ name: 'app' }));
+  write('packages/app/src/index.ts', "import { a } from './a';\nimport fs from 'fs';\nimport('./missing');\n");
+  write('packages/app/src/a.ts', "export * from 'ui';\nexport const a = require('dep');\n");
+  write('packages/ui/package.json', JSON.stringify({ name: 'ui', main: 'index.ts' }));
+  write('packages/ui/index.ts', 'export {}');
+  write('node_modules/dep/package.json', JSON.stringify({ name: 'dep', main: 'index.js' }));
+  write('node_modules/dep/index.js', "require('./not-scanned');");
+  const file = (name) => path.join(dir, name);
+  const entryGlobs = [path.relative(process.cwd(), file('packages/app/src/*.ts'))];
+  const graph = JSON.parse(buildImportGraph(entryGlobs, { project: [file('tsconfig.json')] }));
+  t.deepEqual(graph, {
+    files: {
+      [file('packages/app/src/a.ts')]: [file('node_modules/dep/index.js'), file('packages/ui/index.ts')],
+      [file('packages/app/src/index.ts')]: [file('packages/app/src/a.ts')],
+      [file('packages/ui/index.ts')]: [],
+    },
+    packages: { app: ['dep', 'ui'], ui: [] },
+    unresolved: { [file('packages/app/src/index.ts')]: ['./missing'] },
+  });
+  t.throws(() => buildImportGraph(['src/[index.ts'], {}), { message: /Invalid entry pattern/ });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('leave the core modules of the runtime out of the import graph', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.writeFileSync(path.join(dir, 'tsconfig.json'), '{}');
+  fs.writeFileSync(path.join(dir, 'index.ts'), "import { Database } from 'bun:sqlite';\nimport fs from 'fs';\n");
+  const options = { project: ['tsconfig.json'], cwd: dir, runtime: 'bun' };
+  const graph = JSON.parse(buildImportGraph(['index.ts'], options));
+  t.deepEqual(graph.files, { [path.join(dir, 'index.ts')]: [] });
+  t.deepEqual(graph.unresolved, {});
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('persist resolutions to a cache dir and restore them', (t) => {
+  const cacheDir = fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-cache-'));
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const project = [path.join(__dirname, '../fixtures/withPaths/tsconfig.json')];
+  const expected = resolve('folder/tsImportee', file, { project });
+  t.is(setCacheDir(cacheDir), 0);
+  saveCache();
+  t.is(fs.readdirSync(cacheDir).filter((name) => name.endsWith('.json')).length, 1);
+
+  bumpGeneration();
+  t.true(setCacheDir(cacheDir) > 0);
+  const before = getCacheStats().resolutions;
+  t.deepEqual(resolve('folder/tsImportee', file, { project }), expected);
+  t.is(getCacheStats().resolutions.hits, before.hits + 1);
+});
+
+test('take relative projects, cache dirs and manifests from cwd', async (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.mkdirSync(path.join(dir, 'src'));
+  fs.writeFileSync(path.join(dir, 'src/foo.ts'), 'export default 1');
+  fs.writeFileSync(path.join(dir, 'tsconfig.json'), JSON.stringify({ compilerOptions: { baseUrl: 'src' } }));
+  const before = getCacheStats().tsconfigs.size;
+  await warm('tsconfig.json', false, { cwd: dir });
+  t.true(getCacheStats().tsconfigs.size > before);
+  t.like(getEffectivePaths('tsconfig.json', { cwd: dir }), {
+    project: path.join(dir, 'tsconfig.json'),
+    baseUrl: path.join(dir, 'src'),
+  });
+
+  bumpGeneration();
+  t.like(resolve('foo', path.join(dir, 'src/index.ts'), { project: ['tsconfig.json'], cwd: dir }), { found: true });
+  t.true(writeManifest('manifest.json', dir) > 0);
+  t.true(fs.existsSync(path.join(dir, 'manifest.json')));
+  t.true(loadManifest('manifest.json', dir) > 0);
+  unloadManifest();
+
+  t.is(setCacheDir('cache', dir), 0);
+  saveCache(dir);
+  t.is(fs.readdirSync(path.join(dir, 'cache')).filter((name) => name.endsWith('.json')).length, 1);
+  bumpGeneration();
+  t.true(setCacheDir('cache', dir) > 0);
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve to just the path, empty when not found', (t) => {
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const project = [path.join(__dirname, '../fixtures/withPaths/tsconfig.json')];
+  const { path: expected } = resolve('folder/tsImportee', file, { project });
+  t.is(resolvePath('folder/tsImportee', file, { project }), expected);
+  t.is(resolvePath('./missing', file, { project }), '');
+  const resolver = new Resolver({ project });
+  t.is(resolver.resolvePath('folder/tsImportee', file), expected);
+  t.is(resolver.resolvePath('./missing', file), '');
+});
+
+test('answer from a resolution manifest wherever the checkout moved', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  fs.mkdirSync(path.join(dir, 'first/src'), { recursive: true });
+  fs.writeFileSync(path.join(dir, 'first/src/foo.ts'), 'export default 1');
+  bumpGeneration();
+  t.like(resolve('./foo', path.join(dir, 'first/src/index.ts')), { found: true });
+  t.true(writeManifest(path.join(dir, 'first/manifest.json')) > 0);
+
+  fs.renameSync(path.join(dir, 'first'), path.join(dir, 'second'));
+  fs.rmSync(path.join(dir, 'second/src/foo.ts'));
+  t.true(loadManifest(path.join(dir, 'second/manifest.json')) > 0);
+  t.like(resolve('./foo', path.join(dir, 'second/src/index.ts')), {
+    found: true,
+    path: path.join(dir, 'second/src/foo.ts'),
+  });
+  unloadManifest();
+  t.false(resolve('./foo', path.join(dir, 'second/src/index.ts')).found);
+  t.throws(() => loadManifest(path.join(dir, 'missing.json')), { message: /Cannot read resolution manifest/ });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve symlinked packages to their real paths', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const store = path.join(dir, 'node_modules/.pnpm/linked@1.0.0/node_modules/linked');
+  fs.mkdirSync(store, { recursive: true });
+  fs.writeFileSync(path.join(store, 'index.d.ts'), 'export {}');
+  fs.writeFileSync(path.join(store, 'other.d.ts'), 'export {}');
+  fs.symlinkSync(store, path.join(dir, 'node_modules/linked'), 'dir');
+  const file = path.join(dir, 'index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.writeFileSync(project[0], '{}');
+  t.like(resolve('linked', file, { project }), { found: true, path: path.join(store, 'index.d.ts') });
+  t.like(resolve('linked/other', file, { project }), { found: true, path: path.join(store, 'other.d.ts') });
+  t.like(resolve('linked', file, { project, preserveSymlinks: true }), {
+    found: true,
+    path: path.join(dir, 'node_modules/linked/index.d.ts'),
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve dependencies of pnpm peer variants next to their real location', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const pnpm = path.join(dir, 'node_modules/.pnpm');
+  const addPackage = (id, name) => {
+    const pkgDir = path.join(pnpm, id, 'node_modules', name);
+    fs.mkdirSync(pkgDir, { recursive: true });
+    fs.writeFileSync(path.join(pkgDir, 'package.json'), JSON.stringify({ name, version: id.split('@')[1] }));
+    fs.writeFileSync(path.join(pkgDir, 'index.js'), 'module.exports = {}');
+    return pkgDir;
+  };
+  const react17 = addPackage('react@17.0.2', 'react');
+  const react18 = addPackage('react@18.2.0', 'react');
+  const foo = addPackage('foo@1.0.0_react@18.2.0', 'foo');
+  fs.symlinkSync(react18, path.join(pnpm, 'foo@1.0.0_react@18.2.0/node_modules/react'), 'dir');
+  // The app itself depends on react 17 and on foo, with react 18 as its peer
+  fs.symlinkSync(react17, path.join(dir, 'node_modules/react'), 'dir');
+  fs.symlinkSync(foo, path.join(dir, 'node_modules/foo'), 'dir');
+  const project = [path.join(dir, 'tsconfig.json')];
+  fs.writeFileSync(project[0], '{}');
+
+  t.like(resolve('foo', path.join(dir, 'index.ts'), { project }), { found: true, path: path.join(foo, 'index.js') });
+  for (const file of [path.join(foo, 'index.js'), path.join(dir, 'node_modules/foo/index.js')]) {
+    t.like(resolve('react', file, { project }), { found: true, path: path.join(react18, 'index.js') });
+  }
+  t.like(resolve('react', path.join(dir, 'index.ts'), { project }), { found: true, path: path.join(react17, 'index.js') });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve requests of webpack as a resolver plugin', async (t) => {
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const hooks = {};
+  const forwarded = [];
+  const resolver = {
+    ensureHook: (name) => name,
+    getHook: (name) => ({ tapAsync: (plugin, tap) => (hooks[name] = tap) }),
+    doResolve: (hook, request, message, resolveContext, callback) => {
+      forwarded.push({ hook, path: request.path, query: request.query });
+      callback(null, request);
+    },
+  };
+  new ResolverPlugin({ project: ['tsconfig.json'] }).apply(resolver);
+  const run = (request) =>
+    new Promise((resolve) => hooks['described-resolve'](request, {}, (...args) => resolve(args)));
+
+  await run({ request: './tsImportee', query: '?raw', context: { issuer: file } });
+  t.deepEqual(forwarded, [
+    { hook: 'resolved', path: path.join(__dirname, '../fixtures/withoutPaths/tsImportee.ts'), query: '?raw' },
+  ]);
+  t.deepEqual(await run({ request: './missing', context: { issuer: file } }), []);
+  t.deepEqual(await run({ request: './tsImportee', context: {} }), []);
+  t.is(forwarded.length, 1);
+});
+
+test('resolve from worker threads alongside the main thread', async (t) => {
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const project = [path.join(__dirname, '../fixtures/withPaths/tsconfig.json')];
+  const expected = resolve('folder/tsImportee', file, { project });
+  const code = `
+    const { parentPort, workerData } = require('worker_threads');
+    const { resolve } = require(workerData.index);
+    parentPort.postMessage(resolve('folder/tsImportee', workerData.file, { project: workerData.project }));
+  `;
+  const worker = new Worker(code, { eval: true, workerData: { index: path.join(__dirname, '../index.js'), file, project } });
+  const results = await Promise.all([
+    new Promise((resolveMessage, reject) => worker.once('message', resolveMessage).once('error', reject)),
+    resolveAsync('folder/tsImportee', file, { project }),
+  ]);
+  t.deepEqual(results, [expected, expected]);
+  await worker.terminate();
+});
+
+test('report where resolution time goes', (t) => {
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const project = [path.join(__dirname, '../fixtures/withPaths/tsconfig.json')];
+  const { timing, ...resolved } = resolve('folder/tsImportee', file, { project, timing: true });
+  t.deepEqual(resolved, resolve('folder/tsImportee', file, { project }));
+  for (const phase of ['totalMs', 'tsconfigMs', 'pathsMs', 'nodeModulesMs', 'statMs']) {
+    t.true(timing[phase] >= 0);
+  }
+  t.true(timing.totalMs >= timing.tsconfigMs);
+  t.is(resolve('folder/tsImportee', file, { project }).timing, undefined);
+});
+
+test('count file checks and package.json reads', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write('src/a.tsx', 'export {}');
+  write('node_modules/pkg/package.json', JSON.stringify({ name: 'pkg', main: 'lib/main' }));
+  write('node_modules/pkg/lib/main.js', '');
+  const file = path.join(dir, 'src/index.ts');
+  const options = { project: [path.join(dir, 'tsconfig.json')], probeCounts: true };
+
+  const relative = resolve('./a', file, options);
+  t.is(relative.path, path.join(dir, 'src/a.tsx'));
+  t.true(relative.probeCounts.fileChecks > 1);
+  t.is(relative.probeCounts.packageJsonReads, 0);
+  const { probeCounts } = resolve('pkg', file, options);
+  t.true(probeCounts.fileChecks > 1);
+  t.true(probeCounts.packageJsonReads >= 1);
+  // Reused results take no checks
+  t.deepEqual(resolve('pkg', file, options).probeCounts, { fileChecks: 0, packageJsonReads: 0 });
+  t.is(resolve('pkg', file, { project: options.project }).probeCounts, undefined);
+});
+
+test('resolve builtins, conditions, and main fields of the runtime', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write('node_modules/path/package.json', JSON.stringify({ name: 'path', main: 'index.js' }));
+  write('node_modules/path/index.js', '');
+  write(
+    'node_modules/env/package.json',
+    JSON.stringify({ name: 'env', exports: { workerd: './worker.js', node: './node.js', default: './index.js' } }),
+  );
+  write('node_modules/env/worker.js', '');
+  write('node_modules/env/node.js', '');
+  write('node_modules/env/index.js', '');
+  write('node_modules/dom/package.json', JSON.stringify({ name: 'dom', main: 'main.js', browser: 'browser.js' }));
+  write('node_modules/dom/main.js', '');
+  write('node_modules/dom/browser.js', '');
+  const file = path.join(dir, 'src/index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+
+  t.like(resolve('bun:sqlite', file, { project, runtime: 'bun' }), { found: true, path: '' });
+  t.like(resolve('bun:sqlite', file, { project }), { found: false });
+  t.like(resolve('cloudflare:sockets', file, { project, runtime: 'workerd' }), { found: true, path: '' });
+  t.like(resolve('path', file, { project, runtime: 'browser' }), {
+    found: true,
+    path: path.join(dir, 'node_modules/path/index.js'),
+  });
+  t.like(resolve('path', file, { project, runtime: 'node' }), { found: true, path: '' });
+  t.like(resolve('env', file, { project, runtime: 'workerd' }), {
+    found: true,
+    path: path.join(dir, 'node_modules/env/worker.js'),
+  });
+  t.like(resolve('env', file, { project, runtime: 'node' }), {
+    found: true,
+    path: path.join(dir, 'node_modules/env/node.js'),
+  });
+  t.like(resolve('dom', file, { project, runtime: 'browser' }), {
+    found: true,
+    path: path.join(dir, 'node_modules/dom/browser.js'),
+  });
+  t.like(resolve('dom', file, { project, runtime: 'electron' }), {
+    found: true,
+    path: path.join(dir, 'node_modules/dom/main.js'),
+  });
+
+  t.true(isCoreModule('electron', 'electron'));
+  t.false(isCoreModule('fs', 'browser'));
+  t.deepEqual(dumpConfig({ runtime: 'bun' }).mainFields, ['types', 'typings', 'module', 'main']);
+  t.like(dumpConfig({ runtime: 'browser' }), {
+    runtime: 'browser',
+    conditionNames: ['types', 'browser', 'import', 'module', 'require'],
+  });
+  t.is(dumpConfig({}).runtime, 'node');
+  t.throws(() => resolve('fs', file, { project, runtime: 'deno' }), { message: /runtime/ });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve Deno npm: specifiers and import map entries', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write(
+    'deno.json',
+    `{
+      // deno.json may have comments
+      "imports": { "@/": "./src/", "preact": "npm:preact@^10.19.0", "std/": "https://deno.land/std/" },
+      "scopes": { "./legacy/": { "@/": "./legacy/src/" } }
+    }`,
+  );
+  write('src/utils.ts', 'export {}');
+  write('legacy/src/utils.ts', 'export {}');
+  write('node_modules/preact/package.json', JSON.stringify({ name: 'preact', main: 'dist/preact.js' }));
+  write('node_modules/preact/dist/preact.js', '');
+  write('node_modules/preact/hooks/index.js', '');
+  const file = path.join(dir, 'src/main.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const importMap = path.join(dir, 'deno.json');
+
+  t.like(resolve('npm:preact@10.19.0/hooks', file, { project }), {
+    found: true,
+    path: path.join(dir, 'node_modules/preact/hooks/index.js'),
+  });
+  t.like(resolve('@/utils', file, { project, importMap }), { found: true, path: path.join(dir, 'src/utils.ts') });
+  t.like(resolve('@/utils', path.join(dir, 'legacy/main.ts'), { project, importMap }), {
+    found: true,
+    path: path.join(dir, 'legacy/src/utils.ts'),
+  });
+  t.like(resolve('preact', file, { project, importMap }), {
+    found: true,
+    path: path.join(dir, 'node_modules/preact/dist/preact.js'),
+  });
+  t.like(resolve('std/path/mod.ts', file, { project, importMap }), { found: false });
+  t.like(resolve('@/utils', file, { project }), { found: false });
+  t.throws(() => resolve('@/utils', file, { project, importMap: path.join(dir, 'import_map.json') }), {
+    message: /Import map/,
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('resolve with the condition set matching the importing file', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write(
+    'node_modules/dual/package.json',
+    JSON.stringify({ name: 'dual', exports: { node: './node.js', browser: './browser.js', default: './index.js' } }),
+  );
+  write('node_modules/dual/node.js', '');
+  write('node_modules/dual/browser.js', '');
+  write('node_modules/dual/index.js', '');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const options = {
+    project,
+    conditionSets: [
+      { files: [path.join(dir, 'src/main/**'), path.join(dir, 'src/*.main.ts')], conditionNames: ['electron', 'node'] },
+      { files: [path.join(dir, 'src/renderer/**'), path.join(dir, 'src/*.renderer.ts')], conditionNames: ['browser'] },
+    ],
+  };
+  const resolveFrom = (file) => resolve('dual', path.join(dir, file), options).path;
+
+  t.is(resolveFrom('src/main/index.ts'), path.join(dir, 'node_modules/dual/node.js'));
+  t.is(resolveFrom('src/renderer/app/index.ts'), path.join(dir, 'node_modules/dual/browser.js'));
+  t.is(resolveFrom('src/preload.main.ts'), path.join(dir, 'node_modules/dual/node.js'));
+  t.is(resolveFrom('src/preload.renderer.ts'), path.join(dir, 'node_modules/dual/browser.js'));
+  const conditionSets = [{ files: ['src/[main'], conditionNames: [] }];
+  t.throws(() => resolve('dual', path.join(dir, 'src/main.ts'), { project, conditionSets }), {
+    message: /Invalid file pattern/,
+  });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('suggest close specifiers when resolution fails', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths: { '@components/*': ['./src/components/*'] } } }));
+  write('src/utils.ts', 'export {}');
+  write('src/utilities/index.ts', 'export {}');
+  write('src/components/Button.tsx', 'export {}');
+  write('node_modules/lodash/package.json', JSON.stringify({ name: 'lodash', main: 'lodash.js' }));
+  write('node_modules/lodash/lodash.js', '');
+  write('node_modules/lodash/debounce.js', '');
+  write('node_modules/@tanstack/query/index.js', '');
+  const file = path.join(dir, 'src/index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const suggest = (source, options = {}) => resolve(source, file, { project, ...options }).suggestions;
+
+  t.deepEqual(suggest('./utlis'), ['./utils']);
+  t.deepEqual(suggest('./Utils'), ['./utils']);
+  t.deepEqual(suggest('lodahs'), ['lodash']);
+  t.deepEqual(suggest('lodash/debunce'), ['lodash/debounce']);
+  t.deepEqual(suggest('@tanstak/query'), ['@tanstack/query']);
+  t.deepEqual(suggest('@compnents/Button'), ['@components/Button']);
+  t.deepEqual(suggest('@ui/button', { alias: { '@uikit': './src/components' } }), []);
+  t.deepEqual(suggest('@uikt/Button', { alias: { '@uikit': './src/components' } }), ['@uikit/Button']);
+  t.deepEqual(suggest('./nothing-like-it'), []);
+  t.is(resolve('./utils', file, { project }).suggestions, undefined);
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('report configuration and package problems as warnings', async (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write('broken/tsconfig.json', '{ "compilerOptions": ');
+  write('node_modules/unreadable/package.json', '{ "main": ');
+  write('node_modules/unreadable/index.js', '');
+  write('node_modules/mixed/package.json', JSON.stringify({ exports: { '.': './index.js', import: './index.mjs' } }));
+  write('node_modules/mixed/index.js', '');
+  const file = path.join(dir, 'src/index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+
+  t.deepEqual(resolve('unreadable', file, { project }).warnings, [
+    `Unreadable ${path.join(dir, 'node_modules/unreadable/package.json')}: Json parse error`,
+  ]);
+  const mixed = path.join(dir, 'node_modules/mixed/package.json');
+  t.like(resolve('mixed', file, { project }), {
+    found: true,
+    warnings: [`Malformed "exports" in ${mixed}: subpaths and conditions are mixed`],
+  });
+  const broken = resolve('mixed', file, { project: [path.join(dir, 'broken/tsconfig.json'), ...project] });
+  t.regex(broken.warnings[0], /^Ignoring project .*broken\/tsconfig\.json: Invalid tsConfig/);
+  t.deepEqual(resolve('./missing', file, { project, conditionNames: ['types', 'sorce'] }).warnings, [
+    'Unknown condition name "sorce" in `conditionNames`',
+  ]);
+  t.is(resolve('mixed', file, { project, conditionNames: ['import'] }).warnings.length, 1);
+  t.is(resolve('./missing', file, { project }).warnings, undefined);
+
+  const event = new Promise((done) => {
+    setLogger((event) => {
+      if (event.kind === 'ConfigWarning') done(event);
+    });
+  });
+  resolve('./other', file, { project, conditionNames: ['sorce'] });
+  t.like(await event, {
+    kind: 'ConfigWarning',
+    path: file,
+    message: 'Unknown condition name "sorce" in `conditionNames`',
+  });
+  setLogger();
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('append a JSON record per resolution to the TS_RESOLVER_TRACE file', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const traceFile = path.join(dir, 'trace.jsonl');
+  const file = path.join(__dirname, '../fixtures/withoutPaths/index.ts');
+  const script = `
+    const { resolve } = require(${JSON.stringify(path.join(__dirname, '../index.js'))});
+    const file = ${JSON.stringify(file)};
+    console.log(JSON.stringify(resolve('./tsImportee', file, { project: ['tsconfig.json'] })));
+    resolve('./missing', file, { project: ['tsconfig.json'] });
+    resolve('./missing', file, { project: ['tsconfig.json'] });
+  `;
+  const env = { ...process.env, TS_RESOLVER_TRACE: traceFile };
+  const child = spawnSync(process.execPath, ['-e', script], { encoding: 'utf8', env });
+  t.is(child.status, 0);
+  t.is(JSON.parse(child.stdout).trace, undefined);
+
+  const records = fs.readFileSync(traceFile, 'utf8').trim().split('\n').map(JSON.parse);
+  t.is(records.length, 2);
+  t.like(records[0], {
+    pid: child.pid,
+    source: './tsImportee',
+    file,
+    projects: [path.resolve('tsconfig.json')],
+    found: true,
+    path: path.join(path.dirname(file), 'tsImportee.ts'),
+    reason: null,
+  });
+  t.true(records[0].steps.some((step) => step.startsWith('probe ')));
+  t.true(records[0].timing.totalMs >= records[0].timing.tsconfigMs);
+  t.like(records[1], { source: './missing', found: false, path: '', reason: 'NotFound' });
+  fs.rmSync(dir, { recursive: true });
+});
+
+test('log tracing spans and events to stderr per RUST_LOG', (t) => {
+  const file = path.join(__dirname, '../fixtures/withPaths/index.ts');
+  const script = `
+    const { resolve } = require(${JSON.stringify(path.join(__dirname, '../index.js'))});
+    resolve('folder/tsImportee', ${JSON.stringify(file)}, { project: ['fixtures/withPaths/tsconfig.json'] });
+  `;
+  const run = (filter) => {
+    const env = { ...process.env, RUST_LOG: filter, NO_COLOR: '1' };
+    return spawnSync(process.execPath, ['-e', script], { encoding: 'utf8', env }).stderr;
+  };
+
+  const debug = run('eslint_import_resolver_typescript=debug,ts_import_resolver=trace');
+  t.regex(debug, /resolution\{source="folder\/tsImportee" file=.*\}/);
+  t.regex(debug, /tsconfig\{path=.*withPaths\/tsconfig\.json\}: .*close/);
+  t.regex(debug, /paths pattern pattern="folder\/\*"/);
+  t.regex(debug, /probe path=.*tsImportee\.ts found=true/);
+  t.notRegex(run('ts_import_resolver=debug'), /probe/);
+});
+
+test('explain a resolution project by project', (t) => {
+  const fixture = (part) => path.resolve(path.join(__dirname, '../fixtures/withPaths', part));
+  const project = ['fixtures/withPaths/tsconfig.json', 'tsconfig.json'];
+  const lines = explain('folder/tsImportee', fixture('index.ts'), { project }).split('\n');
+  t.is(lines[0], `Resolving "folder/tsImportee" from ${fixture('index.ts')}`);
+  t.is(lines[1], `  project ${fixture('tsconfig.json')}`);
+  t.true(lines.includes('  paths pattern "folder/*"'));
+  t.true(lines.some((line) => /^ {2}probed \d+ files?, found .*withPaths\/tsImportee\.ts$/.test(line)));
+  t.true(lines.includes(`  → found ${fixture('tsImportee.ts')}`));
+  // Projects after the one resolving are tried too
+  t.true(lines.includes(`  project ${path.resolve('tsconfig.json')}`));
+  t.is(lines.filter((line) => line.startsWith('  project ')).length, 2);
+  t.is(lines[lines.length - 1], `Resolved to ${fixture('tsImportee.ts')}`);
+
+  const missing = explain('./tsImporte', fixture('index.ts'), { project }).split('\n');
+  t.true(missing.includes('  → not found: NotFound'));
+  t.is(missing[missing.length - 2], 'Not resolved: NotFound');
+  t.regex(missing[missing.length - 1], /^Did you mean "\.\/tsImportee"/);
+  t.is(explain('fs', fixture('index.ts'), { project }).split('\n').pop(), 'Resolved to a core module');
+});
+
+test('report ambiguous resolutions in strict mode', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  const paths = { '@lib/*': ['./src/lib/*', './vendor/lib/*'], lodash: ['./src/shims/lodash.ts'] };
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths } }));
+  write('src/utils.ts', 'export {}');
+  write('src/lib/a.ts', 'export {}');
+  write('vendor/lib/a.ts', 'export {}');
+  write('src/shims/lodash.ts', 'export {}');
+  write('src/legacy/b.ts', 'export {}');
+  write('src/next/b.ts', 'export {}');
+  write('node_modules/lodash/package.json', JSON.stringify({ name: 'lodash', main: 'lodash.js' }));
+  write('node_modules/lodash/lodash.js', '');
+  const file = path.join(dir, 'src/index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const ambiguous = (source, options = {}) => resolve(source, file, { project, strict: true, ...options });
+
+  const lib = ambiguous('@lib/a');
+  t.is(lib.path, path.join(dir, 'src/lib/a.ts'));
+  t.deepEqual(lib.ambiguousPaths, [path.join(dir, 'vendor/lib/a.ts')]);
+  // The alias shadows the package
+  const lodash = ambiguous('lodash');
+  t.is(lodash.path, path.join(dir, 'src/shims/lodash.ts'));
+  t.deepEqual(lodash.ambiguousPaths, [path.join(dir, 'node_modules/lodash/lodash.js')]);
+  const alias = { '@b': [path.join(dir, 'src/next'), path.join(dir, 'src/legacy')] };
+  t.deepEqual(ambiguous('@b/b', { alias }).ambiguousPaths, [path.join(dir, 'src/legacy/b.ts')]);
+  t.is(ambiguous('./utils').ambiguousPaths, undefined);
+  t.is(resolve('@lib/a', file, { project }).ambiguousPaths, undefined);
+});
+
+test('block resolutions outside the project root with restrictToProject', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  const paths = { '@shared/*': ['../shared/*'] };
+  write('app/tsconfig.json', JSON.stringify({ compilerOptions: { paths } }));
+  write('app/src/utils.ts', 'export {}');
+  write('shared/format.ts', 'export {}');
+  write('linked/package.json', JSON.stringify({ name: 'linked', main: 'index.js' }));
+  write('linked/index.js', '');
+  fs.mkdirSync(path.join(dir, 'app/node_modules'));
+  fs.symlinkSync(path.join(dir, 'linked'), path.join(dir, 'app/node_modules/linked'), 'dir');
+  const file = path.join(dir, 'app/src/index.ts');
+  const options = { project: [path.join(dir, 'app/tsconfig.json')], roots: [path.join(dir, 'app')] };
+
+  t.true(resolve('@shared/format', file, options).found);
+  t.true(resolve('linked', file, options).found);
+  const restricted = { ...options, restrictToProject: true };
+  t.like(resolve('@shared/format', file, restricted), { found: false, path: '', reason: 'OutsideProject' });
+  t.like(resolve('linked', file, restricted), { found: false, reason: 'OutsideProject' });
+  t.is(resolve('./utils', file, restricted).path, path.join(dir, 'app/src/utils.ts'));
+  t.is(resolve('fs', file, restricted).found, true);
+});
+
+test('check extends links, paths targets, and workspace aliases with doctor', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  const paths = { '@app/*': ['./src/*', './gone/*'], config: ['./config'] };
+  write('tsconfig.json', JSON.stringify({ extends: './base.json', compilerOptions: { paths } }));
+  write('base.json', JSON.stringify({ compilerOptions: { strict: true } }));
+  write('tsconfig.broken.json', JSON.stringify({ extends: ['./base.json', './missing.json'] }));
+  write('src/index.ts', 'export {}');
+  write('config.ts', 'export {}');
+  write('package.json', JSON.stringify({ private: true, workspaces: ['packages/*'] }));
+  write('packages/ui/package.json', JSON.stringify({ name: 'ui', source: 'src/index.ts' }));
+  write('packages/ui/src/index.ts', 'export {}');
+  write('packages/core/package.json', JSON.stringify({ name: 'core', main: 'dist/index.js' }));
+  const project = path.join(dir, 'tsconfig.json');
+  const broken = path.join(dir, 'tsconfig.broken.json');
+
+  const outcome = ({ kind, name, target, ok }) => `${ok ? 'ok' : 'failed'} ${kind} ${name} ${target}`;
+  const checks = doctor({ project: [project] });
+  t.true(checks.every((check) => check.project === project));
+  t.deepEqual(checks.map(outcome), [
+    `ok extends ./base.json ${path.join(dir, 'base.json')}`,
+    'ok paths @app/* ./src/*',
+    'failed paths @app/* ./gone/*',
+    'ok paths config ./config',
+    `failed workspaceAlias core ${path.join(dir, 'packages/core/dist/index.js')}`,
+    `ok workspaceAlias ui ${path.join(dir, 'packages/ui/src/index.ts')}`,
+  ]);
+  t.is(checks[2].problem, `No dir ${path.join(dir, 'gone')}`);
+  const brokenChecks = doctor({ project: [broken] });
+  t.deepEqual(brokenChecks.slice(0, 3).map(outcome), [
+    `ok extends ./base.json ${path.join(dir, 'base.json')}`,
+    'failed extends ./missing.json ./missing.json',
+    `failed project ${broken} ${broken}`,
+  ]);
+
+  const cli = path.join(__dirname, '../bin/ts-resolve.js');
+  const report = spawnSync(process.execPath, [cli, 'doctor', '--project', project], { encoding: 'utf8' });
+  t.is(report.status, 1);
+  t.regex(report.stdout, /^.*tsconfig\.json\n {2}ok {6}extends "\.\/base\.json" → /);
+  t.regex(report.stdout, /\n {2}FAILED {2}paths "@app\/\*" → \.\/gone\/\*: No dir .*gone\n/);
+  t.regex(report.stdout, /\n2 of 6 checks failed\n$/);
+  t.throws(() => doctor({ project: [path.join(dir, 'missing.json')] }), { message: /missing\.json" not found/ });
+});
+
+test('collect what every strategy resolves to in exhaustive search mode', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', JSON.stringify({ compilerOptions: { paths: { lodash: ['./src/shims/lodash.ts'] } } }));
+  write('src/shims/lodash.ts', 'export {}');
+  write('node_modules/lodash/package.json', JSON.stringify({ name: 'lodash', main: 'lodash.js' }));
+  write('node_modules/lodash/lodash.js', '');
+  const file = path.join(dir, 'src/index.ts');
+  const project = [path.join(dir, 'tsconfig.json')];
+
+  const resolved = resolve('lodash', file, { project, searchMode: 'exhaustive' });
+  t.is(resolved.path, path.join(dir, 'src/shims/lodash.ts'));
+  const outcomes = resolved.candidates
+    .filter((candidate) => candidate.project === project[0])
+    .map(({ strategy, found, path: candidatePath, reason }) => `${strategy} ${found ? candidatePath : reason}`);
+  t.deepEqual(outcomes, [
+    `paths ${path.join(dir, 'src/shims/lodash.ts')}`,
+    'workspaceDependency NotFound',
+    `node ${path.join(dir, 'node_modules/lodash/lodash.js')}`,
+    'workspacePackage NotFound',
+    'types NotFound',
+  ]);
+  const relative = resolve('./missing', file, { project, searchMode: 'exhaustive' });
+  t.false(relative.found);
+  t.like(relative.candidates[0], { strategy: 'node', specifier: './missing', found: false, reason: 'NotFound' });
+  t.is(resolve('lodash', file, { project }).candidates, undefined);
+  t.throws(() => resolve('lodash', file, { project, searchMode: 'all' }), { message: /searchMode/ });
+});
+
+test('resolve through an import map given inline', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const write = (file, content) => {
+    fs.mkdirSync(path.dirname(path.join(dir, file)), { recursive: true });
+    fs.writeFileSync(path.join(dir, file), content);
+  };
+  write('tsconfig.json', '{}');
+  write('vendor/lit.js', '');
+  write('vendor/lit-2.js', '');
+  write('public/js/app.js', '');
+  const file = path.join(dir, 'public/js/main.js');
+  const project = [path.join(dir, 'tsconfig.json')];
+  const importMap = {
+    imports: { lit: `${dir}/vendor/lit.js`, 'app/': `${dir}/public/js/` },
+    scopes: { [`${dir}/public/legacy/`]: { lit: `${dir}/vendor/lit-2.js` } },
+  };
+
+  t.is(resolve('lit', file, { project, importMap }).path, path.join(dir, 'vendor/lit.js'));
+  t.is(resolve('app/app.js', file, { project, importMap }).path, path.join(dir, 'public/js/app.js'));
+  const legacy = path.join(dir, 'public/legacy/main.js');
+  t.is(resolve('lit', legacy, { project, importMap }).path, path.join(dir, 'vendor/lit-2.js'));
+  t.is(resolve('lit', file, { project, importMap: JSON.stringify(importMap) }).path, path.join(dir, 'vendor/lit.js'));
+  t.throws(() => resolve('lit', file, { project, importMap: '{ "imports": ' }), { message: /Invalid `importMap`/ });
+});
+
+test('load the binding TS_RESOLVER_BINARY names in place of the prebuilt one', (t) => {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'resolver-')));
+  const root = path.join(__dirname, '..');
+  const prebuilt = fs.readdirSync(root).find((name) => name.endsWith('.node'));
+  const binary = path.join(dir, 'resolver.node');
+  fs.copyFileSync(path.join(root, prebuilt), binary);
+  const script = `
+    const { resolve } = require(${JSON.stringify(path.join(root, 'index.js'))});
+    const file = ${JSON.stringify(path.join(root, 'fixtures/withoutPaths/index.ts'))};
+    console.log(resolve('./tsImportee', file, { project: ['tsconfig.json'] }).found);
+    console.log(Object.keys(require.cache).some((name) => name.endsWith('.node') && name !== ${JSON.stringify(binary)}));
+  `;
+  const run = (binary) => {
+    const env = { ...process.env, TS_RESOLVER_BINARY: binary };
+    return spawnSync(process.execPath, ['-e', script], { encoding: 'utf8', env });
+  };
+
+  t.deepEqual(run(binary).stdout.trim().split('\n'), ['true', 'false']);
+  t.regex(run(path.join(dir, 'missing.node')).stderr, /Cannot find module/);
+  fs.rmSync(dir, { recursive: true });
+});
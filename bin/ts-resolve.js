@@ -0,0 +1,122 @@
+#!/usr/bin/env node
+const path = require('path');
+const { doctor, explain, resolve } = require('..');
+
+const USAGE = [
+  'Usage: ts-resolve <specifier> --from <file> [--project <tsconfig>]... [--trace | --explain]',
+  '       ts-resolve doctor [--project <tsconfig>]...',
+].join('\n');
+
+const parseArgs = (args) => {
+  const parsed = { specifier: undefined, from: undefined, project: [], trace: false, explain: false };
+  for (let i = 0; i < args.length; i++) {
+    const arg = args[i];
+    if (arg === '--trace') {
+      parsed.trace = true;
+    } else if (arg === '--explain') {
+      parsed.explain = true;
+    } else if (arg === '--from' || arg === '--project') {
+      const value = args[++i];
+      if (value === undefined) {
+        throw new Error(`Missing value for ${arg}`);
+      }
+      if (arg === '--from') {
+        parsed.from = path.resolve(value);
+      } else {
+        parsed.project.push(value);
+      }
+    } else if (arg.startsWith('--')) {
+      throw new Error(`Unknown option ${arg}`);
+    } else if (parsed.specifier === undefined) {
+      parsed.specifier = arg;
+    } else {
+      throw new Error(`Unexpected argument ${arg}`);
+    }
+  }
+  if (parsed.specifier === undefined || parsed.from === undefined) {
+    throw new Error('Missing specifier or --from');
+  }
+  return parsed;
+};
+
+const parseDoctorArgs = (args) => {
+  const project = [];
+  for (let i = 0; i < args.length; i++) {
+    if (args[i] !== '--project') {
+      throw new Error(args[i].startsWith('--') ? `Unknown option ${args[i]}` : `Unexpected argument ${args[i]}`);
+    }
+    if (args[++i] === undefined) {
+      throw new Error('Missing value for --project');
+    }
+    project.push(args[i]);
+  }
+  return { project };
+};
+
+// Check every `extends` link, `paths` target, and workspace alias of the
+// projects, printing one line per check grouped by project
+const runDoctor = (args) => {
+  let checks;
+  try {
+    checks = doctor(parseDoctorArgs(args));
+  } catch (error) {
+    console.error(`${error.message}\n${USAGE}`);
+    return 2;
+  }
+
+  let project;
+  for (const check of checks) {
+    if (check.project !== project) {
+      project = check.project;
+      console.log(project);
+    }
+    const mapping = `${check.kind} "${check.name}" → ${check.target}`;
+    console.log(check.ok ? `  ok      ${mapping}` : `  FAILED  ${mapping}: ${check.problem}`);
+  }
+  const failed = checks.filter((check) => !check.ok).length;
+  console.log(`${failed} of ${checks.length} checks failed`);
+  return failed > 0 ? 1 : 0;
+};
+
+const main = (args) => {
+  if (args[0] === 'doctor') {
+    return runDoctor(args.slice(1));
+  }
+  let parsed;
+  try {
+    parsed = parseArgs(args);
+  } catch (error) {
+    console.error(`${error.message}\n${USAGE}`);
+    return 2;
+  }
+
+  const { specifier, from, project, trace } = parsed;
+  // Without --project, resolve like ESLint would: from ./tsconfig.json
+  const resolved = resolve(specifier, from, { project, trace });
+  if (parsed.explain) {
+    console.log(explain(specifier, from, { project }));
+    return resolved.found ? 0 : 1;
+  }
+  const printTrace = () => (resolved.trace || []).forEach((step) => console.error(`  ${step}`));
+  (resolved.warnings || []).forEach((warning) => console.error(`warning: ${warning}`));
+  if (resolved.found) {
+    if (trace) {
+      printTrace();
+    }
+    console.log(resolved.path || `${specifier} (core module)`);
+    return 0;
+  }
+
+  console.error(`Cannot resolve "${specifier}" from ${from}: ${resolved.reason}`);
+  if (resolved.suggestions && resolved.suggestions.length > 0) {
+    console.error(`Did you mean ${resolved.suggestions.map((suggestion) => `"${suggestion}"`).join(', ')}?`);
+  }
+  if (trace) {
+    printTrace();
+  } else {
+    (resolved.attemptedPaths || []).forEach((attempted) => console.error(`  tried ${attempted}`));
+  }
+  return 1;
+};
+
+process.exitCode = main(process.argv.slice(2));
@@ -0,0 +1,61 @@
+//! Count the file checks and package.json reads of a single resolution, to
+//! find extension orders or aliases that probe more than they should.
+//!
+//! ```rust
+//! use ts_import_resolver::counters::{Counter, Counters};
+//!
+//! let counters = Counters::new(true);
+//! counters.add(Counter::FileChecks);
+//! counters.get(Counter::FileChecks);
+//! // → 1
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Copy)]
+pub enum Counter {
+  // checks if a candidate file or package.json exists, most answered by the
+  // stat cache
+  FileChecks,
+  // package.json files read, most parsed once per process
+  PackageJsonReads,
+}
+
+/// Counts shared by every resolver cloned from the one they were attached
+/// to. Disabled counters count nothing.
+#[derive(Clone, Default)]
+pub struct Counters {
+  counts: Option<Arc<[AtomicU64; 2]>>,
+}
+
+impl Counters {
+  pub fn new(enabled: bool) -> Self {
+    Counters {
+      counts: if enabled {
+        Some(Arc::new(Default::default()))
+      } else {
+        None
+      },
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.counts.is_some()
+  }
+
+  /// Count one more of `counter`.
+  pub fn add(&self, counter: Counter) {
+    if let Some(counts) = &self.counts {
+      counts[counter as usize].fetch_add(1, Ordering::Relaxed);
+    }
+  }
+
+  /// Get the count of `counter` so far.
+  pub fn get(&self, counter: Counter) -> u64 {
+    self
+      .counts
+      .as_ref()
+      .map_or(0, |counts| counts[counter as usize].load(Ordering::Relaxed))
+  }
+}
@@ -0,0 +1,80 @@
+//! List each probed directory once, so candidates that aren't there, e.g.
+//! `foo.tsx`, `foo.d.ts`, and `foo.js` next to `foo.ts`, are ruled out in
+//! memory instead of with a `stat` each. Listings are reused as long as the
+//! stat cache reuses its results.
+//!
+//! ```rust
+//! use std::path::Path;
+//! use ts_import_resolver::dir_listing::may_exist;
+//!
+//! may_exist(Path::new("/repo/src/foo.tsx"));
+//! // → lists /repo/src once, then false if it has no "foo.tsx"
+//! ```
+
+use crate::lru::{self, LruMap};
+use crate::stat_cache;
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+// Lowercased entry names per dir, so case-insensitive filesystems still
+// find "Foo.ts" for "foo.ts". `None` when the dir can't be listed
+type Listing = Option<Arc<HashSet<String>>>;
+
+fn listings() -> &'static LruMap<PathBuf, (Listing, Instant)> {
+  static LISTINGS: OnceLock<LruMap<PathBuf, (Listing, Instant)>> = OnceLock::new();
+  LISTINGS.get_or_init(|| LruMap::new(lru::capacity(10_000)))
+}
+
+fn list(dir: &Path) -> Listing {
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(error) if error.kind() == ErrorKind::NotFound => return Some(Arc::default()),
+    // e.g. a dir that may be entered but not listed
+    Err(_) => return None,
+  };
+  let names = entries
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+    .collect();
+  Some(Arc::new(names))
+}
+
+/// Check if `path` may exist, false only if its dir has no entry by its name.
+pub fn may_exist(path: &Path) -> bool {
+  let (dir, name) = match (path.parent(), path.file_name()) {
+    (Some(dir), Some(name)) => (dir, name.to_string_lossy().to_lowercase()),
+    _ => return true,
+  };
+  let ttl = stat_cache::ttl();
+  if ttl.is_zero() {
+    return true;
+  }
+
+  let now = Instant::now();
+  let listing = match listings().get(dir) {
+    Some((listing, listed_at)) if now.duration_since(listed_at) < ttl => listing,
+    _ => {
+      let listing = list(dir);
+      listings().insert(dir.to_path_buf(), (listing.clone(), now));
+      listing
+    }
+  };
+  listing.is_none_or(|names| names.contains(&name))
+}
+
+pub fn clear() {
+  listings().clear();
+}
+
+/// Drop the listings of `path` and its dir, e.g. after it was created or
+/// deleted.
+pub fn invalidate(path: &Path) {
+  listings().remove(path);
+  if let Some(dir) = path.parent() {
+    listings().remove(dir);
+  }
+}
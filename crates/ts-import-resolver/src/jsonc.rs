@@ -0,0 +1,96 @@
+//! Read JSON with comments and trailing commas, as used by tsconfig files.
+//!
+//! ```rust
+//! use ts_import_resolver::jsonc::parse_str;
+//!
+//! parse_str("{ \"a\": 1, /* comment */ }");
+//! // → Some({"a": 1})
+//! ```
+
+use serde_json::Value;
+use std::fs;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+// Skip a comment whose leading '/' was just consumed, if one starts here.
+// Line comments keep their newline.
+fn skip_comment(chars: &mut Peekable<Chars>) -> Option<bool> {
+  match chars.peek() {
+    Some('/') => {
+      for c in chars.by_ref() {
+        if c == '\n' {
+          return Some(true);
+        }
+      }
+      Some(false)
+    }
+    Some('*') => {
+      chars.next();
+      let mut previous = ' ';
+      for c in chars.by_ref() {
+        if previous == '*' && c == '/' {
+          break;
+        }
+        previous = c;
+      }
+      Some(false)
+    }
+    _ => None,
+  }
+}
+
+// Get the next character that is neither whitespace nor inside a comment
+fn next_significant(mut chars: Peekable<Chars>) -> Option<char> {
+  while let Some(c) = chars.next() {
+    if c.is_whitespace() || c == '/' && skip_comment(&mut chars).is_some() {
+      continue;
+    }
+    return Some(c);
+  }
+  None
+}
+
+/// Remove comments and trailing commas, keeping strings intact.
+pub fn strip(text: &str) -> String {
+  let mut stripped = String::with_capacity(text.len());
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => {
+        stripped.push(c);
+        while let Some(c) = chars.next() {
+          stripped.push(c);
+          if c == '\\' {
+            if let Some(escaped) = chars.next() {
+              stripped.push(escaped);
+            }
+          } else if c == '"' {
+            break;
+          }
+        }
+      }
+      '/' => match skip_comment(&mut chars) {
+        Some(true) => stripped.push('\n'),
+        Some(false) => {}
+        None => stripped.push(c),
+      },
+      ',' => match next_significant(chars.clone()) {
+        Some('}') | Some(']') => {}
+        _ => stripped.push(c),
+      },
+      _ => stripped.push(c),
+    }
+  }
+  stripped
+}
+
+/// Parse JSON with comments and trailing commas.
+pub fn parse_str(text: &str) -> Option<Value> {
+  serde_json::from_str(strip(text).as_str()).ok()
+}
+
+/// Read and parse a file of JSON with comments and trailing commas.
+pub fn parse_file(path: &Path) -> Option<Value> {
+  parse_str(fs::read_to_string(path).ok()?.as_str())
+}
@@ -0,0 +1,51 @@
+#![deny(clippy::all)]
+
+//! Node.js module resolution, with the process-wide caches of file checks,
+//! dir listings, realpaths, package.json files, and node_modules indexes it
+//! shares across lookups, plus workspace lookups and resolution through
+//! tsconfig `paths` and `baseUrl` with `ProjectResolver`. The native ESLint
+//! resolver builds on it; other tools can use it without Node.js.
+//!
+//! ```rust
+//! use ts_import_resolver::Resolver;
+//!
+//! # use std::fs;
+//! # let dir = std::env::temp_dir().join("ts-import-resolver-doc-lib");
+//! # fs::create_dir_all(dir.join("src")).unwrap();
+//! # fs::create_dir_all(dir.join("node_modules/lodash")).unwrap();
+//! # fs::write(dir.join("src/app.tsx"), "").unwrap();
+//! # fs::write(dir.join("node_modules/lodash/package.json"), r#"{ "main": "lodash.js" }"#).unwrap();
+//! # fs::write(dir.join("node_modules/lodash/lodash.js"), "").unwrap();
+//! # let dir = dir.canonicalize().unwrap();
+//! let resolver = Resolver::new()
+//!   .with_basedir(dir.join("src"))
+//!   .with_extensions([".ts", ".tsx", ".js"]);
+//! assert_eq!(resolver.resolve("./app").unwrap(), dir.join("src/app.tsx"));
+//! assert_eq!(
+//!   resolver.resolve("lodash").unwrap(),
+//!   dir.join("node_modules/lodash/lodash.js")
+//! );
+//! ```
+
+pub mod counters;
+pub mod dir_listing;
+pub mod jsonc;
+pub mod lru;
+pub mod node_resolve;
+pub mod package_index;
+pub mod paths_matcher;
+pub mod project;
+pub mod realpath_cache;
+pub mod runtime;
+pub mod stat_cache;
+pub mod timing;
+pub mod trace;
+pub mod workspace;
+
+pub use node_resolve::{is_core_module, ResolutionError, ResolutionErrorKind, Resolver};
+pub use paths_matcher::{PathsMatch, PathsMatcher};
+pub use project::{
+  Attempt, LookupOptions, Project, ProjectOptions, ProjectResolver, Resolution, ResolveOutcome,
+  Strategy,
+};
+pub use runtime::Runtime;
@@ -0,0 +1,153 @@
+//! Bound the native caches, so long-lived editor processes don't grow without
+//! limit. Every cache holds at most its default number of entries, or the
+//! number in the `TS_RESOLVER_CACHE_SIZE` environment variable.
+//!
+//! ```rust
+//! use ts_import_resolver::lru::{capacity, LruMap};
+//!
+//! let map = LruMap::new(capacity(2));
+//! map.insert("a", 1);
+//! map.insert("b", 2);
+//! map.get("a");
+//! map.insert("c", 3);
+//! // → "b" is dropped, it was used least recently
+//! ```
+
+use dashmap::DashMap;
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+/// Get the capacity of a cache, `default` unless `TS_RESOLVER_CACHE_SIZE`
+/// is set. Read once per process.
+pub fn capacity(default: usize) -> usize {
+  static CACHE_SIZE: OnceLock<Option<usize>> = OnceLock::new();
+  let cache_size = CACHE_SIZE.get_or_init(|| {
+    std::env::var("TS_RESOLVER_CACHE_SIZE")
+      .ok()
+      .and_then(|size| size.trim().parse().ok())
+  });
+  cache_size.unwrap_or(default).max(1)
+}
+
+/// Concurrent map dropping its least recently used entries when full. Usage
+/// is tracked with a counter per entry, and a tenth of the entries is dropped
+/// at once, so scanning for them is rare.
+pub struct LruMap<K, V> {
+  entries: DashMap<K, (V, AtomicU64)>,
+  capacity: usize,
+  tick: AtomicU64,
+}
+
+impl<K, V> LruMap<K, V>
+where
+  K: Eq + Hash,
+  V: Clone,
+{
+  pub fn new(capacity: usize) -> Self {
+    LruMap {
+      entries: DashMap::new(),
+      capacity,
+      tick: AtomicU64::new(0),
+    }
+  }
+
+  fn next_tick(&self) -> u64 {
+    self.tick.fetch_add(1, Ordering::Relaxed)
+  }
+
+  pub fn get<Q>(&self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+  {
+    let entry = self.entries.get(key)?;
+    entry.1.store(self.next_tick(), Ordering::Relaxed);
+    Some(entry.0.clone())
+  }
+
+  pub fn insert(&self, key: K, value: V) {
+    let tick = AtomicU64::new(self.next_tick());
+    self.entries.insert(key, (value, tick));
+    if self.entries.len() > self.capacity {
+      self.evict();
+    }
+  }
+
+  // Keep the most recently used nine tenths of the capacity
+  fn evict(&self) {
+    let mut ticks: Vec<u64> = self
+      .entries
+      .iter()
+      .map(|entry| entry.1.load(Ordering::Relaxed))
+      .collect();
+    let keep = self.capacity - self.capacity / 10;
+    if ticks.len() <= keep {
+      return;
+    }
+    let drop_count = ticks.len() - keep;
+    let (_, cutoff, _) = ticks.select_nth_unstable(drop_count);
+    let cutoff = *cutoff;
+    self
+      .entries
+      .retain(|_, (_, tick)| tick.load(Ordering::Relaxed) >= cutoff);
+  }
+
+  pub fn remove<Q>(&self, key: &Q)
+  where
+    K: Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+  {
+    self.entries.remove(key);
+  }
+
+  pub fn retain<F>(&self, keep: F)
+  where
+    F: Fn(&K, &V) -> bool,
+  {
+    self.entries.retain(|key, (value, _)| keep(key, value));
+  }
+
+  pub fn clear(&self) {
+    self.entries.clear();
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Get a copy of every entry, in no particular order.
+  pub fn entries(&self) -> Vec<(K, V)>
+  where
+    K: Clone,
+  {
+    self
+      .entries
+      .iter()
+      .map(|entry| (entry.key().clone(), entry.value().0.clone()))
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn drops_least_recently_used() {
+    let map = LruMap::new(2);
+    map.insert("a", 1);
+    map.insert("b", 2);
+    assert_eq!(Some(1), map.get("a"));
+    map.insert("c", 3);
+    assert_eq!(2, map.len());
+    assert_eq!(None, map.get("b"));
+    assert_eq!(Some(1), map.get("a"));
+    assert_eq!(Some(3), map.get("c"));
+  }
+}
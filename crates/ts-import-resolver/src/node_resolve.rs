@@ -0,0 +1,1308 @@
+//! Resolve module identifiers in a Node-style `require()` to a full file path.
+//!
+//! ```rust
+//! use std::path::PathBuf;
+//! use ts_import_resolver::node_resolve::{resolve, resolve_from};
+//!
+//! resolve("abc");
+//! // → Ok("/path/to/cwd/node_modules/abc/index.js")
+//! resolve_from("abc", PathBuf::from("/other/path"));
+//! // → Ok("/other/path/node_modules/abc/index.js")
+//! ```
+
+extern crate serde_json;
+
+use cached::proc_macro::cached;
+use cached::{Cached, SizedCache};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::default::Default;
+use std::error::Error;
+use std::fs::File;
+use std::path::{Component as PathComponent, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use std::{env, fmt, fs, io};
+
+use crate::counters::{Counter, Counters};
+use crate::dir_listing;
+use crate::package_index;
+use crate::realpath_cache;
+use crate::runtime::Runtime;
+use crate::stat_cache;
+use crate::timing::{Phase, Timing};
+use crate::trace::Trace;
+/// Why a module could not be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionErrorKind {
+  /// No candidate file exists.
+  NotFound,
+  /// The package has "exports", but none of them matches the subpath.
+  NotExported,
+  /// The matching "exports" target is `null`.
+  BlockedByExportsNull,
+}
+
+/// An Error, returned when the module could not be resolved.
+#[derive(Debug)]
+pub struct ResolutionError {
+  description: String,
+  kind: ResolutionErrorKind,
+}
+impl ResolutionError {
+  pub(crate) fn new(description: &str) -> Self {
+    ResolutionError::with_kind(ResolutionErrorKind::NotFound, description)
+  }
+
+  fn with_kind(kind: ResolutionErrorKind, description: &str) -> Self {
+    ResolutionError {
+      description: String::from(description),
+      kind,
+    }
+  }
+
+  /// Get why the module could not be resolved.
+  pub fn kind(&self) -> ResolutionErrorKind {
+    self.kind
+  }
+}
+
+impl From<serde_json::Error> for ResolutionError {
+  fn from(_error: serde_json::Error) -> Self {
+    ResolutionError::new("Json parse error")
+  }
+}
+
+impl From<std::io::Error> for ResolutionError {
+  fn from(_error: std::io::Error) -> Self {
+    ResolutionError::new("Io error")
+  }
+}
+
+impl fmt::Display for ResolutionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.description)
+  }
+}
+
+impl Error for ResolutionError {
+  fn description(&self) -> &str {
+    self.description.as_str()
+  }
+  fn cause(&self) -> Option<&dyn Error> {
+    None
+  }
+}
+
+// TypeScript sources of JavaScript extensions, which imports name them by
+const TS_SOURCE_EXTENSIONS: [(&str, &[&str]); 4] = [
+  (".js", &[".ts", ".tsx", ".d.ts"]),
+  (".jsx", &[".tsx"]),
+  (".mjs", &[".mts", ".d.mts"]),
+  (".cjs", &[".cts", ".d.cts"]),
+];
+
+/// Resolver instances keep track of options.
+#[derive(Clone)]
+pub struct Resolver {
+  basedir: Option<PathBuf>,
+  extensions: Vec<String>,
+  preserve_symlinks: bool,
+  main_fields: Vec<String>,
+  condition_names: Vec<String>,
+  modules: Vec<String>,
+  runtime: Runtime,
+  cwd: PathBuf,
+  resolve_directories: bool,
+  fully_specified: bool,
+  probes: Option<Arc<Mutex<Vec<PathBuf>>>>,
+  warnings: Option<Arc<Mutex<Vec<String>>>>,
+  trace: Trace,
+  timing: Timing,
+  counters: Counters,
+}
+
+impl Default for Resolver {
+  fn default() -> Resolver {
+    Resolver {
+      basedir: None,
+      extensions: vec![
+        String::from(".js"),
+        String::from(".json"),
+        String::from(".node"),
+      ],
+      preserve_symlinks: false,
+      main_fields: vec![String::from("main")],
+      condition_names: vec![String::from("require"), String::from("node")],
+      modules: vec![String::from("node_modules")],
+      runtime: Runtime::Node,
+      cwd: env::current_dir().unwrap_or_default(),
+      resolve_directories: true,
+      fully_specified: false,
+      probes: None,
+      warnings: None,
+      trace: Trace::default(),
+      timing: Timing::default(),
+      counters: Counters::default(),
+    }
+  }
+}
+
+impl Resolver {
+  /// Create a new resolver with the given options.
+  pub fn new() -> Self {
+    Resolver::default()
+  }
+
+  fn get_basedir(&self) -> Result<&PathBuf, ResolutionError> {
+    self
+      .basedir
+      .as_ref()
+      .ok_or_else(|| ResolutionError::new("Must set a basedir before resolving"))
+  }
+
+  /// Create a new resolver with a different basedir.
+  pub fn with_basedir(&self, basedir: PathBuf) -> Self {
+    Resolver {
+      basedir: Some(basedir),
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver with a different set of extensions.
+  pub fn with_extensions<T>(&self, extensions: T) -> Self
+  where
+    T: IntoIterator,
+    T::Item: ToString,
+  {
+    Resolver {
+      extensions: extensions
+        .into_iter()
+        .map(|ext| ext.to_string())
+        .map(|ext| {
+          if ext.starts_with('.') {
+            ext
+          } else {
+            format!(".{}", ext)
+          }
+        })
+        .collect(),
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver with a different set of main fields.
+  pub fn with_main_fields<T>(&self, main_fields: T) -> Self
+  where
+    T: IntoIterator,
+    T::Item: ToString,
+  {
+    Resolver {
+      main_fields: main_fields
+        .into_iter()
+        .map(|field| field.to_string())
+        .collect(),
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver with a different set of package.json "exports"
+  /// conditions. "default" always matches.
+  pub fn with_condition_names<T>(&self, condition_names: T) -> Self
+  where
+    T: IntoIterator,
+    T::Item: ToString,
+  {
+    Resolver {
+      condition_names: condition_names
+        .into_iter()
+        .map(|condition| condition.to_string())
+        .collect(),
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver looking up packages in different dirs: names,
+  /// like "node_modules", in the basedir and each dir above it, absolute
+  /// paths as they are.
+  pub fn with_modules<T>(&self, modules: T) -> Self
+  where
+    T: IntoIterator,
+    T::Item: ToString,
+  {
+    Resolver {
+      modules: modules.into_iter().map(|dir| dir.to_string()).collect(),
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver for a different runtime, which decides what core
+  /// modules are. Conditions and main fields are set separately.
+  pub fn with_runtime(&self, runtime: Runtime) -> Self {
+    Resolver {
+      runtime,
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver for a different working dir, whose installed
+  /// Node.js types may declare core modules. The process's if unset.
+  pub fn with_cwd(&self, cwd: PathBuf) -> Self {
+    Resolver {
+      cwd,
+      ..self.clone()
+    }
+  }
+
+  /// Get the working dir, the process's unless set with `with_cwd`.
+  pub fn get_cwd(&self) -> &Path {
+    &self.cwd
+  }
+
+  /// Check if `target` is a core module of the runtime, counting those the
+  /// Node.js types installed in the working dir declare.
+  pub fn is_core_module(&self, target: &str) -> bool {
+    self.runtime.is_core_module_in(target, &self.cwd)
+  }
+
+  /// Create a new resolver with a different symlink option.
+  pub fn preserve_symlinks(&self, preserve_symlinks: bool) -> Self {
+    Resolver {
+      preserve_symlinks,
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver that does or does not resolve directories through
+  /// package.json "main"/"exports" and index files.
+  pub fn resolve_directories(&self, resolve_directories: bool) -> Self {
+    Resolver {
+      resolve_directories,
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver that does or does not require local files to be
+  /// specified with their extension, like ESM, never resolving directories.
+  /// JavaScript extensions still resolve to TypeScript sources.
+  pub fn fully_specified(&self, fully_specified: bool) -> Self {
+    Resolver {
+      fully_specified,
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver that records every candidate file it probes.
+  pub fn with_probe_log(&self, probes: Arc<Mutex<Vec<PathBuf>>>) -> Self {
+    Resolver {
+      probes: Some(probes),
+      ..self.clone()
+    }
+  }
+
+  /// Create a new resolver that collects problems with the packages it
+  /// reads, e.g. malformed "exports", in `warnings`.
+  pub fn with_warnings(&self, warnings: Arc<Mutex<Vec<String>>>) -> Self {
+    Resolver {
+      warnings: Some(warnings),
+      ..self.clone()
+    }
+  }
+
+  /// Record a warning, once per resolution, unless warnings aren't collected.
+  pub fn warn<F>(&self, warning: F)
+  where
+    F: FnOnce() -> String,
+  {
+    if let Some(warnings) = &self.warnings {
+      let warning = warning();
+      tracing::warn!("{}", warning);
+      self.trace.step(|| format!("warning: {}", warning));
+      let mut warnings = warnings.lock().unwrap();
+      if !warnings.contains(&warning) {
+        warnings.push(warning);
+      }
+    }
+  }
+
+  /// Read a package.json, warning if it exists but can't be parsed.
+  fn read_package(&self, pkg_path: &Path) -> Result<Arc<Value>, ResolutionError> {
+    self.counters.add(Counter::PackageJsonReads);
+    let pkg = read_package_json(pkg_path);
+    if let Err(error) = &pkg {
+      if stat_cache::is_file(pkg_path) {
+        self.warn(|| format!("Unreadable {}: {}", pkg_path.display(), error));
+      }
+    }
+    pkg
+  }
+
+  /// Create a new resolver that records its steps in `trace`.
+  pub fn with_trace(&self, trace: Trace) -> Self {
+    Resolver {
+      trace,
+      ..self.clone()
+    }
+  }
+
+  /// Get the trace steps are recorded in.
+  pub fn get_trace(&self) -> &Trace {
+    &self.trace
+  }
+
+  /// Create a new resolver that measures its phases in `timing`.
+  pub fn with_timing(&self, timing: Timing) -> Self {
+    Resolver {
+      timing,
+      ..self.clone()
+    }
+  }
+
+  /// Get the timing phases are measured in.
+  pub fn get_timing(&self) -> &Timing {
+    &self.timing
+  }
+
+  /// Create a new resolver that counts its file checks and package.json
+  /// reads in `counters`.
+  pub fn with_counters(&self, counters: Counters) -> Self {
+    Resolver {
+      counters,
+      ..self.clone()
+    }
+  }
+
+  /// Get the counters file checks and package.json reads are counted in.
+  pub fn get_counters(&self) -> &Counters {
+    &self.counters
+  }
+
+  /// Check if a package.json exists, which unlike candidate files isn't
+  /// logged as a probe.
+  fn has_package_json(&self, pkg_path: &Path) -> bool {
+    self.counters.add(Counter::FileChecks);
+    stat_cache::is_file(pkg_path)
+  }
+
+  /// Check if a candidate file exists, recording it in the probe log.
+  fn is_file(&self, path: &Path) -> bool {
+    self.counters.add(Counter::FileChecks);
+    if let Some(probes) = &self.probes {
+      probes.lock().unwrap().push(normalize_path(path));
+    }
+    let is_file = self.timing.measure(Phase::Stat, || {
+      let path = normalize_path(path);
+      dir_listing::may_exist(&path) && stat_cache::is_file(&path)
+    });
+    tracing::trace!(path = %path.display(), found = is_file, "probe");
+    self.trace.step(|| {
+      format!(
+        "probe {}: {}",
+        normalize_path(path).display(),
+        if is_file { "found" } else { "missing" }
+      )
+    });
+    is_file
+  }
+
+  /// Resolve a `require()` argument.
+  pub fn resolve(&self, target: &str) -> Result<PathBuf, ResolutionError> {
+    let _span = tracing::debug_span!("resolve", target).entered();
+    // 1. If X is a core module
+    if self.is_core_module(target) {
+      // 1.a. Return the core module
+      return Ok(PathBuf::from(""));
+    }
+
+    // TODO how to not always initialise this here?
+    let root = PathBuf::from("/");
+    // 2. If X is absolute, e.g. begins with '/'
+    let basedir = if Path::new(target).is_absolute() {
+      // 2.a. Set Y to be the filesystem root
+      &root
+    } else {
+      self.get_basedir()?
+    };
+
+    // 4. Try local files
+    let path = basedir.as_path().join(target);
+    let resolved = if self.fully_specified {
+      self.resolve_specified_file(&path)
+    } else {
+      self
+        .resolve_as_file(&path)
+        .or_else(|_| self.resolve_as_directory(&path))
+    }
+    .and_then(|p| self.normalize(&p));
+    if resolved.is_ok() {
+      return resolved;
+    }
+
+    // 5. Try node-Modules
+    self
+      .timing
+      .measure(Phase::NodeModules, || self.resolve_node_modules(target))
+      .and_then(|p| self.normalize(&p))
+  }
+
+  fn normalize(&self, path: &PathBuf) -> Result<PathBuf, ResolutionError> {
+    if self.preserve_symlinks {
+      Ok(normalize_path(path))
+    } else {
+      realpath_cache::realpath(path).map_err(|e| e.into())
+    }
+  }
+
+  /// Resolve a path as the file it names, or a JavaScript path as its
+  /// TypeScript source, e.g. `foo.js` as `foo.ts`, like tsc.
+  fn resolve_specified_file(&self, path: &PathBuf) -> Result<PathBuf, ResolutionError> {
+    if self.is_file(path) {
+      return Ok(path.clone());
+    }
+    let str_path = path
+      .to_str()
+      .ok_or_else(|| ResolutionError::new("Invalid path"))?;
+    for (ext, source_extensions) in TS_SOURCE_EXTENSIONS.iter() {
+      let stem = match str_path.strip_suffix(ext) {
+        Some(stem) => stem,
+        None => continue,
+      };
+      for source_ext in source_extensions.iter() {
+        let source_path = PathBuf::from(format!("{}{}", stem, source_ext));
+        if self.is_file(&source_path) {
+          return Ok(source_path);
+        }
+      }
+    }
+    Err(ResolutionError::new("Not found"))
+  }
+
+  /// Resolve a path as a file. If `path` refers to a file, it is returned;
+  /// otherwise the `path` + each extension is tried.
+  fn resolve_as_file(&self, path: &PathBuf) -> Result<PathBuf, ResolutionError> {
+    // 1. If X is a file, load X as JavaScript text.
+    if let Ok(resolved) = self.resolve_specified_file(path) {
+      return Ok(resolved);
+    }
+
+    // 1. If X.js is a file, load X.js as JavaScript text.
+    // 2. If X.json is a file, parse X.json to a JavaScript object.
+    // 3. If X.node is a file, load X.node as binary addon.
+    let str_path = path
+      .to_str()
+      .ok_or_else(|| ResolutionError::new("Invalid path"))?;
+    for ext in &self.extensions {
+      let ext_path = PathBuf::from(format!("{}{}", str_path, ext));
+      if self.is_file(&ext_path) {
+        return Ok(ext_path);
+      }
+    }
+
+    Err(ResolutionError::new("Not found"))
+  }
+
+  /// Resolve a path as a directory, using the "main" key from a package.json file if it
+  /// exists, or resolving to the index.EXT file if it exists.
+  fn resolve_as_directory(&self, path: &PathBuf) -> Result<PathBuf, ResolutionError> {
+    if !self.resolve_directories {
+      return Err(ResolutionError::new("Not found"));
+    }
+
+    // 1. If X/package.json is a file, use it.
+    let pkg_path = path.join("package.json");
+    if self.has_package_json(&pkg_path) {
+      let main = self.resolve_package_main(&pkg_path);
+      if main.is_ok() {
+        return main;
+      }
+    }
+
+    // 2. LOAD_INDEX(X)
+    self.resolve_index(path)
+  }
+
+  /// Resolve using the package.json "main" key.
+  fn resolve_package_main(&self, pkg_path: &Path) -> Result<PathBuf, ResolutionError> {
+    // TODO how to not always initialise this here?
+    let root = PathBuf::from("/");
+    let pkg_dir = pkg_path.parent().unwrap_or(&root);
+    let pkg = self.read_package(pkg_path)?;
+    if !pkg.is_object() {
+      return Err(ResolutionError::new("package.json is not an object"));
+    }
+
+    let main_field = self
+      .main_fields
+      .iter()
+      .find(|name| pkg[name].is_string())
+      .and_then(|name| {
+        self
+          .trace
+          .step(|| format!("main field \"{}\" of {}", name, pkg_path.display()));
+        pkg[name].as_str()
+      });
+    match main_field {
+      Some(target) => {
+        let path = pkg_dir.join(target);
+        self
+          .resolve_as_file(&path)
+          .or_else(|_| self.resolve_as_directory(&path))
+      }
+      None => Err(ResolutionError::new(
+        "package.json does not contain a \"main\" string",
+      )),
+    }
+  }
+
+  /// Resolve a bare specifier through node_modules dirs only, like Node.js,
+  /// without trying it as a path relative to the base dir first.
+  pub fn resolve_package(&self, target: &str) -> Result<PathBuf, ResolutionError> {
+    if self.is_core_module(target) {
+      return Ok(PathBuf::from(""));
+    }
+    self
+      .timing
+      .measure(Phase::NodeModules, || self.resolve_node_modules(target))
+      .and_then(|p| self.normalize(&p))
+  }
+
+  /// Resolve a package importing itself by its `name`, through the "exports"
+  /// of its package.json in `pkg_dir`.
+  pub fn resolve_self_reference(
+    &self,
+    target: &str,
+    pkg_dir: &Path,
+    name: &str,
+  ) -> Result<PathBuf, ResolutionError> {
+    let is_self = match target.strip_prefix(name) {
+      Some(subpath) => subpath.is_empty() || subpath.starts_with('/'),
+      None => false,
+    };
+    if !is_self {
+      return Err(ResolutionError::new("Not found"));
+    }
+    self
+      .resolve_package_exports(target, &pkg_dir.to_path_buf(), name)
+      .and_then(|p| self.normalize(&p))
+  }
+
+  /// Resolve `target` inside the package `name` in `pkg_dir` as if it were
+  /// in node_modules, e.g. a workspace package that isn't linked there yet.
+  pub fn resolve_in_package(
+    &self,
+    target: &str,
+    pkg_dir: &Path,
+    name: &str,
+  ) -> Result<PathBuf, ResolutionError> {
+    let path = match target.strip_prefix(name) {
+      Some("") => pkg_dir.to_path_buf(),
+      Some(subpath) if subpath.starts_with('/') => pkg_dir.join(&subpath[1..]),
+      _ => return Err(ResolutionError::new("Not found")),
+    };
+    self
+      .resolve_as_file(&path)
+      .or_else(|_| {
+        if !self.resolve_directories {
+          return Err(ResolutionError::new("Not found"));
+        }
+        self.resolve_package_exports(target, &pkg_dir.to_path_buf(), name)
+      })
+      .or_else(|e| match e.kind() {
+        ResolutionErrorKind::NotFound => self.resolve_as_directory(&path),
+        _ => Err(e),
+      })
+      .and_then(|p| self.normalize(&p))
+  }
+
+  // Resolve using package.json "exports" key https://nodejs.org/api/packages.html#exports
+  fn resolve_package_exports(
+    &self,
+    target: &str,
+    pkg_dir: &PathBuf,
+    recurse_target: &str,
+  ) -> Result<PathBuf, ResolutionError> {
+    let _span = tracing::debug_span!("exports", target, package = %pkg_dir.display()).entered();
+    let pkg_path = pkg_dir.join("package.json");
+    if !self.has_package_json(&pkg_path) && recurse_target.contains('/') {
+      let parent_target = Path::new(recurse_target).parent().and_then(|p| p.to_str());
+      if let (Some(parent_dir), Some(parent_target)) = (pkg_dir.parent(), parent_target) {
+        return self.resolve_package_exports(target, &parent_dir.to_path_buf(), parent_target);
+      }
+    }
+
+    let pkg = self.read_package(&pkg_path)?;
+    if !pkg.is_object() {
+      return Err(ResolutionError::new("package.json is not an object"));
+    }
+
+    // Handle string as exports value
+    if let Some(entry) = pkg["exports"].as_str() {
+      let path = pkg_dir.join(entry);
+      return self.resolve_as_file(&path);
+    }
+
+    // Handle string array as exports value
+    if let Some(entries) = pkg["exports"].as_array() {
+      if let Some(entry) = entries.iter().find_map(|v| v.as_str()) {
+        let path = pkg_dir.join(entry);
+        return self.resolve_as_file(&path);
+      }
+    }
+
+    // Handle path map as exports value
+    if let Some(entries) = pkg["exports"].as_object() {
+      let subpaths = entries.keys().filter(|key| key.starts_with('.')).count();
+      if subpaths > 0 && subpaths < entries.len() {
+        self.warn(|| {
+          format!(
+            "Malformed \"exports\" in {}: subpaths and conditions are mixed",
+            pkg_path.display()
+          )
+        });
+      }
+      let search_source = Path::new(target)
+        .strip_prefix(recurse_target)
+        .ok()
+        .and_then(|p| p.to_str())
+        .ok_or_else(|| ResolutionError::new("package.json exports not found"))?;
+
+      // Conditions only, e.g. `{ "import": "./index.mjs" }`, export the "." subpath
+      if !entries.keys().any(|key| key.starts_with('.')) {
+        if search_source.is_empty() {
+          return self.resolve_export_target(pkg_dir, &pkg["exports"], "");
+        }
+        return Err(not_exported());
+      }
+
+      for (path_pattern, dest_path) in entries {
+        let pattern = path_pattern.trim_start_matches('.').trim_start_matches('/');
+        if let Some(star_match) = match_star(pattern, search_source) {
+          tracing::trace!(subpath = path_pattern.as_str(), "exports subpath");
+          self.trace.step(|| {
+            format!(
+              "exports subpath \"{}\" in {}",
+              path_pattern,
+              pkg_dir.display()
+            )
+          });
+          return self.resolve_export_target(pkg_dir, dest_path, star_match);
+        }
+      }
+      return Err(not_exported());
+    }
+
+    if !pkg["exports"].is_null() && !pkg["exports"].is_array() {
+      self.warn(|| {
+        format!(
+          "Malformed \"exports\" in {}: expected a string, array, or object",
+          pkg_path.display()
+        )
+      });
+    }
+    Err(ResolutionError::new("package.json exports not found"))
+  }
+
+  /// Resolve the target of an "exports" entry, picking the first matching
+  /// condition of condition objects and the first resolvable entry of arrays.
+  fn resolve_export_target(
+    &self,
+    pkg_dir: &PathBuf,
+    dest: &Value,
+    star_match: &str,
+  ) -> Result<PathBuf, ResolutionError> {
+    match dest {
+      Value::String(dest_path) => {
+        if !dest_path.starts_with("./") {
+          self.warn(|| {
+            format!(
+              "Malformed \"exports\" target \"{}\" in {}: expected a path starting with \"./\"",
+              dest_path,
+              pkg_dir.join("package.json").display()
+            )
+          });
+        }
+        let path = pkg_dir.join(dest_path.replace("*", star_match));
+        self.resolve_as_file(&path)
+      }
+      Value::Array(entries) => entries
+        .iter()
+        .find_map(|entry| self.resolve_export_target(pkg_dir, entry, star_match).ok())
+        .ok_or_else(not_exported),
+      Value::Object(conditions) => {
+        let matching = conditions.iter().filter(|(condition, _)| {
+          condition.as_str() == "default" || self.condition_names.contains(condition)
+        });
+        for (condition, entry) in matching {
+          tracing::trace!(condition = condition.as_str(), "exports condition");
+          self.trace.step(|| {
+            format!(
+              "exports condition \"{}\" in {}",
+              condition,
+              pkg_dir.display()
+            )
+          });
+          match self.resolve_export_target(pkg_dir, entry, star_match) {
+            Ok(path) => return Ok(path),
+            Err(e) if e.kind() == ResolutionErrorKind::BlockedByExportsNull => return Err(e),
+            Err(_) => continue,
+          }
+        }
+        Err(not_exported())
+      }
+      _ => Err(ResolutionError::with_kind(
+        ResolutionErrorKind::BlockedByExportsNull,
+        "package.json exports target is null",
+      )),
+    }
+  }
+
+  /// Resolve a directory to its index.EXT.
+  fn resolve_index(&self, path: &PathBuf) -> Result<PathBuf, ResolutionError> {
+    // 1. If X/index.js is a file, load X/index.js as JavaScript text.
+    // 2. If X/index.json is a file, parse X/index.json to a JavaScript object.
+    // 3. If X/index.node is a file, load X/index.node as binary addon.
+    for ext in &self.extensions {
+      let ext_path = path.join(format!("index{}", ext));
+      if self.is_file(&ext_path) {
+        return Ok(ext_path);
+      }
+    }
+
+    Err(ResolutionError::new("Not found"))
+  }
+
+  /// Resolve by walking up node_modules folders, or the module dirs set with
+  /// `with_modules`, in their order. Consecutive names are looked up together
+  /// in each dir on the way up.
+  fn resolve_node_modules(&self, target: &str) -> Result<PathBuf, ResolutionError> {
+    let _span = tracing::debug_span!("node_modules", target).entered();
+    let basedir = self.get_basedir()?;
+    let is_absolute = |dir: &String| Path::new(dir).is_absolute();
+    for modules in self
+      .modules
+      .chunk_by(|a, b| is_absolute(a) == is_absolute(b))
+    {
+      let dirs: Vec<PathBuf> = if is_absolute(&modules[0]) {
+        modules.iter().map(PathBuf::from).collect()
+      } else {
+        basedir
+          .ancestors()
+          .flat_map(|dir| modules.iter().map(move |name| dir.join(name)))
+          .collect()
+      };
+      for dir in dirs {
+        match self.resolve_in_modules_dir(&dir, target) {
+          Err(e) if e.kind() == ResolutionErrorKind::NotFound => {}
+          result => return result,
+        }
+      }
+    }
+    Err(ResolutionError::new("Not found"))
+  }
+
+  fn resolve_in_modules_dir(
+    &self,
+    modules_dir: &Path,
+    target: &str,
+  ) -> Result<PathBuf, ResolutionError> {
+    if !package_index::may_contain(modules_dir, target) {
+      return Err(ResolutionError::new("Not found"));
+    }
+    let path = modules_dir.join(target);
+    self
+      .resolve_as_file(&path)
+      // "exports" take precedence over "main" and index files
+      .or_else(|_| {
+        if !self.resolve_directories {
+          return Err(ResolutionError::new("Not found"));
+        }
+        self.resolve_package_exports(target, &path, target)
+      })
+      // Packages hide whatever their "exports" do not expose
+      .or_else(|e| match e.kind() {
+        ResolutionErrorKind::NotFound => self.resolve_as_directory(&path),
+        _ => Err(e),
+      })
+  }
+}
+fn not_exported() -> ResolutionError {
+  ResolutionError::with_kind(
+    ResolutionErrorKind::NotExported,
+    "package.json exports do not match",
+  )
+}
+
+/// Normalize `.` and `..` components without touching the filesystem.
+pub fn normalize_path(p: &Path) -> PathBuf {
+  let mut normalized = PathBuf::from("/");
+  for part in p.components() {
+    match part {
+      PathComponent::Prefix(ref prefix) => {
+        normalized.push(prefix.as_os_str());
+      }
+      PathComponent::RootDir => {
+        normalized.push("/");
+      }
+      PathComponent::ParentDir => {
+        normalized.pop();
+      }
+      PathComponent::CurDir => {
+        // Nothing
+      }
+      PathComponent::Normal(name) => {
+        normalized.push(name);
+      }
+    }
+  }
+  normalized
+}
+
+/// Directory whose declarations list the core modules, relative to the
+/// working directory.
+pub const BUILTINS_DIR: &str = "./node_modules/@types/node";
+
+/// Core modules as of Node.js 22, including subpath modules.
+const BUILTINS: &[&str] = &[
+  "assert",
+  "assert/strict",
+  "async_hooks",
+  "buffer",
+  "child_process",
+  "cluster",
+  "console",
+  "constants",
+  "crypto",
+  "dgram",
+  "diagnostics_channel",
+  "dns",
+  "dns/promises",
+  "domain",
+  "events",
+  "fs",
+  "fs/promises",
+  "http",
+  "http2",
+  "https",
+  "inspector",
+  "inspector/promises",
+  "module",
+  "net",
+  "os",
+  "path",
+  "path/posix",
+  "path/win32",
+  "perf_hooks",
+  "process",
+  "punycode",
+  "querystring",
+  "readline",
+  "readline/promises",
+  "repl",
+  "stream",
+  "stream/consumers",
+  "stream/promises",
+  "stream/web",
+  "string_decoder",
+  "sys",
+  "timers",
+  "timers/promises",
+  "tls",
+  "trace_events",
+  "tty",
+  "url",
+  "util",
+  "util/types",
+  "v8",
+  "vm",
+  "wasi",
+  "worker_threads",
+  "zlib",
+];
+
+/// Core modules only importable with the "node:" prefix.
+const PREFIXED_BUILTINS: &[&str] = &["sea", "sqlite", "test", "test/reporters"];
+
+/// Declaration files in `BUILTINS_DIR` that don't declare a module.
+const NON_MODULE_DECLARATIONS: &[&str] = &["compatibility", "dom-events", "globals", "index"];
+
+// Get the modules declared in `BUILTINS_DIR` of the working dir `cwd`,
+// which may be newer than `BUILTINS`. Read once per dir
+#[cached(
+  type = "SizedCache<PathBuf, Arc<HashSet<String>>>",
+  create = "{ SizedCache::with_size(crate::lru::capacity(16)) }",
+  convert = "{ cwd.clone() }"
+)]
+fn get_declared_builtins(cwd: PathBuf) -> Arc<HashSet<String>> {
+  let entries = match fs::read_dir(cwd.join(BUILTINS_DIR)) {
+    Ok(entries) => entries,
+    Err(_) => return Arc::default(),
+  };
+  let names = entries
+    .filter_map(|entry| {
+      let entry = entry.ok()?;
+      let filename = entry.file_name().into_string().ok()?;
+      let name = if entry.path().is_dir() {
+        filename
+      } else {
+        String::from(filename.strip_suffix(".d.ts")?)
+      };
+      // e.g. "ts5.6" typesVersions dirs and "globals.typedarray.d.ts"
+      if name.contains('.') || NON_MODULE_DECLARATIONS.contains(&name.as_str()) {
+        return None;
+      }
+      Some(name)
+    })
+    .collect();
+  Arc::new(names)
+}
+
+/// Check if a string references a core module, such as "events",
+/// "fs/promises", or "node:test".
+pub fn is_core_module(target: &str) -> bool {
+  is_core_module_in(target, Path::new(""))
+}
+
+/// Check if a string references a core module, also counting those the
+/// Node.js types installed in the working dir `cwd` declare.
+pub fn is_core_module_in(target: &str, cwd: &Path) -> bool {
+  let is_declared = |name: &str| get_declared_builtins(cwd.to_path_buf()).contains(name);
+  match target.strip_prefix("node:") {
+    Some(name) => {
+      BUILTINS.contains(&name) || PREFIXED_BUILTINS.contains(&name) || is_declared(name)
+    }
+    None => BUILTINS.contains(&target) || is_declared(target),
+  }
+}
+
+/// Resolve a node.js module path relative to the current working directory.
+/// Returns the absolute path to the module, or an error.
+///
+/// ```rust,no_run
+/// # use ts_import_resolver::node_resolve::resolve;
+/// match resolve("./lib") {
+///     Ok(path) => println!("Path is: {:?}", path),
+///     Err(err) => panic!("Failed: {:?}", err),
+/// }
+/// ```
+pub fn resolve(target: &str) -> Result<PathBuf, ResolutionError> {
+  Resolver::new()
+    .with_basedir(PathBuf::from("."))
+    .resolve(target)
+}
+
+/// Resolve a node.js module path relative to `basedir`.
+/// Returns the absolute path to the module, or an error.
+///
+/// ```rust,no_run
+/// # use std::env;
+/// # use ts_import_resolver::node_resolve::resolve_from;
+/// match resolve_from("./index.js", env::current_dir().unwrap()) {
+///     Ok(path) => println!("Path is: {:?}", path),
+///     Err(err) => panic!("Failed: {:?}", err),
+/// }
+/// ```
+pub fn resolve_from(target: &str, basedir: PathBuf) -> Result<PathBuf, ResolutionError> {
+  Resolver::new().with_basedir(basedir).resolve(target)
+}
+
+// Is source path match the tsConfig pattern
+// Parse a package.json file. Cached by path, modification time, and size,
+// so "main" and "exports" lookups share one parse per package
+#[cached(
+  type = "SizedCache<(PathBuf, (SystemTime, u64)), Option<Arc<Value>>>",
+  create = "{ SizedCache::with_size(crate::lru::capacity(1024)) }",
+  convert = "{ (pkg_path.clone(), _stamp) }"
+)]
+fn parse_package_json(pkg_path: PathBuf, _stamp: (SystemTime, u64)) -> Option<Arc<Value>> {
+  let file = File::open(pkg_path).ok()?;
+  serde_json::from_reader(file).ok().map(Arc::new)
+}
+
+/// Read and parse a package.json file, reusing the parsed document while the
+/// file is unchanged.
+pub fn read_package_json(pkg_path: &Path) -> Result<Arc<Value>, ResolutionError> {
+  let metadata = fs::metadata(pkg_path)?;
+  let stamp = (metadata.modified()?, metadata.len());
+  parse_package_json(pkg_path.to_path_buf(), stamp)
+    .ok_or_else(|| ResolutionError::new("Json parse error"))
+}
+
+/// Get the dir and package.json of the package a file belongs to, the
+/// nearest package.json with a name, without looking above `node_modules`.
+pub fn get_enclosing_package(path: &Path) -> Option<(PathBuf, Arc<Value>)> {
+  for dir in path.ancestors().skip(1) {
+    if dir.ends_with("node_modules") {
+      break;
+    }
+    let pkg = read_package_json(&dir.join("package.json")).ok();
+    if let Some(pkg) = pkg.filter(|pkg| pkg["name"].is_string()) {
+      return Some((dir.to_path_buf(), pkg));
+    }
+  }
+  None
+}
+
+/// Check if a path goes through a node_modules dir.
+pub fn is_in_node_modules(path: &Path) -> bool {
+  path
+    .components()
+    .any(|part| part.as_os_str() == "node_modules")
+}
+
+/// Get hits, misses, and size of the package.json cache.
+pub fn package_json_cache_stats() -> (u64, u64, usize) {
+  let cache = PARSE_PACKAGE_JSON.lock().unwrap();
+  (
+    cache.cache_hits().unwrap_or(0),
+    cache.cache_misses().unwrap_or(0),
+    cache.cache_size(),
+  )
+}
+
+/// Get the parsed package.json files with the stamps they were parsed at.
+pub fn package_json_entries() -> Vec<(PathBuf, (SystemTime, u64), Arc<Value>)> {
+  let cache = PARSE_PACKAGE_JSON.lock().unwrap();
+  cache
+    .key_order()
+    .zip(cache.value_order())
+    .filter_map(|((pkg_path, stamp), pkg)| Some((pkg_path.clone(), *stamp, pkg.clone()?)))
+    .collect()
+}
+
+/// Seed the package.json cache, e.g. with files parsed by an earlier process.
+/// Used only while the file still has `stamp`.
+pub fn insert_package_json(pkg_path: PathBuf, stamp: (SystemTime, u64), pkg: Value) {
+  PARSE_PACKAGE_JSON
+    .lock()
+    .unwrap()
+    .cache_set((pkg_path, stamp), Some(Arc::new(pkg)));
+}
+
+pub fn clear_package_json_cache() {
+  PARSE_PACKAGE_JSON.lock().unwrap().cache_clear();
+}
+
+/// Match `search` against a pattern with at most one "*", getting what the
+/// "*" stands for. Patterns without "*" only match themselves, standing for "".
+pub fn match_star<'a>(pattern: &str, search: &'a str) -> Option<&'a str> {
+  if search.len() < pattern.len() {
+    return None;
+  }
+
+  if pattern == "*" {
+    return Some(search);
+  }
+
+  if search == pattern {
+    return Some("");
+  }
+
+  let (prefix, suffix) = pattern.split_once('*')?;
+  search.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::env;
+  use std::path::PathBuf;
+
+  fn fixture(part: &str) -> PathBuf {
+    env::current_dir().unwrap().join("fixtures").join(part)
+  }
+  fn resolve_fixture(target: &str) -> PathBuf {
+    resolve_from(target, fixture("")).unwrap()
+  }
+
+  #[test]
+  fn appends_extensions() {
+    assert_eq!(
+      fixture("extensions/js-file.js"),
+      resolve_fixture("./extensions/js-file")
+    );
+    assert_eq!(
+      fixture("extensions/json-file.json"),
+      resolve_fixture("./extensions/json-file")
+    );
+    assert_eq!(
+      fixture("extensions/native-file.node"),
+      resolve_fixture("./extensions/native-file")
+    );
+    assert_eq!(
+      fixture("extensions/other-file.ext"),
+      resolve_fixture("./extensions/other-file.ext")
+    );
+    assert_eq!(
+      fixture("extensions/no-ext"),
+      resolve_fixture("./extensions/no-ext")
+    );
+    assert_eq!(
+      fixture("extensions/other-file.ext"),
+      Resolver::new()
+        .with_extensions(&[".ext"])
+        .with_basedir(fixture(""))
+        .resolve("./extensions/other-file")
+        .unwrap()
+    );
+    assert_eq!(
+      fixture("extensions/module.mjs"),
+      Resolver::new()
+        .with_extensions(&[".mjs"])
+        .with_basedir(fixture(""))
+        .resolve("./extensions/module")
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn resolves_package_json() {
+    assert_eq!(
+      fixture("package-json/main-file/whatever.js"),
+      resolve_fixture("./package-json/main-file")
+    );
+    assert_eq!(
+      fixture("package-json/main-file-noext/whatever.js"),
+      resolve_fixture("./package-json/main-file-noext")
+    );
+    assert_eq!(
+      fixture("package-json/main-dir/subdir/index.js"),
+      resolve_fixture("./package-json/main-dir")
+    );
+    assert_eq!(
+      fixture("package-json/not-object/index.js"),
+      resolve_fixture("./package-json/not-object")
+    );
+    assert_eq!(
+      fixture("package-json/invalid/index.js"),
+      resolve_fixture("./package-json/invalid")
+    );
+    assert_eq!(
+      fixture("package-json/main-none/index.js"),
+      resolve_fixture("./package-json/main-none")
+    );
+    assert_eq!(
+      fixture("package-json/main-file/whatever.js"),
+      Resolver::new()
+        .with_main_fields(&["module", "main"])
+        .with_basedir(fixture(""))
+        .resolve("./package-json/main-file")
+        .unwrap()
+    );
+    assert_eq!(
+      fixture("package-json/module/index.mjs"),
+      Resolver::new()
+        .with_extensions(&[".mjs", ".js"])
+        .with_main_fields(&["module", "main"])
+        .with_basedir(fixture(""))
+        .resolve("./package-json/module")
+        .unwrap()
+    );
+    assert_eq!(
+      fixture("package-json/module-main/main.mjs"),
+      Resolver::new()
+        .with_extensions(&[".mjs", ".js"])
+        .with_main_fields(&["module", "main"])
+        .with_basedir(fixture(""))
+        .resolve("./package-json/module-main")
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn resolves_node_modules() {
+    assert_eq!(
+      fixture("node-modules/same-dir/node_modules/a.js"),
+      resolve_from("a", fixture("node-modules/same-dir")).unwrap()
+    );
+    assert_eq!(
+      fixture("node-modules/parent-dir/node_modules/a/index.js"),
+      resolve_from("a", fixture("node-modules/parent-dir/src")).unwrap()
+    );
+    assert_eq!(
+      fixture("node-modules/package-json/node_modules/dep/lib/index.js"),
+      resolve_from("dep", fixture("node-modules/package-json")).unwrap()
+    );
+    assert_eq!(
+      fixture("node-modules/walk/src/node_modules/not-ok/index.js"),
+      resolve_from("not-ok", fixture("node-modules/walk/src")).unwrap()
+    );
+    assert_eq!(
+      fixture("node-modules/walk/node_modules/ok/index.js"),
+      resolve_from("ok", fixture("node-modules/walk/src")).unwrap()
+    );
+  }
+
+  #[test]
+  fn skips_directories() {
+    assert_eq!(
+      fixture("extensions/other-file.ext"),
+      Resolver::new()
+        .resolve_directories(false)
+        .with_basedir(fixture(""))
+        .resolve("./extensions/other-file.ext")
+        .unwrap()
+    );
+    assert!(Resolver::new()
+      .resolve_directories(false)
+      .with_basedir(fixture(""))
+      .resolve("./package-json/main-file")
+      .is_err());
+  }
+
+  #[test]
+  fn preserves_symlinks() {
+    assert_eq!(
+      fixture("symlink/node_modules/dep/main.js"),
+      Resolver::new()
+        .preserve_symlinks(true)
+        .with_basedir(fixture("symlink"))
+        .resolve("dep")
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn does_not_preserve_symlinks() {
+    assert_eq!(
+      fixture("symlink/linked/main.js"),
+      Resolver::new()
+        .preserve_symlinks(false)
+        .with_basedir(fixture("symlink"))
+        .resolve("dep")
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn resolves_absolute_specifier() {
+    let full_path = fixture("extensions/js-file");
+    let id = full_path.to_str().unwrap();
+    assert_eq!(fixture("extensions/js-file.js"), resolve(id).unwrap());
+  }
+
+  #[test]
+  fn core_modules() {
+    assert!(is_core_module("events"));
+    assert!(!is_core_module("events/"));
+    assert!(!is_core_module("./events"));
+    assert!(is_core_module("stream"));
+    assert!(!is_core_module("acorn"));
+    assert!(is_core_module("node:events"));
+    assert!(!is_core_module("node:acorn"));
+    assert!(is_core_module("fs/promises"));
+    assert!(!is_core_module("fs/missing"));
+    assert!(is_core_module("node:test"));
+    assert!(!is_core_module("test"));
+  }
+
+  #[test]
+  fn matches_star_patterns() {
+    assert_eq!(Some("foo/bar"), match_star("@/*", "@/foo/bar"));
+    assert_eq!(Some("foo"), match_star("lib/*.js", "lib/foo.js"));
+    assert_eq!(Some(""), match_star("exact", "exact"));
+    assert_eq!(Some("anything"), match_star("*", "anything"));
+    assert_eq!(None, match_star("lib/*.js", "lib/.js"));
+    assert_eq!(None, match_star("lib/*", "src/foo"));
+    assert_eq!(None, match_star("exact", "exactly"));
+  }
+}
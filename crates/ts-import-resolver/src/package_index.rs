@@ -0,0 +1,107 @@
+//! Index what each node_modules dir contains, so walking up to find a bare
+//! import costs one hash lookup per level instead of a `stat` per candidate.
+//! Scoped packages are listed as "@scope/name", and symlinked entries, e.g.
+//! pnpm and workspace links, are listed like any other.
+//!
+//! ```rust
+//! use std::path::Path;
+//! use ts_import_resolver::package_index::may_contain;
+//!
+//! may_contain(Path::new("/repo/node_modules"), "lodash/fp");
+//! // → lists /repo/node_modules once, then true if it has "lodash" or e.g. "lodash.js"
+//! may_contain(Path::new("/repo/node_modules"), "@types/node");
+//! // → true if /repo/node_modules/@types has "node"
+//! ```
+
+use crate::lru::{self, LruMap};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+fn indexes() -> &'static LruMap<PathBuf, Arc<HashSet<String>>> {
+  static INDEXES: OnceLock<LruMap<PathBuf, Arc<HashSet<String>>>> = OnceLock::new();
+  INDEXES.get_or_init(|| LruMap::new(lru::capacity(1_000)))
+}
+
+// Add an entry under its full name and each name a file could be imported
+// by, e.g. "foo.d.ts" as "foo", "foo.d", and "foo.d.ts"
+fn add_entry(names: &mut HashSet<String>, name: &str) {
+  for (index, _) in name.match_indices('.').filter(|(index, _)| *index > 0) {
+    names.insert(String::from(&name[..index]));
+  }
+  names.insert(String::from(name));
+}
+
+fn list(node_modules: &Path) -> HashSet<String> {
+  let mut names = HashSet::new();
+  let entries = match fs::read_dir(node_modules) {
+    Ok(entries) => entries,
+    Err(_) => return names,
+  };
+  for entry in entries.filter_map(|entry| entry.ok()) {
+    let name = entry.file_name().to_string_lossy().into_owned();
+    if !name.starts_with('@') {
+      add_entry(&mut names, name.as_str());
+      continue;
+    }
+    let scoped = match fs::read_dir(entry.path()) {
+      Ok(scoped) => scoped,
+      Err(_) => continue,
+    };
+    for scoped_entry in scoped.filter_map(|entry| entry.ok()) {
+      let scoped_name = scoped_entry.file_name().to_string_lossy().into_owned();
+      add_entry(&mut names, format!("{}/{}", name, scoped_name).as_str());
+    }
+  }
+  names
+}
+
+fn get_index(node_modules: &Path) -> Arc<HashSet<String>> {
+  let cached = indexes().get(node_modules);
+  if let Some(index) = cached {
+    return index;
+  }
+  // List without holding the entry, other threads may index other dirs
+  let index = Arc::new(list(node_modules));
+  indexes().insert(node_modules.to_path_buf(), index.clone());
+  index
+}
+
+/// Get the name of the package `target` imports from, e.g. "@scope/name" of
+/// "@scope/name/sub/path".
+pub fn get_package_name(target: &str) -> &str {
+  let segments = if target.starts_with('@') { 2 } else { 1 };
+  match target.match_indices('/').nth(segments - 1) {
+    Some((index, _)) => &target[..index],
+    None => target,
+  }
+}
+
+/// Check if `target` may resolve inside `node_modules`. False means it
+/// certainly does not, including when `node_modules` does not exist.
+pub fn may_contain(node_modules: &Path, target: &str) -> bool {
+  get_index(node_modules).contains(get_package_name(target))
+}
+
+/// List `dir`'s node_modules dir and those of its ancestors ahead of the
+/// first resolution.
+pub fn index_ancestors(dir: &Path) {
+  for ancestor in dir.ancestors() {
+    get_index(&ancestor.join("node_modules"));
+  }
+}
+
+pub fn clear() {
+  indexes().clear();
+}
+
+/// Drop the listings of the node_modules dirs `path` is in, if any.
+pub fn invalidate(path: &Path) {
+  for dir in path
+    .ancestors()
+    .filter(|ancestor| ancestor.ends_with("node_modules"))
+  {
+    indexes().remove(dir);
+  }
+}
@@ -0,0 +1,217 @@
+//! Match specifiers against tsconfig `paths` patterns in one walk over the
+//! specifier, instead of trying every pattern per import. Patterns are kept
+//! in a trie by the part before their "*".
+//!
+//! ```rust
+//! use ts_import_resolver::PathsMatcher;
+//!
+//! let matcher = PathsMatcher::new(vec![
+//!   (String::from("@app/*"), vec![String::from("src/*")]),
+//!   (String::from("@app/legacy/*"), vec![String::from("legacy/*")]),
+//!   (String::from("jquery"), vec![String::from("vendor/jquery")]),
+//! ]);
+//! matcher.find_matches("@app/legacy/foo");
+//! // → "@app/legacy/*" with "*" standing for "foo", then "@app/*" with "*"
+//! //   standing for "legacy/foo"
+//! ```
+
+use std::collections::HashMap;
+
+/// A pattern matching a specifier, with what its "*" stands for, `None` for
+/// patterns without "*", whose targets are used as they are.
+pub struct PathsMatch<'m, 's> {
+  pub pattern: &'m str,
+  pub targets: &'m [String],
+  pub star_match: Option<&'s str>,
+}
+
+struct Pattern {
+  pattern: String,
+  // what follows the "*", `None` for patterns without one
+  suffix: Option<String>,
+  targets: Vec<String>,
+}
+
+#[derive(Default)]
+struct Node {
+  children: HashMap<u8, Node>,
+  // patterns whose prefix ends here
+  patterns: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct PathsMatcher {
+  root: Node,
+  patterns: Vec<Pattern>,
+}
+
+impl PathsMatcher {
+  /// Build a matcher for `paths` in declaration order, which breaks ties
+  /// between equally specific patterns.
+  pub fn new(paths: Vec<(String, Vec<String>)>) -> Self {
+    let mut matcher = PathsMatcher::default();
+    for (pattern, targets) in paths {
+      let (prefix, suffix) = match pattern.split_once('*') {
+        Some((prefix, suffix)) => (prefix.to_string(), Some(suffix.to_string())),
+        None => (pattern.clone(), None),
+      };
+      let node = prefix.bytes().fold(&mut matcher.root, |node, byte| {
+        node.children.entry(byte).or_default()
+      });
+      node.patterns.push(matcher.patterns.len());
+      matcher.patterns.push(Pattern {
+        pattern,
+        suffix,
+        targets,
+      });
+    }
+    matcher
+  }
+
+  /// Get the patterns matching `specifier` in the order tsc prefers them:
+  /// the longest prefix before the "*" first, patterns without "*" before
+  /// all others, then declaration order. Like `match_star`, a "*" stands for
+  /// at least one character.
+  pub fn find_matches<'m, 's>(&'m self, specifier: &'s str) -> Vec<PathsMatch<'m, 's>> {
+    let mut nodes = vec![(&self.root, specifier)];
+    let mut node = &self.root;
+    for (index, byte) in specifier.bytes().enumerate() {
+      node = match node.children.get(&byte) {
+        Some(node) => node,
+        None => break,
+      };
+      // Patterns end on char boundaries, so no pattern ends inside a char
+      if specifier.is_char_boundary(index + 1) {
+        nodes.push((node, &specifier[index + 1..]));
+      }
+    }
+
+    let mut matches = vec![];
+    for (node, rest) in nodes.into_iter().rev() {
+      self.collect(node, rest, &mut matches);
+    }
+    matches
+  }
+
+  fn collect<'m, 's>(
+    &'m self,
+    node: &'m Node,
+    rest: &'s str,
+    matches: &mut Vec<PathsMatch<'m, 's>>,
+  ) {
+    for &index in node.patterns.iter() {
+      let pattern = &self.patterns[index];
+      let star_match = match &pattern.suffix {
+        None if rest.is_empty() => None,
+        None => continue,
+        Some(suffix) if rest.len() > suffix.len() => match rest.strip_suffix(suffix.as_str()) {
+          Some(star_match) => Some(star_match),
+          None => continue,
+        },
+        Some(_) => continue,
+      };
+      matches.push(PathsMatch {
+        pattern: pattern.pattern.as_str(),
+        targets: pattern.targets.as_slice(),
+        star_match,
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn find(paths: &[(&str, &str)], specifier: &str) -> Vec<(String, String)> {
+    let paths = paths
+      .iter()
+      .map(|(pattern, target)| (pattern.to_string(), vec![target.to_string()]))
+      .collect();
+    PathsMatcher::new(paths)
+      .find_matches(specifier)
+      .iter()
+      .map(|found| {
+        let star_match = found.star_match.unwrap_or("(exact)");
+        (found.pattern.to_string(), star_match.to_string())
+      })
+      .collect()
+  }
+
+  #[test]
+  fn matches_longest_prefix_first() {
+    let paths = [
+      ("@app/*", "src/*"),
+      ("@app/legacy/*", "legacy/*"),
+      ("lib/*.js", "lib/*"),
+      ("jquery", "vendor/jquery"),
+      ("*", "types/*"),
+    ];
+    let found = |specifier| find(&paths, specifier);
+    assert_eq!(
+      vec![
+        ("@app/legacy/*".to_string(), "foo".to_string()),
+        ("@app/*".to_string(), "legacy/foo".to_string()),
+        ("*".to_string(), "@app/legacy/foo".to_string()),
+      ],
+      found("@app/legacy/foo")
+    );
+    assert_eq!(
+      vec![
+        ("lib/*.js".to_string(), "foo".to_string()),
+        ("*".to_string(), "lib/foo.js".to_string()),
+      ],
+      found("lib/foo.js")
+    );
+    assert_eq!(
+      vec![("*".to_string(), "lib/.js".to_string())],
+      found("lib/.js")
+    );
+    assert_eq!(
+      vec![
+        ("jquery".to_string(), "(exact)".to_string()),
+        ("*".to_string(), "jquery".to_string()),
+      ],
+      found("jquery")
+    );
+    assert_eq!(
+      vec![("*".to_string(), "jqueryx".to_string())],
+      found("jqueryx")
+    );
+    assert!(found("").is_empty());
+  }
+
+  #[test]
+  fn breaks_ties_in_declaration_order() {
+    let found = |paths: &[(&str, &str)]| find(paths, "lib/foo.js");
+    assert_eq!(
+      vec!["lib/*", "lib/*.js"],
+      found(&[("lib/*", "a/*"), ("lib/*.js", "b/*")])
+        .into_iter()
+        .map(|(pattern, _)| pattern)
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+      vec!["lib/*.js", "lib/*"],
+      found(&[("lib/*.js", "b/*"), ("lib/*", "a/*")])
+        .into_iter()
+        .map(|(pattern, _)| pattern)
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn matches_non_ascii_patterns() {
+    let paths = [("é/*", "accents/*"), ("日本/*", "ja/*"), ("ü", "vendor/u")];
+    let found = |specifier| find(&paths, specifier);
+    assert_eq!(vec![("é/*".to_string(), "foo".to_string())], found("é/foo"));
+    assert!(found("è/foo").is_empty());
+    assert_eq!(
+      vec![("日本/*".to_string(), "語".to_string())],
+      found("日本/語")
+    );
+    assert!(found("日曜/foo").is_empty());
+    assert_eq!(vec![("ü".to_string(), "(exact)".to_string())], found("ü"));
+    assert!(found("ű").is_empty());
+  }
+}
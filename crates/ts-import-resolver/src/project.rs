@@ -0,0 +1,974 @@
+//! Resolve imports the way TypeScript does for a project: through the
+//! `paths` and `baseUrl` of its tsconfig files, read through `extends`, then
+//! like Node.js, plus the workspace lookups of monorepos. The ESLint resolver
+//! resolves every import through `ProjectResolver`, with its ESLint-only
+//! options, e.g. aliases and fallbacks, around it.
+//!
+//! ```rust
+//! use std::fs;
+//! use ts_import_resolver::{ProjectOptions, ProjectResolver, ResolveOutcome};
+//!
+//! # let dir = std::env::temp_dir().join("ts-import-resolver-doc-project");
+//! # fs::create_dir_all(dir.join("src/utils")).unwrap();
+//! # fs::write(dir.join("src/utils/index.ts"), "").unwrap();
+//! # let dir = dir.canonicalize().unwrap();
+//! fs::write(
+//!   dir.join("tsconfig.json"),
+//!   r#"{ "compilerOptions": { "paths": { "@app/*": ["./src/*"] } } }"#,
+//! )
+//! .unwrap();
+//! let resolver = ProjectResolver::new(ProjectOptions {
+//!   cwd: dir.clone(),
+//!   ..ProjectOptions::default()
+//! });
+//! let resolution = match resolver.resolve("@app/utils", &dir.join("src/index.ts")) {
+//!   ResolveOutcome::Found(resolution) => resolution,
+//!   outcome => panic!("{:?}", outcome),
+//! };
+//! assert_eq!(resolution.path, dir.join("src/utils/index.ts"));
+//! assert_eq!(resolution.matched_pattern.as_deref(), Some("@app/*"));
+//! ```
+
+use crate::jsonc;
+use crate::node_resolve::{
+  get_enclosing_package, is_in_node_modules, normalize_path, ResolutionError, Resolver,
+};
+use crate::package_index;
+use crate::paths_matcher::PathsMatcher;
+use crate::realpath_cache;
+use crate::runtime::Runtime;
+use crate::timing::Phase;
+use crate::workspace;
+use serde_json::{Map, Value};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// Probe order of extensionless imports by default.
+pub const DEFAULT_EXTENSIONS: &[&str] = &[
+  ".js", ".json", ".node", ".mjs", ".cjs", ".ts", ".tsx", ".d.ts",
+];
+
+/// package.json fields naming a package's entry by default, typings first.
+pub const DEFAULT_MAIN_FIELDS: &[&str] = &[
+  "types",
+  "typings",
+  // APF: https://angular.io/guide/angular-package-format
+  "fesm2020",
+  "fesm2015",
+  "esm2020",
+  "es2020",
+  "module",
+  "jsnext:main",
+  "main",
+];
+
+/// "exports" conditions matched by default, typings first.
+pub const DEFAULT_CONDITION_NAMES: &[&str] = &[
+  "types",
+  "import",
+  // APF: https://angular.io/guide/angular-package-format
+  "esm2020",
+  "es2020",
+  "es2015",
+  "require",
+  "node",
+  "node-addons",
+  "browser",
+];
+
+/// How imports are looked up besides tsconfig `paths` and node_modules.
+#[derive(Clone, Debug)]
+pub struct LookupOptions {
+  /// Whether `paths` go before node_modules, like tsc, else after, so
+  /// packages shadow same-named aliases.
+  pub prefer_ts_paths: bool,
+  /// Whether bare imports try sibling files first, e.g. "utils" as "./utils".
+  pub prefer_relative: bool,
+  /// Whether bare imports fall back to their `@types` package.
+  pub always_try_types: bool,
+  /// Extensions probed for `paths` targets and aliases, those of the
+  /// resolver if `None`.
+  pub paths_extensions: Option<Vec<String>>,
+  /// Whether workspace package names map to the `source`, else `main`,
+  /// entry of their package.json.
+  pub workspace_aliases: bool,
+  /// The dir under the base dir "~/" and "@/" map to, if any.
+  pub src_alias: Option<String>,
+}
+
+impl Default for LookupOptions {
+  fn default() -> Self {
+    LookupOptions {
+      prefer_ts_paths: true,
+      prefer_relative: false,
+      always_try_types: true,
+      paths_extensions: None,
+      workspace_aliases: false,
+      src_alias: None,
+    }
+  }
+}
+
+/// Where and how to resolve through tsconfig files.
+#[derive(Clone, Debug)]
+pub struct ProjectOptions {
+  /// The tsconfig files to resolve with, in order. Without any, the
+  /// tsconfig.json of `cwd`, if there is one.
+  pub projects: Vec<PathBuf>,
+  /// The dir relative `projects` are relative to, the current dir by default.
+  pub cwd: PathBuf,
+  pub extensions: Vec<String>,
+  pub main_fields: Vec<String>,
+  pub condition_names: Vec<String>,
+  pub runtime: Runtime,
+  pub lookup: LookupOptions,
+}
+
+impl Default for ProjectOptions {
+  fn default() -> Self {
+    let to_strings = |values: &[&str]| values.iter().map(|value| String::from(*value)).collect();
+    ProjectOptions {
+      projects: vec![],
+      cwd: env::current_dir().unwrap_or_default(),
+      extensions: to_strings(DEFAULT_EXTENSIONS),
+      main_fields: to_strings(DEFAULT_MAIN_FIELDS),
+      condition_names: to_strings(DEFAULT_CONDITION_NAMES),
+      runtime: Runtime::default(),
+      lookup: LookupOptions::default(),
+    }
+  }
+}
+
+/// A file an import resolved to.
+#[derive(Debug, PartialEq)]
+pub struct Resolution {
+  pub path: PathBuf,
+  /// The tsconfig file it was resolved with, `None` without one.
+  pub project: Option<PathBuf>,
+  /// The `paths` pattern or alias that mapped the import, if any.
+  pub matched_pattern: Option<String>,
+}
+
+/// How a resolution through tsconfig files ended.
+#[derive(Debug)]
+pub enum ResolveOutcome {
+  /// The file the import resolves to.
+  Found(Resolution),
+  /// A core module of the runtime, e.g. "fs".
+  Builtin,
+  /// No file was found, with why Node.js resolution failed.
+  NotFound(ResolutionError),
+}
+
+/// A way `ProjectResolver` looks imports up, in the order it tries them with
+/// `prefer_ts_paths`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+  Paths,
+  WorkspaceAlias,
+  Relative,
+  WorkspaceDependency,
+  Node,
+  WorkspacePackage,
+  SrcAlias,
+  Types,
+}
+
+impl Strategy {
+  /// Get the name of the strategy, e.g. "workspaceAlias".
+  pub fn name(&self) -> &'static str {
+    match self {
+      Strategy::Paths => "paths",
+      Strategy::WorkspaceAlias => "workspaceAlias",
+      Strategy::Relative => "relative",
+      Strategy::WorkspaceDependency => "workspaceDependency",
+      Strategy::Node => "node",
+      Strategy::WorkspacePackage => "workspacePackage",
+      Strategy::SrcAlias => "srcAlias",
+      Strategy::Types => "types",
+    }
+  }
+}
+
+/// What one strategy resolved an import to in one project.
+#[derive(Debug)]
+pub struct Attempt {
+  pub project: Option<PathBuf>,
+  pub strategy: Strategy,
+  pub resolved: Result<PathBuf, ResolutionError>,
+}
+
+/// A tsconfig file, with the `baseUrl` and `paths` it sets or inherits.
+pub struct Project {
+  path: PathBuf,
+  base_dir: PathBuf,
+  paths: Vec<(String, Vec<String>)>,
+  paths_base_dir: PathBuf,
+  // the config setting the `paths` this one inherits as they are
+  inherits_paths_of: Option<PathBuf>,
+  invalid_paths_entries: Vec<String>,
+  paths_matcher: OnceLock<Arc<PathsMatcher>>,
+}
+
+impl Project {
+  /// Read the tsconfig file at `path` and the files it extends, `None` if it
+  /// doesn't exist.
+  pub fn load(path: &Path) -> Option<Self> {
+    if !path.is_file() {
+      return None;
+    }
+    let base_url = get_compiler_dir_option(path, "baseUrl");
+    let paths = find_raw_compiler_option(path, "paths", &mut vec![]);
+    Some(Project::new(path, base_url, paths))
+  }
+
+  /// Read tsconfig content standing for a tsconfig file at `path`, e.g. one
+  /// given inline. Its `extends` aren't followed.
+  pub fn parse(path: &Path, config: &Value) -> Self {
+    let ts_config_dir = path.parent().unwrap_or(Path::new(""));
+    let compiler_options = &config["compilerOptions"];
+    let base_url = compiler_options["baseUrl"].as_str().map(|base_url| {
+      normalize_path(&ts_config_dir.join(expand_config_dir(base_url, ts_config_dir)))
+    });
+    let paths = compiler_options
+      .get("paths")
+      .map(|paths| (paths.clone(), path.to_path_buf()));
+    Project::new(path, base_url, paths)
+  }
+
+  fn new(path: &Path, base_url: Option<PathBuf>, paths: Option<(Value, PathBuf)>) -> Self {
+    let ts_config_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let (raw_paths, defined_in) = match paths {
+      Some((Value::Object(paths), defined_in)) => (paths, Some(defined_in)),
+      _ => (Map::new(), None),
+    };
+    let mut invalid_paths_entries = vec![];
+    let paths = expand_paths(&raw_paths, &ts_config_dir, &mut invalid_paths_entries);
+    // `paths` targets are relative to `baseUrl`, else to the config setting them
+    let paths_base_dir = match (&base_url, &defined_in) {
+      (Some(base_url), _) => base_url.clone(),
+      (None, Some(defined_in)) => defined_in.parent().unwrap_or(Path::new("")).to_path_buf(),
+      (None, None) => ts_config_dir.clone(),
+    };
+    // `${configDir}` expands to the dir of each config using the `paths`
+    let uses_config_dir = Value::Object(raw_paths)
+      .to_string()
+      .contains("${configDir}");
+    let inherits_paths_of = defined_in.filter(|defined_in| defined_in != path && !uses_config_dir);
+    Project {
+      path: path.to_path_buf(),
+      base_dir: base_url.unwrap_or(ts_config_dir),
+      paths,
+      paths_base_dir,
+      inherits_paths_of,
+      invalid_paths_entries,
+      paths_matcher: OnceLock::new(),
+    }
+  }
+
+  /// Get the path of the tsconfig file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Get the dir bare imports are looked up from: the `baseUrl`, relative to
+  /// the config setting it, else the tsconfig file's dir.
+  pub fn base_dir(&self) -> &Path {
+    &self.base_dir
+  }
+
+  /// Get the `paths` in declaration order, with `${configDir}` expanded.
+  pub fn paths(&self) -> &[(String, Vec<String>)] {
+    &self.paths
+  }
+
+  /// Get the dir `paths` targets are relative to, like tsc: the `baseUrl` if
+  /// any, else the dir of the config setting `paths`.
+  pub fn paths_base_dir(&self) -> &Path {
+    &self.paths_base_dir
+  }
+
+  /// Get the `paths` patterns and targets left out for having more than one
+  /// "*", like tsc does.
+  pub fn invalid_paths_entries(&self) -> &[String] {
+    &self.invalid_paths_entries
+  }
+
+  /// Get the tsconfig file setting the `paths` this one inherits as they
+  /// are, whose matcher `share_paths_matcher` can reuse.
+  pub fn inherits_paths_of(&self) -> Option<&Path> {
+    self.inherits_paths_of.as_deref()
+  }
+
+  /// Reuse the `paths` matcher of the project this one inherits `paths` of,
+  /// e.g. so the apps and libs of an Nx workspace extending one
+  /// tsconfig.base.json don't each build one. Does nothing once this
+  /// project's matcher is built.
+  pub fn share_paths_matcher(&self, other: &Project) {
+    if self.inherits_paths_of() == Some(other.path()) {
+      let _ = self.paths_matcher.set(other.get_paths_matcher().clone());
+    }
+  }
+
+  /// Get the matcher of `paths`, built on first use.
+  pub fn paths_matcher(&self) -> &PathsMatcher {
+    self.get_paths_matcher()
+  }
+
+  fn get_paths_matcher(&self) -> &Arc<PathsMatcher> {
+    self
+      .paths_matcher
+      .get_or_init(|| Arc::new(PathsMatcher::new(self.paths.clone())))
+  }
+
+  /// Resolve a `paths` target, its "*" replaced, relative to the paths base
+  /// dir. Bare targets, e.g. "lodash" for "lodash-es", may name a package.
+  pub fn resolve_paths_target(
+    &self,
+    target: &str,
+    resolver: &Resolver,
+  ) -> Result<PathBuf, ResolutionError> {
+    // Targets may leave the base dir, e.g. "../shared/src/*" in monorepos
+    let path = normalize_path(&self.paths_base_dir.join(target));
+    let resolver = resolver.with_basedir(self.paths_base_dir.clone());
+    let resolved = resolver.resolve(path.to_string_lossy().as_ref());
+    match resolved {
+      Err(_) if is_bare_specifier(target) => resolver.resolve_package(target),
+      resolved => resolved,
+    }
+  }
+
+  /// Resolve the targets of the `paths` patterns matching `specifier`, the
+  /// most specific pattern first, with the patterns they are targets of.
+  /// Only the first that exists, unless `all`.
+  pub fn resolve_paths(
+    &self,
+    specifier: &str,
+    resolver: &Resolver,
+    all: bool,
+  ) -> Vec<(String, PathBuf)> {
+    let trace = resolver.get_trace();
+    let _span = tracing::debug_span!("paths", specifier, project = %self.path.display()).entered();
+    let mut resolutions = vec![];
+    resolver.get_timing().measure(Phase::Paths, || {
+      for paths_match in self.paths_matcher().find_matches(specifier) {
+        let pattern = paths_match.pattern;
+        tracing::debug!(pattern, "paths pattern");
+        trace.step(|| format!("paths pattern \"{}\"", pattern));
+        for target in paths_match.targets.iter() {
+          trace.step(|| format!("paths target \"{}\"", target));
+          let target = match paths_match.star_match {
+            Some(star_match) => target.replace('*', star_match),
+            None => target.clone(),
+          };
+          if let Ok(resolved) = self.resolve_paths_target(target.as_str(), resolver) {
+            resolutions.push((pattern.to_string(), resolved));
+            if !all {
+              return;
+            }
+          }
+        }
+      }
+    });
+    resolutions
+  }
+}
+
+// Get `paths` in declaration order, with `${configDir}` expanded. Patterns
+// and targets with more than one "*" are left out, like tsc does, and
+// collected in `invalid`
+fn expand_paths(
+  paths: &Map<String, Value>,
+  ts_config_dir: &Path,
+  invalid: &mut Vec<String>,
+) -> Vec<(String, Vec<String>)> {
+  let mut is_valid = |value: &str| {
+    let is_valid = value.matches('*').count() <= 1;
+    if !is_valid {
+      invalid.push(String::from(value));
+    }
+    is_valid
+  };
+  let mut expanded = vec![];
+  for (pattern, targets) in paths.iter() {
+    if !is_valid(pattern) {
+      continue;
+    }
+    let targets = targets
+      .as_array()
+      .into_iter()
+      .flatten()
+      .filter_map(Value::as_str)
+      .filter(|target| is_valid(target))
+      .map(|target| expand_config_dir(target, ts_config_dir))
+      .collect();
+    expanded.push((pattern.clone(), targets));
+  }
+  expanded
+}
+
+fn is_bare_specifier(specifier: &str) -> bool {
+  !specifier.starts_with('.') && !Path::new(specifier).is_absolute()
+}
+
+fn get_parent_dir(path: &Path) -> PathBuf {
+  path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Resolves imports through tsconfig files, like TypeScript, then like
+/// Node.js, then through the packages of the workspace.
+pub struct ProjectResolver {
+  projects: Vec<Arc<Project>>,
+  resolver: Resolver,
+  lookup: LookupOptions,
+}
+
+impl ProjectResolver {
+  /// Create a resolver reading the tsconfig files of `options` once.
+  pub fn new(options: ProjectOptions) -> Self {
+    let ts_config_paths = match options.projects.is_empty() {
+      true => vec![options.cwd.join("tsconfig.json")],
+      false => options
+        .projects
+        .iter()
+        .map(|project| normalize_path(&options.cwd.join(project)))
+        .collect(),
+    };
+    let resolver = Resolver::new()
+      .with_extensions(options.extensions)
+      .with_main_fields(options.main_fields)
+      .with_condition_names(options.condition_names)
+      .with_runtime(options.runtime)
+      .with_cwd(options.cwd);
+    let projects = ts_config_paths
+      .iter()
+      .filter_map(|path| Project::load(path))
+      .map(Arc::new)
+      .collect();
+    ProjectResolver::with_projects(projects, resolver, options.lookup)
+  }
+
+  /// Create a resolver for projects read already, looking files up with
+  /// `resolver`, e.g. one sharing a probe log or trace with other lookups.
+  pub fn with_projects(
+    projects: Vec<Arc<Project>>,
+    resolver: Resolver,
+    lookup: LookupOptions,
+  ) -> Self {
+    ProjectResolver {
+      projects,
+      resolver,
+      lookup,
+    }
+  }
+
+  /// Get the tsconfig files that could be read.
+  pub fn projects(&self) -> &[Arc<Project>] {
+    &self.projects
+  }
+
+  // Get the projects to resolve in, without any the tsconfig.json of the
+  // working dir as if it were empty, with the tsconfig file to report
+  fn get_projects(&self) -> Vec<(Arc<Project>, Option<PathBuf>)> {
+    if self.projects.is_empty() {
+      let path = self.resolver.get_cwd().join("tsconfig.json");
+      return vec![(Arc::new(Project::parse(&path, &Value::Null)), None)];
+    }
+    self
+      .projects
+      .iter()
+      .map(|project| (project.clone(), Some(project.path.clone())))
+      .collect()
+  }
+
+  /// Resolve an import of `specifier` in `file` in each project in turn.
+  /// Imports in node_modules resolve like in Node.js. Others go through the
+  /// project's `paths`, then from the file's dir if relative, else from the
+  /// base dir and node_modules, then through workspace packages and
+  /// `@types`. A relative `file` imports from the base dir.
+  pub fn resolve(&self, specifier: &str, file: &Path) -> ResolveOutcome {
+    let mut failed = None;
+    for (project, project_path) in self.get_projects() {
+      match self.resolve_in(&project, specifier, file) {
+        Ok((path, _)) if path.as_os_str().is_empty() => return ResolveOutcome::Builtin,
+        Ok((path, matched_pattern)) => {
+          return ResolveOutcome::Found(Resolution {
+            path,
+            project: project_path,
+            matched_pattern,
+          })
+        }
+        Err(error) => {
+          failed.get_or_insert(error);
+        }
+      }
+    }
+    ResolveOutcome::NotFound(failed.unwrap_or_else(|| ResolutionError::new("Not found")))
+  }
+
+  fn resolve_in(
+    &self,
+    project: &Project,
+    specifier: &str,
+    file: &Path,
+  ) -> Result<(PathBuf, Option<String>), ResolutionError> {
+    let base_dir = project.base_dir();
+    let resolver = self.resolver.with_basedir(base_dir.to_path_buf());
+
+    // Dependencies don't see the project's aliases, their imports resolve
+    // like in Node.js, from their own dir
+    if file.is_absolute() && is_in_node_modules(file) {
+      let resolver = resolver.fully_specified(false);
+      return self
+        .resolve_from_dependency(specifier, file, &resolver)
+        .map(|path| (path, None));
+    }
+
+    let importer_dir = match file.is_absolute() {
+      true => get_parent_dir(file),
+      false => base_dir.to_path_buf(),
+    };
+    let is_bare = is_bare_specifier(specifier);
+
+    // Like tsc, map bare specifiers before looking into node_modules, unless
+    // packages should shadow same-named aliases
+    let paths_first = self.lookup.prefer_ts_paths && !specifier.starts_with('.');
+    if paths_first {
+      if let Some(mapped) = self.resolve_mapped(project, specifier, &importer_dir, &resolver) {
+        return Ok(mapped);
+      }
+    }
+
+    // Legacy imports of sibling files without "./" go before packages
+    if self.lookup.prefer_relative && file.is_absolute() && is_bare {
+      if let Some(resolved) = resolve_as_relative(specifier, &importer_dir, &resolver) {
+        return Ok((resolved, None));
+      }
+    }
+
+    // `workspace:` dependencies are packages of the workspace, never copies
+    // published to node_modules
+    if file.is_absolute() && is_bare {
+      if let Some(resolved) = resolve_workspace_dependency(specifier, file, &resolver) {
+        return Ok((resolved, None));
+      }
+    }
+
+    let node_dir = match file.is_absolute() && specifier.starts_with('.') {
+      true => importer_dir.clone(),
+      false => base_dir.to_path_buf(),
+    };
+    let error = match resolver.with_basedir(node_dir).resolve(specifier) {
+      Ok(resolved) => return Ok((resolved, None)),
+      Err(error) => error,
+    };
+
+    if !paths_first {
+      if let Some(mapped) = self.resolve_mapped(project, specifier, &importer_dir, &resolver) {
+        return Ok(mapped);
+      }
+    }
+
+    // Sibling workspace packages resolve from their dirs, linked or not
+    if is_bare {
+      if let Some(resolved) = resolve_workspace_package(specifier, &importer_dir, &resolver) {
+        return Ok((resolved, None));
+      }
+    }
+
+    // Undeclared template aliases, after `paths` declaring them otherwise
+    if let Some(mapped) = self.resolve_with_src_alias(specifier, base_dir, &resolver) {
+      return Ok(mapped);
+    }
+
+    // Type packages come last, whichever of the above goes first
+    if is_bare && self.lookup.always_try_types {
+      if let Ok(resolved) = resolver.resolve(format!("@types/{}", specifier).as_str()) {
+        return Ok((resolved, None));
+      }
+    }
+    Err(error)
+  }
+
+  // Resolve through the `paths` of `project`, then the workspace aliases
+  fn resolve_mapped(
+    &self,
+    project: &Project,
+    specifier: &str,
+    importer_dir: &Path,
+    resolver: &Resolver,
+  ) -> Option<(PathBuf, Option<String>)> {
+    let paths_resolver = self.get_paths_resolver(resolver);
+    if let Some((pattern, resolved)) = project
+      .resolve_paths(specifier, &paths_resolver, false)
+      .into_iter()
+      .next()
+    {
+      return Some((resolved, Some(pattern)));
+    }
+    self.resolve_with_workspace_aliases(specifier, importer_dir, resolver)
+  }
+
+  fn get_paths_resolver(&self, resolver: &Resolver) -> Resolver {
+    match &self.lookup.paths_extensions {
+      Some(paths_extensions) => resolver.with_extensions(paths_extensions.clone()),
+      None => resolver.clone(),
+    }
+  }
+
+  // Resolve an import of a file inside node_modules. Paths are relative to
+  // the file, bare specifiers are the package importing itself by name, else
+  // packages in the node_modules dirs above the file, else their types
+  fn resolve_from_dependency(
+    &self,
+    specifier: &str,
+    file: &Path,
+    resolver: &Resolver,
+  ) -> Result<PathBuf, ResolutionError> {
+    let file_dir = get_parent_dir(file);
+    let resolver = resolver.with_basedir(file_dir.clone());
+    if !is_bare_specifier(specifier) {
+      return resolver.resolve(specifier);
+    }
+
+    if let Some((pkg_dir, pkg)) = get_enclosing_package(file) {
+      let name = pkg["name"].as_str().unwrap_or_default();
+      if let Ok(resolved) = resolver.resolve_self_reference(specifier, &pkg_dir, name) {
+        return Ok(resolved);
+      }
+    }
+
+    // Packages are looked up next to the real location of the importer, like
+    // Node.js does: pnpm links each peer variant's dependencies next to it,
+    // e.g. in `.pnpm/foo@1.0.0_react@18.2.0/node_modules`, while the symlink
+    // it may be reached through, `node_modules/foo`, sits next to other
+    // versions
+    let resolver = match realpath_cache::realpath(&file_dir) {
+      Ok(real_dir) if real_dir != file_dir => resolver.with_basedir(real_dir),
+      _ => resolver,
+    };
+    let error = match resolver.resolve_package(specifier) {
+      Ok(resolved) => return Ok(resolved),
+      Err(error) => error,
+    };
+    if self.lookup.always_try_types {
+      if let Ok(resolved) = resolver.resolve_package(format!("@types/{}", specifier).as_str()) {
+        return Ok(resolved);
+      }
+    }
+    Err(error)
+  }
+
+  // Resolve through the aliases implied by the manifests of the workspace
+  // `dir` is in, with `workspace_aliases`: a package name maps to the
+  // `source` (else `main`) entry of its package.json, and its subpaths to the
+  // entry's dir, e.g. "@org/ui/button" to packages/ui/src/button.ts for
+  // "src/index.ts"
+  fn resolve_with_workspace_aliases(
+    &self,
+    specifier: &str,
+    dir: &Path,
+    resolver: &Resolver,
+  ) -> Option<(PathBuf, Option<String>)> {
+    if !self.lookup.workspace_aliases || !is_bare_specifier(specifier) {
+      return None;
+    }
+    let name = package_index::get_package_name(specifier);
+    let pkg_dir = workspace::find_package(dir, name)?;
+    let entry = workspace::get_source_entry(&pkg_dir)?;
+    let (target, pattern) = match specifier[name.len()..].strip_prefix('/') {
+      Some(subpath) => (entry.parent()?.join(subpath), format!("{}/*", name)),
+      None => (entry, String::from(name)),
+    };
+    resolver
+      .get_trace()
+      .step(|| format!("workspace alias \"{}\"", pattern));
+    let resolved = self
+      .get_paths_resolver(resolver)
+      .resolve(target.to_string_lossy().as_ref())
+      .ok()?;
+    Some((resolved, Some(pattern)))
+  }
+
+  // Resolve "~/x" and "@/x" to "x" under the `src_alias` dir in `base_dir`,
+  // e.g. "@/utils" to src/utils.ts, as many app templates alias them without
+  // declaring them in tsconfig
+  fn resolve_with_src_alias(
+    &self,
+    specifier: &str,
+    base_dir: &Path,
+    resolver: &Resolver,
+  ) -> Option<(PathBuf, Option<String>)> {
+    let root = self.lookup.src_alias.as_deref()?;
+    let (prefix, subpath) = ["~/", "@/"]
+      .iter()
+      .find_map(|prefix| Some((*prefix, specifier.strip_prefix(prefix)?)))?;
+    let pattern = format!("{}*", prefix);
+    resolver
+      .get_trace()
+      .step(|| format!("src alias \"{}\"", pattern));
+    let target = normalize_path(&base_dir.join(root).join(subpath));
+    let resolved = self
+      .get_paths_resolver(resolver)
+      .resolve(target.to_string_lossy().as_ref())
+      .ok()?;
+    Some((resolved, Some(pattern)))
+  }
+
+  /// Try `specifier` in `file` with every strategy that applies to it in
+  /// each project, without stopping at the first resolving, e.g. to tell
+  /// imports that resolve differently depending on the order. `paths` report
+  /// every target that resolves, imports in node_modules Node.js resolution
+  /// only.
+  pub fn try_all(&self, specifier: &str, file: &Path) -> Vec<Attempt> {
+    let mut attempts = vec![];
+    for (project, project_path) in self.get_projects() {
+      let mut attempt = |strategy: Strategy, resolved: Result<PathBuf, ResolutionError>| {
+        attempts.push(Attempt {
+          project: project_path.clone(),
+          strategy,
+          resolved,
+        })
+      };
+      let found =
+        |resolved: Option<PathBuf>| resolved.ok_or_else(|| ResolutionError::new("Not found"));
+      let base_dir = project.base_dir();
+      let resolver = self.resolver.with_basedir(base_dir.to_path_buf());
+      if file.is_absolute() && is_in_node_modules(file) {
+        let resolver = resolver.fully_specified(false);
+        attempt(
+          Strategy::Node,
+          self.resolve_from_dependency(specifier, file, &resolver),
+        );
+        continue;
+      }
+      let importer_dir = match file.is_absolute() {
+        true => get_parent_dir(file),
+        false => base_dir.to_path_buf(),
+      };
+      let is_bare = is_bare_specifier(specifier);
+
+      if !specifier.starts_with('.') {
+        let mapped = project.resolve_paths(specifier, &self.get_paths_resolver(&resolver), true);
+        if mapped.is_empty() {
+          attempt(Strategy::Paths, found(None));
+        }
+        for (_, resolved) in mapped {
+          attempt(Strategy::Paths, Ok(resolved));
+        }
+      }
+      if is_bare && self.lookup.workspace_aliases {
+        let mapped = self.resolve_with_workspace_aliases(specifier, &importer_dir, &resolver);
+        attempt(
+          Strategy::WorkspaceAlias,
+          found(mapped.map(|(path, _)| path)),
+        );
+      }
+      if is_bare && file.is_absolute() && self.lookup.prefer_relative {
+        let resolved = resolve_as_relative(specifier, &importer_dir, &resolver);
+        attempt(Strategy::Relative, found(resolved));
+      }
+      if is_bare && file.is_absolute() {
+        let resolved = resolve_workspace_dependency(specifier, file, &resolver);
+        attempt(Strategy::WorkspaceDependency, found(resolved));
+      }
+      let node_dir = match file.is_absolute() && specifier.starts_with('.') {
+        true => importer_dir.clone(),
+        false => base_dir.to_path_buf(),
+      };
+      attempt(
+        Strategy::Node,
+        resolver.with_basedir(node_dir).resolve(specifier),
+      );
+      if is_bare {
+        let resolved = resolve_workspace_package(specifier, &importer_dir, &resolver);
+        attempt(Strategy::WorkspacePackage, found(resolved));
+      }
+      if self.lookup.src_alias.is_some()
+        && (specifier.starts_with("~/") || specifier.starts_with("@/"))
+      {
+        let mapped = self.resolve_with_src_alias(specifier, base_dir, &resolver);
+        attempt(Strategy::SrcAlias, found(mapped.map(|(path, _)| path)));
+      }
+      if is_bare && self.lookup.always_try_types {
+        let resolved = resolver.resolve(format!("@types/{}", specifier).as_str());
+        attempt(Strategy::Types, resolved);
+      }
+    }
+    attempts
+  }
+}
+
+// Resolve the bare `specifier` as relative to `dir`, e.g. "utils" as
+// "./utils", with `prefer_relative`
+fn resolve_as_relative(specifier: &str, dir: &Path, resolver: &Resolver) -> Option<PathBuf> {
+  let relative = format!("./{}", specifier);
+  resolver
+    .get_trace()
+    .step(|| format!("relative \"{}\"", relative));
+  resolver
+    .with_basedir(dir.to_path_buf())
+    .resolve(relative.as_str())
+    .ok()
+}
+
+// Resolve `specifier` in the workspace package it names, if `dir` is in a
+// workspace listing one by that name
+fn resolve_workspace_package(specifier: &str, dir: &Path, resolver: &Resolver) -> Option<PathBuf> {
+  let name = package_index::get_package_name(specifier);
+  let pkg_dir = workspace::find_package(dir, name)?;
+  resolver
+    .get_trace()
+    .step(|| format!("workspace package {}", pkg_dir.display()));
+  resolver.resolve_in_package(specifier, &pkg_dir, name).ok()
+}
+
+// Resolve `specifier` in the workspace package it names, if the package
+// `file` belongs to depends on it with the `workspace:` protocol
+fn resolve_workspace_dependency(
+  specifier: &str,
+  file: &Path,
+  resolver: &Resolver,
+) -> Option<PathBuf> {
+  let (pkg_dir, pkg) = get_enclosing_package(file)?;
+  let name = package_index::get_package_name(specifier);
+  let workspace_name = workspace::get_workspace_dependency(&pkg, name)?;
+  let workspace_dir = workspace::find_package(&pkg_dir, workspace_name.as_str())?;
+  resolver
+    .get_trace()
+    .step(|| format!("workspace dependency {}", workspace_dir.display()));
+  let target = format!("{}{}", workspace_name, &specifier[name.len()..]);
+  resolver
+    .resolve_in_package(target.as_str(), &workspace_dir, workspace_name.as_str())
+    .ok()
+}
+
+/// Get the `extends` entries of a tsconfig file, one or several.
+pub fn get_extends_entries(config: &Value) -> Vec<&str> {
+  match config.get("extends") {
+    Some(Value::String(extends)) => vec![extends.as_str()],
+    Some(Value::Array(extends)) => extends.iter().filter_map(|e| e.as_str()).collect(),
+    _ => vec![],
+  }
+}
+
+/// Find the file of a tsconfig `extends` entry, either a path relative to
+/// the extending config or a package.
+pub fn resolve_extends(extends: &str, ts_config_dir: &Path) -> Option<PathBuf> {
+  if extends.starts_with('.') || Path::new(extends).is_absolute() {
+    let path = normalize_path(&ts_config_dir.join(extends));
+    if path.is_file() {
+      return Some(path);
+    }
+    let path = PathBuf::from(format!("{}.json", path.to_str()?));
+    return if path.is_file() { Some(path) } else { None };
+  }
+
+  let resolver = Resolver::new()
+    .with_basedir(ts_config_dir.to_path_buf())
+    .with_extensions([".json"]);
+  resolver
+    .resolve(extends)
+    .or_else(|_| resolver.resolve(format!("{}/tsconfig.json", extends).as_str()))
+    .ok()
+}
+
+/// Read a compiler option as written in a tsconfig file, following
+/// `extends` like TypeScript: the extending config wins over the configs it
+/// extends, and later `extends` entries win over earlier ones.
+pub fn get_raw_compiler_option(
+  ts_config_path: &Path,
+  name: &str,
+  visited: &mut Vec<PathBuf>,
+) -> Option<Value> {
+  find_raw_compiler_option(ts_config_path, name, visited).map(|(value, _)| value)
+}
+
+/// Like `get_raw_compiler_option`, also getting the config file setting it,
+/// which relative paths in it are relative to.
+pub fn find_raw_compiler_option(
+  ts_config_path: &Path,
+  name: &str,
+  visited: &mut Vec<PathBuf>,
+) -> Option<(Value, PathBuf)> {
+  if visited.contains(&ts_config_path.to_path_buf()) {
+    return None;
+  }
+  visited.push(ts_config_path.to_path_buf());
+
+  let config = jsonc::parse_file(ts_config_path)?;
+  if let Some(value) = config
+    .get("compilerOptions")
+    .and_then(|compiler_options| compiler_options.get(name))
+  {
+    return Some((value.clone(), ts_config_path.to_path_buf()));
+  }
+
+  let extends = get_extends_entries(&config);
+  let ts_config_dir = ts_config_path.parent()?;
+  extends.iter().rev().find_map(|extends| {
+    let extends_path = resolve_extends(extends, ts_config_dir)?;
+    find_raw_compiler_option(&extends_path, name, visited)
+  })
+}
+
+/// Get a dir compiler option, e.g. `outDir`, as an absolute path.
+pub fn get_compiler_dir_option(ts_config_path: &Path, name: &str) -> Option<PathBuf> {
+  let (value, defined_in) = find_raw_compiler_option(ts_config_path, name, &mut vec![])?;
+  let ts_config_dir = ts_config_path.parent().unwrap_or(Path::new(""));
+  let value = expand_config_dir(value.as_str()?, ts_config_dir);
+  let defined_in_dir = defined_in.parent().unwrap_or(Path::new(""));
+  Some(normalize_path(&defined_in_dir.join(value)))
+}
+
+/// Expand TypeScript's `${configDir}` template to the tsconfig file's dir.
+pub fn expand_config_dir(value: &str, ts_config_dir: &Path) -> String {
+  value.replace("${configDir}", ts_config_dir.to_string_lossy().as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+
+  #[test]
+  fn resolves_inherited_paths_and_base_url() {
+    let dir = env::temp_dir().join(format!("ts-import-resolver-project-{}", std::process::id()));
+    let write = |file: &str, content: &str| {
+      let path = dir.join(file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, content).unwrap();
+    };
+    write("tsconfig.json", r#"{ "extends": "./base.json" }"#);
+    write(
+      "base.json",
+      r#"{ "compilerOptions": { "baseUrl": "src", "paths": { "@lib/*": ["../lib/*"] } } }"#,
+    );
+    write("lib/util.ts", "export {}");
+    write("src/app.ts", "export {}");
+    write("src/index.ts", "");
+
+    let resolver = ProjectResolver::new(ProjectOptions {
+      cwd: dir.clone(),
+      ..ProjectOptions::default()
+    });
+    let file = dir.join("src/index.ts");
+    let found = |specifier| match resolver.resolve(specifier, &file) {
+      ResolveOutcome::Found(resolution) => Some((resolution.path, resolution.matched_pattern)),
+      _ => None,
+    };
+    assert_eq!(
+      Some((dir.join("lib/util.ts"), Some(String::from("@lib/*")))),
+      found("@lib/util")
+    );
+    assert_eq!(Some((dir.join("src/app.ts"), None)), found("app"));
+    assert_eq!(Some((dir.join("src/app.ts"), None)), found("./app"));
+    assert_eq!(None, found("@lib/missing"));
+    assert!(matches!(
+      resolver.resolve("fs", &file),
+      ResolveOutcome::Builtin
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,69 @@
+//! Resolve symlinks like `fs::canonicalize`, caching the real path of each
+//! directory, so resolutions into the same deep (e.g. pnpm store) dirs cost
+//! one `lstat` for the file instead of one per path component.
+//!
+//! ```rust
+//! use std::path::Path;
+//! use ts_import_resolver::realpath_cache::realpath;
+//!
+//! realpath(Path::new("/repo/node_modules/foo/index.js"));
+//! // → Ok("/repo/node_modules/.pnpm/foo@1.0.0/node_modules/foo/index.js")
+//! realpath(Path::new("/repo/node_modules/foo/lib.js"));
+//! // → reuses the real path of /repo/node_modules/foo
+//! ```
+
+use crate::lru::{self, LruMap};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+fn dirs() -> &'static LruMap<PathBuf, PathBuf> {
+  static DIRS: OnceLock<LruMap<PathBuf, PathBuf>> = OnceLock::new();
+  DIRS.get_or_init(|| LruMap::new(lru::capacity(10_000)))
+}
+
+// Follow `path` if it is a symlink, given its parent is already real
+fn resolve_link(path: PathBuf) -> io::Result<PathBuf> {
+  if fs::symlink_metadata(&path)?.file_type().is_symlink() {
+    fs::canonicalize(&path)
+  } else {
+    Ok(path)
+  }
+}
+
+fn get_real_dir(dir: &Path) -> io::Result<PathBuf> {
+  let cached = dirs().get(dir);
+  if let Some(real_dir) = cached {
+    return Ok(real_dir);
+  }
+  let real_dir = match (dir.parent(), dir.file_name()) {
+    (Some(parent), Some(name)) => resolve_link(get_real_dir(parent)?.join(name))?,
+    _ => fs::canonicalize(dir)?,
+  };
+  dirs().insert(dir.to_path_buf(), real_dir.clone());
+  Ok(real_dir)
+}
+
+/// Get the real path of `path`, with every symlink resolved.
+pub fn realpath(path: &Path) -> io::Result<PathBuf> {
+  // ".." after a symlink leaves the link's target, leave that to the OS
+  let is_plain = path.is_absolute()
+    && path
+      .components()
+      .all(|part| !matches!(part, Component::CurDir | Component::ParentDir));
+  match (is_plain, path.parent(), path.file_name()) {
+    (true, Some(parent), Some(name)) => resolve_link(get_real_dir(parent)?.join(name)),
+    _ => fs::canonicalize(path),
+  }
+}
+
+pub fn clear() {
+  dirs().clear();
+}
+
+/// Drop the real paths of `path` and the dirs inside it, e.g. after a
+/// symlink was replaced.
+pub fn invalidate(path: &Path) {
+  dirs().retain(|dir, _| !dir.starts_with(path));
+}
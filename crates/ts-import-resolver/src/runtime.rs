@@ -0,0 +1,98 @@
+//! Runtimes code is resolved for: the modules each one provides itself, and
+//! the package.json "exports" conditions and entry fields it reads.
+//!
+//! ```rust
+//! use ts_import_resolver::Runtime;
+//!
+//! Runtime::from_name("bun").unwrap().is_core_module("bun:sqlite");
+//! // → true
+//! Runtime::Browser.is_core_module("fs");
+//! // → false, browsers resolve "fs" like any package
+//! ```
+
+use crate::node_resolve::is_core_module_in;
+use std::path::Path;
+
+/// Modules Electron provides on top of those of Node.js.
+const ELECTRON_MODULES: &[&str] = &["electron", "original-fs"];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Runtime {
+  #[default]
+  Node,
+  Bun,
+  Browser,
+  Workerd,
+  Electron,
+}
+
+impl Runtime {
+  /// Names of all runtimes, as taken by `from_name`.
+  pub const NAMES: &'static [&'static str] = &["node", "bun", "browser", "workerd", "electron"];
+
+  /// Get the runtime called `name`, e.g. "workerd" for Cloudflare Workers.
+  pub fn from_name(name: &str) -> Option<Runtime> {
+    match name {
+      "node" => Some(Runtime::Node),
+      "bun" => Some(Runtime::Bun),
+      "browser" => Some(Runtime::Browser),
+      "workerd" => Some(Runtime::Workerd),
+      "electron" => Some(Runtime::Electron),
+      _ => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Runtime::Node => "node",
+      Runtime::Bun => "bun",
+      Runtime::Browser => "browser",
+      Runtime::Workerd => "workerd",
+      Runtime::Electron => "electron",
+    }
+  }
+
+  /// Check if the runtime provides `target` itself, such as "fs" in Node.js
+  /// or "cloudflare:sockets" in workerd.
+  pub fn is_core_module(&self, target: &str) -> bool {
+    self.is_core_module_in(target, Path::new(""))
+  }
+
+  /// Like `is_core_module`, also counting the Node.js modules the Node.js
+  /// types installed in the working dir `cwd` declare.
+  pub fn is_core_module_in(&self, target: &str, cwd: &Path) -> bool {
+    let is_node_module = |target: &str| is_core_module_in(target, cwd);
+    match self {
+      Runtime::Node => is_node_module(target),
+      Runtime::Bun => target == "bun" || target.starts_with("bun:") || is_node_module(target),
+      Runtime::Browser => false,
+      // Node.js modules come with the `nodejs_compat` flag, by "node:" names
+      Runtime::Workerd => {
+        target.starts_with("cloudflare:") || (target.starts_with("node:") && is_node_module(target))
+      }
+      Runtime::Electron => ELECTRON_MODULES.contains(&target) || is_node_module(target),
+    }
+  }
+
+  /// Get the package.json "exports" conditions the runtime matches, besides
+  /// "default".
+  pub fn condition_names(&self) -> &'static [&'static str] {
+    match self {
+      Runtime::Node => &["node", "node-addons", "import", "require"],
+      Runtime::Bun => &["bun", "node", "import", "require"],
+      Runtime::Browser => &["browser", "import", "module", "require"],
+      Runtime::Workerd => &["workerd", "worker", "browser", "import", "require"],
+      Runtime::Electron => &["electron", "node", "import", "require"],
+    }
+  }
+
+  /// Get the package.json fields the runtime reads entry points from, in
+  /// order.
+  pub fn main_fields(&self) -> &'static [&'static str] {
+    match self {
+      Runtime::Node | Runtime::Electron => &["main"],
+      Runtime::Bun => &["module", "main"],
+      Runtime::Browser | Runtime::Workerd => &["browser", "module", "main"],
+    }
+  }
+}
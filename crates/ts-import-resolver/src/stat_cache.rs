@@ -0,0 +1,103 @@
+//! Cache what probed paths are (file, directory, or missing) for a short
+//! time, so the many candidates tried per import cost one `stat` each per
+//! lint run instead of one per import.
+//!
+//! ```rust
+//! use std::path::Path;
+//! use ts_import_resolver::stat_cache::is_file;
+//!
+//! is_file(Path::new("/repo/src/foo.ts"));
+//! // → stat, then true
+//! is_file(Path::new("/repo/src/foo.ts"));
+//! // → true, from the cache until the TTL passes or the path is invalidated
+//! ```
+
+use crate::dir_listing;
+use crate::lru::{self, LruMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, PartialEq)]
+enum FileKind {
+  File,
+  Dir,
+  Missing,
+}
+
+const DEFAULT_TTL_MS: u64 = 1000;
+
+static TTL_MS: AtomicU64 = AtomicU64::new(DEFAULT_TTL_MS);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+fn entries() -> &'static LruMap<PathBuf, (FileKind, Instant)> {
+  static ENTRIES: OnceLock<LruMap<PathBuf, (FileKind, Instant)>> = OnceLock::new();
+  ENTRIES.get_or_init(|| LruMap::new(lru::capacity(100_000)))
+}
+
+/// Get how long results are reused.
+pub fn ttl() -> Duration {
+  Duration::from_millis(TTL_MS.load(Ordering::Relaxed))
+}
+
+fn get_kind(path: &Path) -> FileKind {
+  let ttl = ttl();
+  if ttl.is_zero() {
+    return stat(path);
+  }
+
+  let now = Instant::now();
+  let cached = entries().get(path);
+  if let Some((kind, checked_at)) = cached {
+    if now.duration_since(checked_at) < ttl {
+      HITS.fetch_add(1, Ordering::Relaxed);
+      return kind;
+    }
+  }
+  MISSES.fetch_add(1, Ordering::Relaxed);
+  // Stat without holding the entry, batches probe from many threads at once
+  let kind = stat(path);
+  entries().insert(path.to_path_buf(), (kind, now));
+  kind
+}
+
+fn stat(path: &Path) -> FileKind {
+  match fs::metadata(path) {
+    Ok(metadata) if metadata.is_file() => FileKind::File,
+    Ok(metadata) if metadata.is_dir() => FileKind::Dir,
+    _ => FileKind::Missing,
+  }
+}
+
+/// Check if `path` is a file, following symlinks like `Path::is_file`.
+pub fn is_file(path: &Path) -> bool {
+  get_kind(path) == FileKind::File
+}
+
+/// Set how long results are reused; 0 turns the cache off.
+pub fn set_ttl(ttl_ms: u64) {
+  TTL_MS.store(ttl_ms, Ordering::Relaxed);
+  clear();
+  dir_listing::clear();
+}
+
+pub fn clear() {
+  entries().clear();
+}
+
+/// Drop the cached result for `path`, e.g. after it was created or deleted.
+pub fn invalidate(path: &Path) {
+  entries().remove(path);
+}
+
+/// Get hits, misses, and size of the stat cache.
+pub fn cache_stats() -> (u64, u64, usize) {
+  (
+    HITS.load(Ordering::Relaxed),
+    MISSES.load(Ordering::Relaxed),
+    entries().len(),
+  )
+}
@@ -0,0 +1,68 @@
+//! Record the steps taken by a single resolution.
+//!
+//! ```rust
+//! use ts_import_resolver::trace::Trace;
+//!
+//! let trace = Trace::new(true, false);
+//! trace.step(|| format!("probe {}", "/repo/src/foo.ts"));
+//! trace.steps();
+//! // → ["probe /repo/src/foo.ts"]
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+/// Steps of one resolution, shared by every resolver cloned from the one it
+/// was attached to. Disabled traces skip formatting steps entirely.
+#[derive(Clone, Default)]
+pub struct Trace {
+  steps: Option<Arc<Mutex<Vec<String>>>>,
+  log: bool,
+}
+
+impl Trace {
+  /// Create a trace that records steps to return them, logs them to stderr,
+  /// or both.
+  pub fn new(record: bool, log: bool) -> Self {
+    Trace {
+      steps: if record {
+        Some(Arc::new(Mutex::new(vec![])))
+      } else {
+        None
+      },
+      log,
+    }
+  }
+
+  /// Create a trace controlled by the `TS_RESOLVER_DEBUG=1` environment
+  /// variable, which logs steps to stderr.
+  pub fn from_env(record: bool) -> Self {
+    let log = std::env::var("TS_RESOLVER_DEBUG").as_deref() == Ok("1");
+    Trace::new(record, log)
+  }
+
+  /// Record a step.
+  pub fn step<F>(&self, step: F)
+  where
+    F: FnOnce() -> String,
+  {
+    if self.steps.is_none() && !self.log {
+      return;
+    }
+
+    let step = step();
+    if self.log {
+      eprintln!("[eslint-import-resolver-typescript] {}", step);
+    }
+    if let Some(steps) = &self.steps {
+      steps.lock().unwrap().push(step);
+    }
+  }
+
+  /// Get the recorded steps, if recording.
+  pub fn steps(&self) -> Option<Vec<String>> {
+    self
+      .steps
+      .as_ref()
+      .map(|steps| steps.lock().unwrap().clone())
+  }
+}
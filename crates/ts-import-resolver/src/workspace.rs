@@ -0,0 +1,246 @@
+//! Find the packages of npm, yarn, and pnpm workspaces by name, so imports of
+//! sibling packages resolve to their dirs even before they are linked into
+//! node_modules. Workspaces are listed by the `workspaces` of the root
+//! package.json, or by `packages` in pnpm-workspace.yaml.
+//!
+//! ```rust
+//! use std::path::Path;
+//! use ts_import_resolver::workspace::find_package;
+//!
+//! find_package(Path::new("/repo/apps/web/src"), "@org/ui");
+//! // → finds /repo/package.json with `"workspaces": ["packages/*"]`, then
+//! //   Some("/repo/packages/ui") if its package.json is named "@org/ui"
+//! ```
+
+use crate::lru::{self, LruMap};
+use crate::node_resolve::read_package_json;
+use crate::stat_cache;
+use glob::{glob, Pattern};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// Fields of package.json declaring dependencies.
+pub const DEPENDENCY_FIELDS: &[&str] = &[
+  "dependencies",
+  "devDependencies",
+  "peerDependencies",
+  "optionalDependencies",
+];
+
+// Package dirs by package name
+type Packages = HashMap<String, PathBuf>;
+
+// The workspace root of each dir, `None` outside of workspaces
+fn roots() -> &'static LruMap<PathBuf, Option<PathBuf>> {
+  static ROOTS: OnceLock<LruMap<PathBuf, Option<PathBuf>>> = OnceLock::new();
+  ROOTS.get_or_init(|| LruMap::new(lru::capacity(10_000)))
+}
+
+fn packages() -> &'static LruMap<PathBuf, Arc<Packages>> {
+  static PACKAGES: OnceLock<LruMap<PathBuf, Arc<Packages>>> = OnceLock::new();
+  PACKAGES.get_or_init(|| LruMap::new(lru::capacity(100)))
+}
+
+// Read the `packages` list of a pnpm-workspace.yaml, the only key it has
+// besides catalogs, without a full YAML parser
+fn parse_pnpm_workspace(content: &str) -> Vec<String> {
+  let mut patterns = vec![];
+  let mut in_packages = false;
+  for line in content.lines() {
+    let line = line.split(" #").next().unwrap_or_default().trim_end();
+    if line.trim().is_empty() || line.trim_start().starts_with('#') {
+      continue;
+    }
+    if !line.starts_with([' ', '\t', '-']) {
+      in_packages = line.trim() == "packages:";
+      continue;
+    }
+    if let Some(pattern) = line.trim().strip_prefix('-').filter(|_| in_packages) {
+      let pattern = pattern.trim().trim_matches(|c| c == '"' || c == '\'');
+      patterns.push(String::from(pattern));
+    }
+  }
+  patterns
+}
+
+// Get the workspace patterns declared in `dir`, `None` if it is no
+// workspace root
+fn get_patterns(dir: &Path) -> Option<Vec<String>> {
+  let pnpm_workspace = dir.join("pnpm-workspace.yaml");
+  if stat_cache::is_file(&pnpm_workspace) {
+    let content = fs::read_to_string(&pnpm_workspace).unwrap_or_default();
+    return Some(parse_pnpm_workspace(content.as_str()));
+  }
+
+  let pkg = read_package_json(&dir.join("package.json")).ok()?;
+  // Yarn also takes `{ "packages": [..], "nohoist": [..] }`
+  let workspaces = match pkg["workspaces"].get("packages") {
+    Some(workspaces) => workspaces,
+    None => &pkg["workspaces"],
+  };
+  let patterns = workspaces
+    .as_array()?
+    .iter()
+    .filter_map(|pattern| pattern.as_str().map(String::from))
+    .collect();
+  Some(patterns)
+}
+
+fn list_packages(root: &Path, patterns: &[String]) -> Packages {
+  let root_pattern = Pattern::escape(root.to_string_lossy().as_ref());
+  let mut dirs: Vec<PathBuf> = vec![];
+  // Negated patterns, e.g. "!**/test/**", exclude what the others include
+  let (excluded, included): (Vec<&String>, Vec<&String>) = patterns
+    .iter()
+    .partition(|pattern| pattern.starts_with('!'));
+  for pattern in included {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let entries = match glob(format!("{}/{}/package.json", root_pattern, pattern).as_str()) {
+      Ok(entries) => entries,
+      Err(_) => continue,
+    };
+    dirs.extend(
+      entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.parent().map(Path::to_path_buf)),
+    );
+  }
+  let excluded: Vec<Pattern> = excluded
+    .iter()
+    .filter_map(|pattern| {
+      let pattern = pattern[1..].trim_start_matches("./").trim_end_matches('/');
+      Pattern::new(format!("{}/{}", root_pattern, pattern).as_str()).ok()
+    })
+    .collect();
+
+  let mut packages = Packages::new();
+  for dir in dirs {
+    let is_excluded = excluded.iter().any(|pattern| pattern.matches_path(&dir));
+    if is_excluded
+      || dir
+        .components()
+        .any(|part| part.as_os_str() == "node_modules")
+    {
+      continue;
+    }
+    let pkg = match read_package_json(&dir.join("package.json")) {
+      Ok(pkg) => pkg,
+      Err(_) => continue,
+    };
+    if let Some(name) = pkg["name"].as_str() {
+      // The first listed wins, like package managers that refuse duplicates
+      packages.entry(String::from(name)).or_insert(dir);
+    }
+  }
+  packages
+}
+
+/// Get the root of the workspace `dir` belongs to, the nearest dir above it
+/// declaring workspaces.
+pub fn find_root(dir: &Path) -> Option<PathBuf> {
+  if let Some(root) = roots().get(dir) {
+    return root;
+  }
+  let root = dir
+    .ancestors()
+    .find(|ancestor| get_patterns(ancestor).is_some())
+    .map(Path::to_path_buf);
+  roots().insert(dir.to_path_buf(), root.clone());
+  root
+}
+
+/// Get the dirs of the packages of the workspace at `root` by name.
+pub fn get_packages(root: &Path) -> Arc<HashMap<String, PathBuf>> {
+  if let Some(cached) = packages().get(root) {
+    return cached;
+  }
+  let patterns = get_patterns(root).unwrap_or_default();
+  let listed = Arc::new(list_packages(root, patterns.as_slice()));
+  packages().insert(root.to_path_buf(), listed.clone());
+  listed
+}
+
+/// Get the dir of the workspace package `name` in the workspace `dir`
+/// belongs to.
+pub fn find_package(dir: &Path, name: &str) -> Option<PathBuf> {
+  let root = find_root(dir)?;
+  get_packages(&root).get(name).cloned()
+}
+
+/// Get the entry of the workspace package in `pkg_dir` imports of it by name
+/// are aliased to, its `source` field, else its `main`, e.g.
+/// "/repo/packages/ui/src/index.ts" for `"source": "src/index.ts"`.
+pub fn get_source_entry(pkg_dir: &Path) -> Option<PathBuf> {
+  let pkg = read_package_json(&pkg_dir.join("package.json")).ok()?;
+  let entry = pkg["source"].as_str().or_else(|| pkg["main"].as_str())?;
+  Some(pkg_dir.join(entry.trim_start_matches("./")))
+}
+
+/// Get the workspace package the dependency `name` of `pkg` refers to, if
+/// it uses the `workspace:` protocol, e.g. "ui" for `"ui": "workspace:*"` or
+/// "@org/ui" for the alias `"ui": "workspace:@org/ui@*"`.
+pub fn get_workspace_dependency(pkg: &Value, name: &str) -> Option<String> {
+  let range = DEPENDENCY_FIELDS
+    .iter()
+    .find_map(|field| pkg[field][name].as_str()?.strip_prefix("workspace:"))?;
+  match range.rfind('@') {
+    Some(index) if index > 0 => Some(String::from(&range[..index])),
+    _ => Some(String::from(name)),
+  }
+}
+
+pub fn clear() {
+  roots().clear();
+  packages().clear();
+}
+
+/// Drop what was read from `path`, if it may declare workspaces or name a
+/// workspace package.
+pub fn invalidate(path: &Path) {
+  let is_manifest = path
+    .file_name()
+    .is_some_and(|name| name == "package.json" || name == "pnpm-workspace.yaml");
+  if is_manifest {
+    clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_pnpm_workspace_packages() {
+    let content = r#"
+# all packages in direct subdirs of packages/
+packages:
+  - 'packages/*'
+  - "apps/**" # and nested apps
+  - '!**/test/**'
+
+catalog:
+  - not-a-package
+"#;
+    assert_eq!(
+      vec!["packages/*", "apps/**", "!**/test/**"],
+      parse_pnpm_workspace(content)
+    );
+  }
+
+  #[test]
+  fn reads_workspace_protocol_dependencies() {
+    let pkg = serde_json::json!({
+      "dependencies": { "ui": "workspace:*", "lodash": "^4.17.21" },
+      "devDependencies": { "utils": "workspace:@org/utils@^1.0.0", "@org/config": "workspace:~" },
+    });
+    let get = |name| get_workspace_dependency(&pkg, name);
+    assert_eq!(Some(String::from("ui")), get("ui"));
+    assert_eq!(Some(String::from("@org/utils")), get("utils"));
+    assert_eq!(Some(String::from("@org/config")), get("@org/config"));
+    assert_eq!(None, get("lodash"));
+    assert_eq!(None, get("react"));
+  }
+}
@@ -236,6 +236,31 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { resolve } = nativeBinding
+const { isCoreModule, getEffectivePaths, getProjectInfo, getCacheStats, setLogger, registerProtocolHandler, dumpConfig, bumpGeneration, setStatCacheTtl, setCacheDir, saveCache, loadManifest, unloadManifest, writeManifest, clearCache, invalidate, resolve, explain, doctor, resolvePath, resolveAsync, resolveBatch, buildImportGraph, warm, Resolver, createResolver } = nativeBinding
 
+module.exports.isCoreModule = isCoreModule
+module.exports.getEffectivePaths = getEffectivePaths
+module.exports.getProjectInfo = getProjectInfo
+module.exports.getCacheStats = getCacheStats
+module.exports.setLogger = setLogger
+module.exports.registerProtocolHandler = registerProtocolHandler
+module.exports.dumpConfig = dumpConfig
+module.exports.bumpGeneration = bumpGeneration
+module.exports.setStatCacheTtl = setStatCacheTtl
+module.exports.setCacheDir = setCacheDir
+module.exports.saveCache = saveCache
+module.exports.loadManifest = loadManifest
+module.exports.unloadManifest = unloadManifest
+module.exports.writeManifest = writeManifest
+module.exports.clearCache = clearCache
+module.exports.invalidate = invalidate
 module.exports.resolve = resolve
+module.exports.explain = explain
+module.exports.doctor = doctor
+module.exports.resolvePath = resolvePath
+module.exports.resolveAsync = resolveAsync
+module.exports.resolveBatch = resolveBatch
+module.exports.buildImportGraph = buildImportGraph
+module.exports.warm = warm
+module.exports.Resolver = Resolver
+module.exports.createResolver = createResolver
@@ -0,0 +1 @@
+export default 'stale build output'
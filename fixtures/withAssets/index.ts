@@ -0,0 +1,3 @@
+// import assets
+import './logo.svg'
+import './fonts/inter.woff2'
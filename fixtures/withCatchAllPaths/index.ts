@@ -0,0 +1,9 @@
+// import using a specific path mapping, despite the catch-all matching too
+import '@app/foo'
+
+// import using the catch-all path mapping
+import 'bar'
+import 'baz'
+
+// import builtins
+import 'fs'
@@ -0,0 +1 @@
+export default 'shadowed by @app/*'
@@ -0,0 +1,2 @@
+// import with ${configDir} paths
+import '@/foo'
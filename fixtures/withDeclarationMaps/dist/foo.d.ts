@@ -0,0 +1,2 @@
+export declare const foo = "yes";
+//# sourceMappingURL=foo.d.ts.map
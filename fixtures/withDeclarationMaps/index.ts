@@ -0,0 +1,3 @@
+// import built declarations
+import './dist/foo'
+import './dist/bar'
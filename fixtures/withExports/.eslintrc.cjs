@@ -0,0 +1 @@
+module.exports = require('../baseEslintConfig.cjs')(__dirname)
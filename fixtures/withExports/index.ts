@@ -0,0 +1,3 @@
+// import conditional exports
+import 'cond-pkg'
+import 'cond-pkg/feature'
@@ -0,0 +1,3 @@
+// import flow typed files
+import './typed'
+import './untyped'
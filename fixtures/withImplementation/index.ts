@@ -0,0 +1,2 @@
+// import declaration with implementation
+import './lib.js'
@@ -0,0 +1 @@
+exports.lib = 'yes'
@@ -0,0 +1,3 @@
+// import platform specific files
+import './styles'
+import './button'
@@ -0,0 +1,4 @@
+// import style sheets
+import './styles.module.scss'
+import './theme.css'
+import './variables.less'
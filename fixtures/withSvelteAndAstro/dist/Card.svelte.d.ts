@@ -0,0 +1,2 @@
+import { SvelteComponentTyped } from 'svelte'
+export default class Card extends SvelteComponentTyped {}
@@ -0,0 +1,3 @@
+// import with extension
+import './Button.svelte'
+import './Layout.astro'
@@ -0,0 +1,2 @@
+// import with extension
+import './Component.vue'
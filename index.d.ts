@@ -3,11 +3,218 @@
 
 /* auto-generated by NAPI-RS */
 
+/** Why resolution failed */
+export const enum FailureReason {
+  NotFound = 'NotFound',
+  NoTsconfig = 'NoTsconfig',
+  NotExported = 'NotExported',
+  BlockedByExportsNull = 'BlockedByExportsNull',
+  ExtensionMissing = 'ExtensionMissing',
+  CaseMismatch = 'CaseMismatch',
+  OutsideProject = 'OutsideProject',
+  NotHandled = 'NotHandled',
+  UndeclaredDependency = 'UndeclaredDependency'
+}
+export interface ResolveTiming {
+  totalMs: number
+  tsconfigMs: number
+  pathsMs: number
+  nodeModulesMs: number
+  statMs: number
+}
+export interface ProbeCounts {
+  fileChecks: number
+  packageJsonReads: number
+}
+export interface Candidate {
+  project: string
+  strategy: string
+  specifier: string
+  found: boolean
+  path: string
+  reason?: FailureReason
+}
 export interface ResolveResult {
   found: boolean
   path: string
+  implementationPath?: string
+  reason?: FailureReason
+  attemptedPaths?: Array<string>
+  suggestions?: Array<string>
+  warnings?: Array<string>
+  ambiguousPaths?: Array<string>
+  candidates?: Array<Candidate>
+  trace?: Array<string>
+  packageName?: string
+  packageVersion?: string
+  isExternal?: boolean
+  isWorkspacePackage?: boolean
+  isDeclarationFile?: boolean
+  isFallback?: boolean
+  matchedPattern?: string
+  timing?: ResolveTiming
+  probeCounts?: ProbeCounts
 }
 export interface Options {
   project?: Array<string>
+  roots?: Array<string>
+  cwd?: string
+  extensions?: Array<string>
+  extensionOrderPreset?: string
+  pathsExtensions?: Array<string>
+  extraExtensions?: Array<string>
+  frameworks?: Array<string>
+  styleExtensions?: Array<string>
+  assetExtensions?: Array<string>
+  assetsAlwaysFound?: boolean
+  platforms?: Array<string>
+  flowDeclarations?: boolean
+  followDeclarationMaps?: boolean
+  dedupeInjectedPackages?: boolean
+  useSourceOfProjectReferences?: boolean
+  workspaceAliases?: boolean
+  preferRelative?: boolean
+  fullySpecified?: boolean
+  srcAlias?: boolean | string
+  declarationPreference?: string
+  searchMode?: string
+  runtime?: string
+  mainFields?: Array<string>
+  conditionNames?: Array<string>
+  conditionSets?: Array<ConditionSet>
+  modules?: Array<string>
+  alias?: Record<string, string | Array<string> | false> | Array<{ name: string; alias: string | Array<string> | false; onlyModule?: boolean } | { find: string | RegExp; replacement: string }>
+  fallback?: Record<string, string | Array<string> | false>
+  importMap?: string | Record<string, unknown>
+  ignore?: Array<string | RegExp>
+  resolveOnly?: Array<string>
+  alwaysTryTypes?: boolean
+  preferTsPaths?: boolean
+  nearestTsconfigFirst?: boolean
+  preserveSymlinks?: boolean
+  symlinks?: boolean
+  restrictToProject?: boolean
+  strict?: boolean
+  strictDependencies?: boolean
+  dependencyFields?: Array<string>
+  trace?: boolean
+  timing?: boolean
+  probeCounts?: boolean
+  metadata?: boolean
+  tsconfigContent?: string | Record<string, unknown>
+  compilerOptions?: CompilerOptions
+}
+export interface Alias {
+  name: string
+  targets: Array<string>
+  regex?: boolean
+}
+export interface ConditionSet {
+  files: Array<string>
+  conditionNames: Array<string>
+}
+export interface CompilerOptions {
+  baseUrl?: string
+  paths?: Record<string, Array<string>>
+  customConditions?: Array<string>
+}
+export interface ResolverConfig {
+  projects: Array<string>
+  roots: Array<string>
+  extensions: Array<string>
+  pathsExtensions: Array<string>
+  mainFields: Array<string>
+  conditionNames: Array<string>
+  modules: Array<string>
+  runtime: string
+  builtinsSource: string
+  preserveSymlinks: boolean
+  caches: Array<string>
+}
+export interface EffectivePaths {
+  project: string
+  baseUrl: string
+  paths: Record<string, Array<string>>
+  references: Array<string>
+}
+export interface CacheCounters {
+  hits: number
+  misses: number
+  size: number
+}
+export interface CacheStats {
+  tsconfigPaths: CacheCounters
+  tsconfigs: CacheCounters
+  resolutions: CacheCounters
+  declarationMaps: CacheCounters
+  fileStats: CacheCounters
+  packageJsons: CacheCounters
+}
+export const enum LogEventKind {
+  CacheMiss = 'CacheMiss',
+  TsconfigReload = 'TsconfigReload',
+  ExportsMismatch = 'ExportsMismatch',
+  InvalidPathsPattern = 'InvalidPathsPattern',
+  ConfigWarning = 'ConfigWarning'
+}
+export interface LogEvent {
+  kind: LogEventKind
+  path: string
+  message: string
+}
+export interface ProjectInfo {
+  project?: string
+  baseDir: string
+  moduleResolution?: string
+}
+export interface DoctorCheck {
+  project: string
+  kind: string
+  name: string
+  target: string
+  ok: boolean
+  problem?: string
+}
+export interface ResolveRequest {
+  source: string
+  file: string
 }
 export function resolve(sourceInput: string, file: string, options: Options): ResolveResult
+export function explain(sourceInput: string, file: string, options: Options): string
+export function doctor(options: Options): Array<DoctorCheck>
+export function resolvePath(sourceInput: string, file: string, options: Options): string
+export function resolveAsync(sourceInput: string, file: string, options: Options): Promise<ResolveResult>
+export function resolveBatch(requests: Array<ResolveRequest>, options: Options): Array<ResolveResult>
+export function buildImportGraph(entryGlobs: Array<string>, options: Options): string
+export function isCoreModule(specifier: string, runtime?: string | undefined | null, cwd?: string | undefined | null): boolean
+export function getEffectivePaths(project: string, options?: Options | undefined | null): EffectivePaths
+export function getProjectInfo(file: string, options?: Options | undefined | null): ProjectInfo
+export function getCacheStats(): CacheStats
+export function setLogger(logger?: ((event: LogEvent) => void) | undefined | null): void
+export function registerProtocolHandler(prefix: string, handler?: ((specifier: string, file: string) => string | { external: true } | null | undefined) | undefined | null): void
+export function dumpConfig(options: Options): ResolverConfig
+export function bumpGeneration(): number
+export function setStatCacheTtl(ttlMs: number): void
+export function setCacheDir(dir: string, cwd?: string | undefined | null): number
+export function saveCache(cwd?: string | undefined | null): void
+export function loadManifest(path: string, cwd?: string | undefined | null): number
+export function unloadManifest(): void
+export function writeManifest(path: string, cwd?: string | undefined | null): number
+export function clearCache(): void
+export function invalidate(path: string): void
+export function warm(project: string, statNodeModules?: boolean | undefined | null, options?: Options | undefined | null): Promise<void>
+export function createResolver(options: Options): Resolver
+/**
+ * Resolver built once from options. Reuses its node resolver and expanded
+ * `project` globs across `resolve()` calls. Instances implement the
+ * import-resolver v3 interface.
+ */
+export class Resolver {
+  constructor(options: Options)
+  get interfaceVersion(): number
+  get name(): string
+  dumpConfig(): ResolverConfig
+  resolve(sourceInput: string, file: string): ResolveResult
+  resolvePath(sourceInput: string, file: string): string
+  resolveBatch(requests: Array<ResolveRequest>): Array<ResolveResult>
+}
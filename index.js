@@ -1,8 +1,180 @@
-const worker = require('./entry');
+const path = require('path');
 
-exports.resolve = (source, file, options) => {
-  const project = Array.isArray(options.project) ? options.project : [options.project];
-  return worker.resolve(source, file, { project });
+// Where no prebuilt binary is published, e.g. on Alpine variants, FreeBSD, or
+// ARM niches, `TS_RESOLVER_BINARY` names a binding built from source with
+// `napi build --release`, loaded in place of the prebuilt one
+const loadBinding = () => {
+  const binary = process.env.TS_RESOLVER_BINARY;
+  if (binary) {
+    return require(path.resolve(binary));
+  }
+  try {
+    return require('./entry');
+  } catch (error) {
+    throw new Error(
+      `No prebuilt binary loads on ${process.platform}-${process.arch}, build one with ` +
+        `\`napi build --release\` and set TS_RESOLVER_BINARY to its path: ${error.message}`,
+    );
+  }
+};
+
+const worker = loadBinding();
+const pkg = require('./package.json');
+
+// RegExps are passed on as their source, case-insensitive ones flagged inline
+const normalizePattern = (pattern) => {
+  if (!(pattern instanceof RegExp)) {
+    return pattern;
+  }
+  return pattern.flags.includes('i') ? `(?i)${pattern.source}` : pattern.source;
+};
+
+// An alias entry is webpack's `{ name, alias, onlyModule }` or Vite's
+// `{ find, replacement }`, whose `find` may be a RegExp
+const normalizeAliasEntry = (entry) => {
+  if (entry.find instanceof RegExp) {
+    return { name: normalizePattern(entry.find), targets: [entry.replacement], regex: true };
+  }
+  if ('find' in entry) {
+    return { name: entry.find, targets: [entry.replacement] };
+  }
+  const name = entry.onlyModule ? `${entry.name}$` : entry.name;
+  return { name, targets: entry.alias === false ? [] : [].concat(entry.alias) };
+};
+
+// Both webpack and Vite also take an object mapping names to targets
+const normalizeAlias = (alias) => {
+  if (!alias) {
+    return alias;
+  }
+  const entries = Array.isArray(alias)
+    ? alias
+    : Object.entries(alias).map(([name, target]) => ({ name, alias: target }));
+  return entries.map(normalizeAliasEntry);
+};
+
+const normalizeOptions = (options = {}) => {
+  const project = options.project === undefined ? [] : [].concat(options.project);
+  const tsconfigContent =
+    options.tsconfigContent && typeof options.tsconfigContent === 'object'
+      ? JSON.stringify(options.tsconfigContent)
+      : options.tsconfigContent;
+  const importMap =
+    options.importMap && typeof options.importMap === 'object' ? JSON.stringify(options.importMap) : options.importMap;
+  const srcAlias = options.srcAlias === true ? 'src' : options.srcAlias || undefined;
+  const ignore = options.ignore && options.ignore.map(normalizePattern);
+  return {
+    ...options,
+    project,
+    tsconfigContent,
+    importMap,
+    srcAlias,
+    ignore,
+    alias: normalizeAlias(options.alias),
+    fallback: normalizeAlias(options.fallback),
+  };
+};
+
+exports.resolve = (source, file, options) => worker.resolve(source, file, normalizeOptions(options));
+
+exports.explain = (source, file, options) => worker.explain(source, file, normalizeOptions(options));
+
+exports.doctor = (options) => worker.doctor(normalizeOptions(options));
+
+exports.resolvePath = (source, file, options) => worker.resolvePath(source, file, normalizeOptions(options));
+
+exports.resolveAsync = (source, file, options) => worker.resolveAsync(source, file, normalizeOptions(options));
+
+exports.resolveBatch = (requests, options) => worker.resolveBatch(requests, normalizeOptions(options));
+
+exports.buildImportGraph = (entryGlobs, options) => worker.buildImportGraph(entryGlobs, normalizeOptions(options));
+
+exports.isCoreModule = worker.isCoreModule;
+
+exports.getEffectivePaths = (project, options) => worker.getEffectivePaths(project, normalizeOptions(options));
+
+exports.getProjectInfo = (file, options) => worker.getProjectInfo(file, normalizeOptions(options));
+
+exports.getCacheStats = worker.getCacheStats;
+
+exports.setLogger = worker.setLogger;
+
+exports.registerProtocolHandler = worker.registerProtocolHandler;
+
+exports.dumpConfig = (options) => worker.dumpConfig(normalizeOptions(options));
+
+exports.bumpGeneration = worker.bumpGeneration;
+
+exports.setStatCacheTtl = worker.setStatCacheTtl;
+
+let savesCacheOnExit = false;
+
+exports.setCacheDir = (dir, cwd) => {
+  const restored = worker.setCacheDir(dir, cwd);
+  if (!savesCacheOnExit) {
+    savesCacheOnExit = true;
+    // A cache that fails to save must not fail the lint run
+    process.once('exit', () => {
+      try {
+        worker.saveCache();
+      } catch {}
+    });
+  }
+  return restored;
+};
+
+exports.saveCache = worker.saveCache;
+
+exports.loadManifest = worker.loadManifest;
+
+exports.unloadManifest = worker.unloadManifest;
+
+exports.writeManifest = worker.writeManifest;
+
+exports.clearCache = worker.clearCache;
+
+exports.invalidate = worker.invalidate;
+
+exports.warm = (project, statNodeModules, options) =>
+  worker.warm(project, statNodeModules, normalizeOptions(options));
+
+exports.Resolver = class Resolver extends worker.Resolver {
+  constructor(options) {
+    super(normalizeOptions(options));
+  }
+};
+
+exports.createResolver = (options) => worker.createResolver(normalizeOptions(options));
+
+// A webpack `resolve.plugins` entry resolving requests the way ESLint does,
+// so lint and bundle agree. Entry points, which have no issuer, and requests
+// this resolver doesn't find fall through to the bundler's own resolution
+exports.ResolverPlugin = class ResolverPlugin {
+  constructor(options) {
+    this.options = normalizeOptions(options);
+  }
+
+  apply(resolver) {
+    const target = resolver.ensureHook('resolved');
+    resolver.getHook('described-resolve').tapAsync('ResolverPlugin', (request, resolveContext, callback) => {
+      const issuer = request.context && request.context.issuer;
+      if (!request.request || !issuer) {
+        return callback();
+      }
+      worker.resolveAsync(request.request, issuer, this.options).then((resolved) => {
+        // Builtins have no file to bundle
+        if (!resolved.found || !resolved.path) {
+          return callback();
+        }
+        const resolvedRequest = { ...request, path: resolved.path, request: undefined };
+        resolver.doResolve(target, resolvedRequest, `resolved by ${pkg.name}`, resolveContext, callback);
+      }, callback);
+    });
+  }
 };
 
 exports.interfaceVersion = 2;
+
+exports.name = pkg.name;
+
+exports.version = pkg.version;
@@ -0,0 +1,88 @@
+//! Follow TypeScript declaration maps (`foo.d.ts.map`) back to the original
+//! source a declaration file was generated from.
+//!
+//! ```text
+//! original_source(PathBuf::from("/repo/packages/a/dist/index.d.ts"));
+//! // → Some("/repo/packages/a/src/index.ts")
+//! ```
+
+use cached::proc_macro::cached;
+use cached::{Cached, SizedCache};
+use serde_json::Value;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+// Read `sources` of a source map, relative to the map's `sourceRoot` and
+// directory. Cached by map path.
+#[cached(
+  type = "SizedCache<PathBuf, Vec<PathBuf>>",
+  create = "{ SizedCache::with_size(crate::lru::capacity(1024)) }",
+  convert = "{ map_path.clone() }"
+)]
+fn read_map_sources(map_path: PathBuf) -> Vec<PathBuf> {
+  crate::logger::log(
+    crate::logger::LogEventKind::CacheMiss,
+    &map_path.to_string_lossy(),
+    || String::from("read declaration map"),
+  );
+  let map_dir = match map_path.parent() {
+    Some(map_dir) => map_dir.to_path_buf(),
+    None => return vec![],
+  };
+  let map: Value = match File::open(&map_path)
+    .ok()
+    .and_then(|file| serde_json::from_reader(file).ok())
+  {
+    Some(map) => map,
+    None => return vec![],
+  };
+
+  let source_root = map_dir.join(map["sourceRoot"].as_str().unwrap_or(""));
+  map["sources"]
+    .as_array()
+    .map(|sources| {
+      sources
+        .iter()
+        .filter_map(|source| source.as_str())
+        .map(|source| crate::node_resolve::normalize_path(&source_root.join(source)))
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Get the original source of a `.d.ts` file from its `.d.ts.map`, if the
+/// map exists and its first source is on disk.
+pub fn original_source(declaration: PathBuf) -> Option<PathBuf> {
+  let map_path = PathBuf::from(format!("{}.map", declaration.to_str()?));
+  if !map_path.is_file() {
+    return None;
+  }
+
+  read_map_sources(map_path)
+    .into_iter()
+    .next()
+    .filter(|source| source.is_file())
+}
+
+/// Drop all cached declaration maps.
+pub fn clear_cache() {
+  READ_MAP_SOURCES.lock().unwrap().cache_clear();
+}
+
+/// Drop the cached declaration map at `map_path`.
+pub fn invalidate(map_path: &Path) {
+  READ_MAP_SOURCES
+    .lock()
+    .unwrap()
+    .cache_remove(&map_path.to_path_buf());
+}
+
+/// Get hits, misses, and size of the declaration map cache.
+pub fn cache_stats() -> (u64, u64, usize) {
+  let cache = READ_MAP_SOURCES.lock().unwrap();
+  (
+    cache.cache_hits().unwrap_or(0),
+    cache.cache_misses().unwrap_or(0),
+    cache.cache_size(),
+  )
+}
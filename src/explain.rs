@@ -0,0 +1,79 @@
+//! Tell the steps of a resolution as a narrative, runs of probed files
+//! summed up in one line each.
+//!
+//! ```text
+//! narrate(&[
+//!   String::from("paths pattern \"@app/*\""),
+//!   String::from("paths target \"src/app/*\""),
+//!   String::from("probe /repo/src/app/foo: missing"),
+//!   String::from("probe /repo/src/app/foo.ts: found"),
+//! ]);
+//! // → ["paths pattern \"@app/*\"", "paths target \"src/app/*\"",
+//! //   "probed 2 files, found /repo/src/app/foo.ts"]
+//! ```
+
+// Sum up `count` probes, the last of which found `found`, if any
+fn summarize_probes(count: usize, found: Option<&str>) -> String {
+  let files = if count == 1 { "file" } else { "files" };
+  match found {
+    Some(found) => format!("probed {} {}, found {}", count, files, found),
+    None => format!("probed {} {}, none exist", count, files),
+  }
+}
+
+/// Turn trace steps into narrative lines, in order.
+pub fn narrate(steps: &[String]) -> Vec<String> {
+  let mut lines = vec![];
+  let mut probes = 0;
+  for step in steps {
+    let probe = step.strip_prefix("probe ");
+    if let Some(found) = probe.and_then(|probe| probe.strip_suffix(": found")) {
+      lines.push(summarize_probes(probes + 1, Some(found)));
+      probes = 0;
+      continue;
+    }
+    if probe.is_some() {
+      probes += 1;
+      continue;
+    }
+    if probes > 0 {
+      lines.push(summarize_probes(probes, None));
+      probes = 0;
+    }
+    lines.push(step.clone());
+  }
+  if probes > 0 {
+    lines.push(summarize_probes(probes, None));
+  }
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sums_up_runs_of_probes() {
+    let steps: Vec<String> = [
+      "project /repo/tsconfig.json",
+      "probe /repo/src/a: missing",
+      "probe /repo/src/a.ts: missing",
+      "paths pattern \"@/*\"",
+      "probe /repo/lib/a.ts: found",
+      "source of project reference /repo/src/a.ts",
+    ]
+    .iter()
+    .map(|step| String::from(*step))
+    .collect();
+    assert_eq!(
+      vec![
+        "project /repo/tsconfig.json",
+        "probed 2 files, none exist",
+        "paths pattern \"@/*\"",
+        "probed 1 file, found /repo/lib/a.ts",
+        "source of project reference /repo/src/a.ts",
+      ],
+      narrate(&steps)
+    );
+  }
+}
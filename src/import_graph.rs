@@ -0,0 +1,218 @@
+//! Build the graph of imports between files, starting from entry files and
+//! following every import that resolves to a script outside node_modules.
+//! Specifiers are found by a scan for `import`, `export .. from`, `import()`,
+//! and `require()` with string literals, not by a full parse.
+//!
+//! ```text
+//! build(vec![PathBuf::from("/repo/src/index.ts")], &resolver, |specifier, file| ..);
+//! // /repo/src/index.ts: import { a } from './a'; import 'lodash'
+//! // → files: {"/repo/src/index.ts": ["/repo/node_modules/lodash/index.js",
+//! //   "/repo/src/a.ts"], "/repo/src/a.ts": []}
+//! ```
+
+use crate::node_resolve::{is_in_node_modules, Resolver};
+use crate::protocol_handlers;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+#[derive(Default)]
+pub struct ImportGraph {
+  /// The files each scanned file imports, core modules left out.
+  pub files: BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+  /// The specifiers of each scanned file that didn't resolve.
+  pub unresolved: BTreeMap<PathBuf, BTreeSet<String>>,
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+  byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || !byte.is_ascii()
+}
+
+// Get the index of the quote closing the string literal opened at `start`
+fn find_string_end(bytes: &[u8], start: usize) -> usize {
+  let quote = bytes[start];
+  let mut i = start + 1;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\\' => i += 1,
+      b'\n' if quote != b'`' => return i,
+      byte if byte == quote => return i,
+      _ => {}
+    }
+    i += 1;
+  }
+  bytes.len()
+}
+
+// Check if a string literal following `tokens`, the latest last, is the
+// specifier of an import
+fn is_specifier_position(tokens: &[&str; 3]) -> bool {
+  match tokens {
+    // `import x from 'a'`, `export * from 'a'`, `import 'a'`
+    [_, before, "from" | "import"] => *before != ".",
+    // `require('a')`, `import('a')`, `import x = require('a')`
+    [before, "require" | "import", "("] => *before != ".",
+    _ => false,
+  }
+}
+
+fn push_token<'c>(tokens: &mut [&'c str; 3], token: &'c str) {
+  tokens.rotate_left(1);
+  tokens[2] = token;
+}
+
+/// Find the specifiers `content` imports, in order of appearance.
+pub fn find_specifiers(content: &str) -> Vec<String> {
+  let bytes = content.as_bytes();
+  let mut specifiers = vec![];
+  // the last three tokens, string literals as "\""
+  let mut tokens = ["", "", ""];
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'/' if bytes.get(i + 1) == Some(&b'/') => {
+        while i < bytes.len() && bytes[i] != b'\n' {
+          i += 1;
+        }
+      }
+      b'/' if bytes.get(i + 1) == Some(&b'*') => {
+        i = match content[i + 2..].find("*/") {
+          Some(end) => i + 2 + end + 2,
+          None => bytes.len(),
+        };
+      }
+      quote @ (b'\'' | b'"' | b'`') => {
+        let end = find_string_end(bytes, i);
+        let value = &content[i + 1..end.min(bytes.len())];
+        let is_static = quote != b'`' || !value.contains("${");
+        if is_static && is_specifier_position(&tokens) {
+          specifiers.push(String::from(value));
+        }
+        push_token(&mut tokens, "\"");
+        i = end + 1;
+      }
+      byte if is_identifier_byte(byte) => {
+        let start = i;
+        while i < bytes.len() && is_identifier_byte(bytes[i]) {
+          i += 1;
+        }
+        push_token(&mut tokens, &content[start..i]);
+      }
+      byte if byte.is_ascii_whitespace() => i += 1,
+      _ => {
+        push_token(&mut tokens, &content[i..i + 1]);
+        i += 1;
+      }
+    }
+  }
+  specifiers
+}
+
+fn is_script(path: &Path) -> bool {
+  path
+    .extension()
+    .is_some_and(|extension| SCRIPT_EXTENSIONS.iter().any(|script| extension == *script))
+}
+
+/// Build the import graph of `entries` and the files they import, resolving
+/// each specifier with `resolve`, core modules of `resolver`'s runtime and
+/// working dir left out. Files are scanned in parallel, one import depth at
+/// a time, unless protocol handlers are registered.
+pub fn build<F>(entries: Vec<PathBuf>, resolver: &Resolver, resolve: F) -> ImportGraph
+where
+  F: Fn(&str, &Path) -> Option<PathBuf> + Sync,
+{
+  let mut graph = ImportGraph::default();
+  let mut seen: HashSet<PathBuf> = entries.iter().cloned().collect();
+  let mut pending = entries;
+  while !pending.is_empty() {
+    let scan = |file: PathBuf| {
+      let content = fs::read_to_string(&file).unwrap_or_default();
+      let imports: Vec<Result<PathBuf, String>> = find_specifiers(content.as_str())
+        .into_iter()
+        .filter(|specifier| !resolver.is_core_module(specifier))
+        .map(|specifier| resolve(specifier.as_str(), &file).ok_or(specifier))
+        .collect();
+      (file, imports)
+    };
+    // Protocol handlers can only run on the JS thread this is called on
+    let scanned: Vec<(PathBuf, Vec<Result<PathBuf, String>>)> =
+      match protocol_handlers::is_registered() {
+        true => pending.into_iter().map(scan).collect(),
+        false => pending.into_par_iter().map(scan).collect(),
+      };
+
+    pending = vec![];
+    for (file, imports) in scanned {
+      let targets = graph.files.entry(file.clone()).or_default();
+      for import in imports {
+        match import {
+          Ok(target) => {
+            if is_script(&target) && !is_in_node_modules(&target) && seen.insert(target.clone()) {
+              pending.push(target.clone());
+            }
+            targets.insert(target);
+          }
+          Err(specifier) => {
+            graph
+              .unresolved
+              .entry(file.clone())
+              .or_default()
+              .insert(specifier);
+          }
+        }
+      }
+    }
+  }
+  graph
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn finds_static_and_dynamic_imports() {
+    let content = r#"
+import React, { useState } from "react";
+import type { Props } from './types';
+import './styles.css';
+export * from '../shared';
+export { default as Button } from "@org/ui/button";
+const lazy = import('./lazy');
+const fs = require("fs");
+import legacy = require('legacy');
+"#;
+    assert_eq!(
+      vec![
+        "react",
+        "./types",
+        "./styles.css",
+        "../shared",
+        "@org/ui/button",
+        "./lazy",
+        "fs",
+        "legacy"
+      ],
+      find_specifiers(content)
+    );
+  }
+
+  #[test]
+  fn skips_comments_and_other_strings() {
+    let content = r#"
+// import a from 'commented';
+/* require('commented') */
+const from = 'not-an-import';
+const url = `${base}/import`;
+loader.require('method');
+require.resolve('resolved');
+import(`./locales/${locale}`);
+console.log("import 'quoted'");
+"#;
+    assert_eq!(Vec::<String>::new(), find_specifiers(content));
+  }
+}
@@ -0,0 +1,227 @@
+//! Deno-flavored specifiers: `npm:` specifiers, and import maps such as
+//! `import_map.json`, the `imports` and `scopes` of a `deno.json`, or the
+//! JSON of a browser `<script type="importmap">`.
+//!
+//! ```text
+//! strip_npm_prefix("npm:@std/path@^1.0.0/posix");
+//! // → Some("@std/path/posix")
+//! map_specifier("import_map.json", Path::new("/repo"), "@/utils", Path::new("/repo/src/main.ts"));
+//! // /repo/import_map.json: { "imports": { "@/": "./src/" } }
+//! // → Some("/repo/src/utils")
+//! map_specifier(r#"{ "imports": { "lit": "./vendor/lit.js" } }"#, Path::new("/repo"), "lit", file);
+//! // → Some("/repo/vendor/lit.js")
+//! ```
+
+use cached::proc_macro::cached;
+use cached::{Cached, SizedCache};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+type Mappings = Vec<(String, String)>;
+
+#[derive(Clone, Default)]
+struct ImportMap {
+  imports: Mappings,
+  // mappings for importers under each path prefix, the longest first
+  scopes: Vec<(String, Mappings)>,
+}
+
+// Turn an address of an import map into a specifier: paths are relative to
+// the import map, anything else, e.g. "npm:" or URLs, is kept as is
+fn to_specifier(address: &str, map_dir: &Path) -> String {
+  if address.starts_with("./") || address.starts_with("../") || address.starts_with('/') {
+    let path = crate::node_resolve::normalize_path(&map_dir.join(address));
+    let mut specifier = path.to_string_lossy().into_owned();
+    // Keep the trailing slash of prefix mappings, which joining drops
+    if address.ends_with('/') && !specifier.ends_with('/') {
+      specifier.push('/');
+    }
+    return specifier;
+  }
+  String::from(address)
+}
+
+fn read_mappings(value: &Value, map_dir: &Path) -> Mappings {
+  let mut mappings: Mappings = value
+    .as_object()
+    .into_iter()
+    .flatten()
+    .filter_map(|(key, address)| Some((key.clone(), to_specifier(address.as_str()?, map_dir))))
+    .collect();
+  mappings.sort_by_key(|(key, _)| std::cmp::Reverse(key.len()));
+  mappings
+}
+
+// Get the `imports` and `scopes` of a parsed import map, with paths in it
+// relative to `map_dir`
+fn to_import_map(value: &Value, map_dir: &Path) -> ImportMap {
+  let mut scopes: Vec<(String, Mappings)> = value["scopes"]
+    .as_object()
+    .into_iter()
+    .flatten()
+    .map(|(scope, mappings)| {
+      (
+        to_specifier(scope, map_dir),
+        read_mappings(mappings, map_dir),
+      )
+    })
+    .collect();
+  scopes.sort_by_key(|(scope, _)| std::cmp::Reverse(scope.len()));
+  ImportMap {
+    imports: read_mappings(&value["imports"], map_dir),
+    scopes,
+  }
+}
+
+// Read the `imports` and `scopes` of an import map or deno.json, which may
+// have comments. Cached by path.
+#[cached(
+  type = "SizedCache<PathBuf, Arc<ImportMap>>",
+  create = "{ SizedCache::with_size(crate::lru::capacity(16)) }",
+  convert = "{ path.clone() }"
+)]
+fn read_import_map(path: PathBuf) -> Arc<ImportMap> {
+  crate::logger::log(
+    crate::logger::LogEventKind::CacheMiss,
+    &path.to_string_lossy(),
+    || String::from("read import map"),
+  );
+  match (crate::jsonc::parse_file(&path), path.parent()) {
+    (Some(value), Some(map_dir)) => Arc::new(to_import_map(&value, map_dir)),
+    _ => Arc::default(),
+  }
+}
+
+// Parse an import map given as JSON text, with paths in it relative to
+// `dir`. Cached by both.
+#[cached(
+  type = "SizedCache<(String, PathBuf), Arc<ImportMap>>",
+  create = "{ SizedCache::with_size(crate::lru::capacity(16)) }",
+  convert = "{ (content.clone(), dir.clone()) }"
+)]
+fn parse_inline_import_map(content: String, dir: PathBuf) -> Arc<ImportMap> {
+  match crate::jsonc::parse_str(&content) {
+    Some(value) => Arc::new(to_import_map(&value, &dir)),
+    None => Arc::default(),
+  }
+}
+
+/// Check if an `importMap` option is an import map as JSON text, rather
+/// than the path of one.
+pub fn is_inline(import_map: &str) -> bool {
+  import_map.trim_start().starts_with('{')
+}
+
+// Map `specifier` by the longest key equal to it, or ending in "/" and
+// prefixing it
+fn map_by(mappings: &Mappings, specifier: &str) -> Option<String> {
+  mappings.iter().find_map(|(key, address)| {
+    if key == specifier {
+      return Some(address.clone());
+    }
+    let rest = specifier.strip_prefix(key.as_str())?;
+    key.ends_with('/').then(|| format!("{}{}", address, rest))
+  })
+}
+
+/// Map `specifier` imported by `file` with the `importMap` option, a path or
+/// JSON text, relative to `cwd`: by the scopes containing `file`, most
+/// specific first, then by its `imports`.
+pub fn map_specifier(import_map: &str, cwd: &Path, specifier: &str, file: &Path) -> Option<String> {
+  let import_map = match is_inline(import_map) {
+    true => parse_inline_import_map(String::from(import_map), cwd.to_path_buf()),
+    false => read_import_map(cwd.join(import_map)),
+  };
+  let file = file.to_string_lossy();
+  import_map
+    .scopes
+    .iter()
+    .filter(|(scope, _)| file.starts_with(scope.as_str()))
+    .find_map(|(_, mappings)| map_by(mappings, specifier))
+    .or_else(|| map_by(&import_map.imports, specifier))
+}
+
+/// Turn an `npm:` specifier into the bare specifier of its package, without
+/// the version, e.g. "npm:preact@10/hooks" into "preact/hooks".
+pub fn strip_npm_prefix(specifier: &str) -> Option<String> {
+  let rest = specifier.strip_prefix("npm:")?;
+  let rest = rest.strip_prefix('/').unwrap_or(rest);
+  // The name is one segment, two for scoped packages
+  let name_start = match rest.strip_prefix('@') {
+    Some(scoped) => scoped.find('/').map(|slash| slash + 2)?,
+    None => 0,
+  };
+  let name_end = rest[name_start..]
+    .find('/')
+    .map_or(rest.len(), |slash| name_start + slash);
+  let (name, subpath) = rest.split_at(name_end);
+  let name = match name[name_start..].find('@') {
+    Some(at) => &name[..name_start + at],
+    None => name,
+  };
+  if name.is_empty() {
+    return None;
+  }
+  Some(format!("{}{}", name, subpath))
+}
+
+/// Drop all cached import maps.
+pub fn clear_cache() {
+  READ_IMPORT_MAP.lock().unwrap().cache_clear();
+  PARSE_INLINE_IMPORT_MAP.lock().unwrap().cache_clear();
+}
+
+/// Drop the cached import map at `path`.
+pub fn invalidate(path: &Path) {
+  READ_IMPORT_MAP
+    .lock()
+    .unwrap()
+    .cache_remove(&path.to_path_buf());
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn strips_npm_prefix_and_version() {
+    assert_eq!(Some(String::from("preact")), strip_npm_prefix("npm:preact"));
+    assert_eq!(
+      Some(String::from("preact/hooks")),
+      strip_npm_prefix("npm:preact@^10.19.0/hooks")
+    );
+    assert_eq!(
+      Some(String::from("@std/path/posix")),
+      strip_npm_prefix("npm:/@std/path@1/posix")
+    );
+    assert_eq!(
+      Some(String::from("@types/node")),
+      strip_npm_prefix("npm:@types/node")
+    );
+    assert_eq!(None, strip_npm_prefix("preact"));
+    assert_eq!(None, strip_npm_prefix("npm:@1.0.0"));
+  }
+
+  #[test]
+  fn maps_by_longest_key() {
+    let mappings = vec![
+      (String::from("lib/util/"), String::from("/repo/util/")),
+      (String::from("lib/"), String::from("/repo/lib/")),
+      (String::from("lib"), String::from("/repo/lib/mod.ts")),
+    ];
+    assert_eq!(
+      Some(String::from("/repo/lib/mod.ts")),
+      map_by(&mappings, "lib")
+    );
+    assert_eq!(
+      Some(String::from("/repo/util/a.ts")),
+      map_by(&mappings, "lib/util/a.ts")
+    );
+    assert_eq!(
+      Some(String::from("/repo/lib/b.ts")),
+      map_by(&mappings, "lib/b.ts")
+    );
+    assert_eq!(None, map_by(&mappings, "library"));
+  }
+}
@@ -1,29 +1,178 @@
 #![deny(clippy::all)]
 
-use cached::proc_macro::{cached, once};
+use cached::proc_macro::cached;
+use cached::{Cached, SizedCache};
 use glob::glob;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
+use napi::{bindgen_prelude::AsyncTask, Env, JsFunction, Task};
+use rayon::prelude::*;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
 use std::path::Path;
-use substring::Substring;
-use tsconfig::TsConfig;
+use std::sync::{Arc, Mutex, Once, OnceLock};
+use std::time::{Instant, SystemTime};
+use tsconfig::{References, TsConfig};
 
-mod node_resolve;
+use ts_import_resolver::node_resolve::{get_enclosing_package, is_in_node_modules};
+use ts_import_resolver::project::{
+  get_compiler_dir_option, get_extends_entries, get_raw_compiler_option, resolve_extends,
+  LookupOptions, Project, ProjectResolver, ResolveOutcome,
+};
+use ts_import_resolver::{
+  counters, dir_listing, jsonc, lru, node_resolve, package_index, project, realpath_cache,
+  stat_cache, timing, trace, workspace, Runtime,
+};
+
+mod declaration_map;
+mod explain;
+mod import_graph;
+mod import_map;
+mod logger;
+mod manifest;
+mod persistent_cache;
+mod protocol_handlers;
+mod resolution_cache;
+mod resolution_log;
+mod suggestions;
+mod validate;
 use std::{env::current_dir, path::PathBuf};
 
 #[macro_use]
 extern crate napi_derive;
 /** Remove any trailing querystring from module id. */
-fn remove_query_string(id: String) -> String {
-  let query_string_index = id.find('?');
-  if query_string_index.is_some() {
-    return String::from(id.substring(0, query_string_index.unwrap()));
+fn remove_query_string(id: &str) -> &str {
+  match id.split_once('?') {
+    Some((path, _)) => path,
+    None => id,
+  }
+}
+
+// Get the current dir, or an empty path if it is unavailable (e.g. deleted),
+// so lookups relative to it fail like missing files instead of panicking
+fn get_current_dir() -> PathBuf {
+  current_dir().unwrap_or_default()
+}
+
+// Get the dir relative paths are relative to: `cwd`, itself relative to the
+// current dir, else the current dir
+fn get_cwd_or_current(cwd: Option<&str>) -> PathBuf {
+  match cwd {
+    Some(cwd) => node_resolve::normalize_path(&get_current_dir().join(cwd)),
+    None => get_current_dir(),
+  }
+}
+
+// Get the dir relative paths of `options` are relative to
+fn get_cwd(options: &Options) -> PathBuf {
+  get_cwd_or_current(options.cwd.as_deref())
+}
+
+// Make a relative `file` absolute against the `cwd` of `options`, so its
+// imports resolve from its dir however ESLint passes it
+fn get_absolute_file(file: String, options: &Options) -> String {
+  if file.is_empty() || is_absolute(&file) {
+    return file;
+  }
+  path_to_string(&node_resolve::normalize_path(&get_cwd(options).join(file)))
+}
+
+// Check if `path` is absolute on this platform, e.g. "/repo/a.ts", or
+// "C:\repo\a.ts" on Windows
+fn is_absolute(path: &str) -> bool {
+  Path::new(path).is_absolute()
+}
+
+// Get the directory of a file, or an empty path for a root
+fn get_parent_dir(path: &Path) -> PathBuf {
+  path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+// Convert a path for JS, replacing invalid UTF-8
+fn path_to_string(path: &Path) -> String {
+  path.to_string_lossy().into_owned()
+}
+
+// Projects given as tsconfig content are keyed by this prefix and a hash
+// of the content and its dir
+const INLINE_TS_CONFIG_PREFIX: &str = "inline:";
+
+// Inline tsconfig contents and the dirs they act in by project key. Entries
+// are never dropped, the same content in the same dir always maps to the
+// same key
+static INLINE_TS_CONFIGS: Mutex<Vec<(String, String, PathBuf)>> = Mutex::new(Vec::new());
+
+// Register inline tsconfig content acting as a tsconfig.json in `dir`,
+// returning the project key to resolve with
+fn register_inline_ts_config(content: &str, dir: &Path) -> String {
+  let mut hasher = DefaultHasher::new();
+  (content, dir).hash(&mut hasher);
+  let key = format!("{}{:016x}", INLINE_TS_CONFIG_PREFIX, hasher.finish());
+
+  let mut inline_ts_configs = INLINE_TS_CONFIGS.lock().unwrap();
+  if !inline_ts_configs
+    .iter()
+    .any(|(inline_key, _, _)| inline_key == &key)
+  {
+    inline_ts_configs.push((key.clone(), String::from(content), dir.to_path_buf()));
+  }
+  key
+}
+
+fn get_inline_ts_config(ts_config_file: &str) -> Option<String> {
+  INLINE_TS_CONFIGS
+    .lock()
+    .unwrap()
+    .iter()
+    .find(|(key, _, _)| key == ts_config_file)
+    .map(|(_, content, _)| content.clone())
+}
+
+fn get_inline_ts_config_dir(ts_config_file: &str) -> Option<PathBuf> {
+  INLINE_TS_CONFIGS
+    .lock()
+    .unwrap()
+    .iter()
+    .find(|(key, _, _)| key == ts_config_file)
+    .map(|(_, _, dir)| dir.clone())
+}
+
+// Get the dir a tsconfig name is relative to, `cwd`, or `None` for absolute
+// paths and inline content, which is keyed by its dir
+fn get_relative_base(ts_config_file: &str, cwd: &Path) -> Option<PathBuf> {
+  if is_absolute(ts_config_file) || ts_config_file.starts_with(INLINE_TS_CONFIG_PREFIX) {
+    None
+  } else {
+    Some(cwd.to_path_buf())
   }
-  return id;
 }
 
 // Read tsConfig paths
-#[cached]
-fn get_ts_config_path(ts_config_file: String) -> PathBuf {
-  if ts_config_file.starts_with('/') {
+// Inline tsconfig content acts as a tsconfig.json in the dir it was given for
+// Relative names are relative to `cwd`. Cached by name and the dir it is
+// relative to, so relative names follow changes of `cwd`. Paths are
+// normalized, so every spelling of a tsconfig file shares the caches keyed
+// by its path
+#[cached(
+  type = "SizedCache<(String, Option<PathBuf>), PathBuf>",
+  create = "{ SizedCache::with_size(lru::capacity(1024)) }",
+  convert = "{ (ts_config_file.clone(), get_relative_base(&ts_config_file, cwd)) }"
+)]
+fn get_ts_config_path(ts_config_file: String, cwd: &Path) -> PathBuf {
+  node_resolve::normalize_path(&get_unnormalized_ts_config_path(ts_config_file, cwd))
+}
+
+fn get_unnormalized_ts_config_path(ts_config_file: String, cwd: &Path) -> PathBuf {
+  if ts_config_file.starts_with(INLINE_TS_CONFIG_PREFIX) {
+    get_inline_ts_config_dir(ts_config_file.as_str())
+      .unwrap_or_else(|| cwd.to_path_buf())
+      .join("tsconfig.json")
+  } else if is_absolute(&ts_config_file) {
     if ts_config_file.ends_with(".json") {
       Path::new(ts_config_file.as_str()).to_path_buf()
     } else {
@@ -32,218 +181,2961 @@ fn get_ts_config_path(ts_config_file: String) -> PathBuf {
         .to_path_buf()
     }
   } else {
-    Path::new(current_dir().unwrap().to_str().unwrap()).join(ts_config_file)
+    cwd.join(ts_config_file)
   }
 }
 
-#[once(time = 10, sync_writes = true)]
-fn get_ts_config(ts_config_file: String) -> Result<TsConfig, String> {
-  // Read tsConfig paths
-  let tsconfig_path = get_ts_config_path(ts_config_file);
+// Modification time and size of a file, `None` if it is missing
+type FileStamp = Option<(SystemTime, u64)>;
+
+fn get_file_stamp(path: &Path) -> FileStamp {
+  let metadata = fs::metadata(path).ok()?;
+  Some((metadata.modified().ok()?, metadata.len()))
+}
+
+// Get the files a tsconfig file `extends` directly. Cached by path and stamp
+#[cached(
+  type = "SizedCache<(PathBuf, FileStamp), Vec<PathBuf>>",
+  create = "{ SizedCache::with_size(lru::capacity(256)) }",
+  convert = "{ (ts_config_path.clone(), stamp) }"
+)]
+fn get_direct_extends(ts_config_path: PathBuf, stamp: FileStamp) -> Vec<PathBuf> {
+  let config = match stamp.and_then(|_| jsonc::parse_file(&ts_config_path)) {
+    Some(config) => config,
+    None => return vec![],
+  };
+  let extends = get_extends_entries(&config);
+  let ts_config_dir = get_parent_dir(&ts_config_path);
+  extends
+    .iter()
+    .filter_map(|extends| resolve_extends(extends, &ts_config_dir))
+    .collect()
+}
 
-  let config = TsConfig::parse_file(&tsconfig_path);
-  if config.is_ok() {
-    return Ok(config.unwrap());
+// Stamp a tsconfig file and every file it extends, directly or not, so that
+// a change to any of them is noticed
+fn get_ts_config_stamps(ts_config_path: &Path) -> Vec<(PathBuf, FileStamp)> {
+  let mut stamps: Vec<(PathBuf, FileStamp)> = vec![];
+  let mut pending = vec![ts_config_path.to_path_buf()];
+  while let Some(path) = pending.pop() {
+    if stamps.iter().any(|(stamped, _)| stamped == &path) {
+      continue;
+    }
+    let stamp = get_file_stamp(&path);
+    pending.extend(get_direct_extends(path.clone(), stamp));
+    stamps.push((path, stamp));
   }
-  Err(String::from("No tsConfig file found"))
+  stamps
 }
 
-// Get base dir to search for
-// 1. if no tsconfig file found. return current work dir
-// 2. if no baseUrl listed in tsconfig. return the tsconfig file directory
-// 3. if baseUrl is present. join baseUrl with tsconfig file directory as base dir
-#[cached]
-fn get_base_dir(ts_config_file: String) -> PathBuf {
-  let ts_config = get_ts_config(ts_config_file.clone());
+// A parsed tsconfig: the project imports resolve through, and the options
+// and references of the `tsconfig` crate
+struct ParsedTsConfig {
+  ts_config: TsConfig,
+  project: Arc<Project>,
+  output_dirs: OnceLock<OutputDirs>,
+}
+
+struct OutputDirs {
+  emitted: Vec<PathBuf>,
+  root_dir: PathBuf,
+}
 
-  // if no config file found
-  if ts_config.is_err() {
-    return current_dir().ok().unwrap();
+impl ParsedTsConfig {
+  fn new(ts_config: TsConfig, project: Project) -> Self {
+    ParsedTsConfig {
+      ts_config,
+      project: Arc::new(project),
+      output_dirs: OnceLock::new(),
+    }
   }
 
-  let compiler_options = ts_config
-    .clone()
-    .unwrap()
-    .to_owned()
-    .compiler_options
-    .clone();
+  // Get the dirs the project emits to, `outDir` and `declarationDir`, with
+  // the dir of the sources they mirror, `rootDir` or else the config's dir
+  fn get_output_dirs(&self, ts_config_path: &Path) -> &OutputDirs {
+    self.output_dirs.get_or_init(|| {
+      let emitted = ["outDir", "declarationDir"]
+        .iter()
+        .filter_map(|name| get_compiler_dir_option(ts_config_path, name))
+        .collect();
+      let root_dir = get_compiler_dir_option(ts_config_path, "rootDir")
+        .unwrap_or_else(|| get_parent_dir(ts_config_path));
+      OutputDirs { emitted, root_dir }
+    })
+  }
+}
 
-  let ts_config_dir = get_ts_config_path(ts_config_file.clone())
-    .parent()
-    .unwrap()
-    .to_path_buf();
-  // use tsconfig file path as base dir when no baseDir or no compiler options
-  if compiler_options.clone().is_none()
-    || compiler_options
-      .clone()
-      .and_then(|options| options.base_url)
-      .is_none()
-  {
-    return ts_config_dir;
+impl Deref for ParsedTsConfig {
+  type Target = TsConfig;
+
+  fn deref(&self) -> &TsConfig {
+    &self.ts_config
+  }
+}
+
+// Parse a tsconfig file with its `extends`. Cached by path and the stamps of
+// all files involved, so edits are picked up on the next call
+#[cached(
+  type = "SizedCache<(PathBuf, Vec<(PathBuf, FileStamp)>), Result<Arc<ParsedTsConfig>, String>>",
+  create = "{ SizedCache::with_size(lru::capacity(256)) }",
+  convert = "{ (ts_config_path.clone(), stamps.clone()) }"
+)]
+fn read_ts_config(
+  ts_config_path: PathBuf,
+  stamps: Vec<(PathBuf, FileStamp)>,
+) -> Result<Arc<ParsedTsConfig>, String> {
+  let _span = tracing::debug_span!("tsconfig", path = %ts_config_path.display()).entered();
+  logger::log(
+    logger::LogEventKind::TsconfigReload,
+    path_to_string(&ts_config_path).as_str(),
+    || format!("read tsconfig, {} file(s) involved", stamps.len()),
+  );
+  let ts_config =
+    TsConfig::parse_file(&ts_config_path).map_err(|error| match ts_config_path.is_file() {
+      true => format!("Invalid tsConfig: {:?}", error),
+      false => String::from("No tsConfig file found"),
+    })?;
+  let project =
+    Project::load(&ts_config_path).ok_or_else(|| String::from("No tsConfig file found"))?;
+  for entry in project.invalid_paths_entries() {
+    logger::log(
+      logger::LogEventKind::InvalidPathsPattern,
+      path_to_string(&ts_config_path).as_str(),
+      || format!("\"{}\" has more than one \"*\", skipped", entry),
+    );
+  }
+  // Configs inheriting `paths` as they are, e.g. the apps and libs of an Nx
+  // workspace extending one tsconfig.base.json, share the matcher of the
+  // config setting them, so it's built once per root config
+  if let Some(defined_in) = project.inherits_paths_of() {
+    let defining = get_ts_config(path_to_string(defined_in), &get_parent_dir(defined_in));
+    if let Ok(defining) = defining {
+      project.share_paths_matcher(&defining.project);
+    }
+  }
+  Ok(Arc::new(ParsedTsConfig::new(ts_config, project)))
+}
+
+// Parse inline tsconfig content. Cached by its key, a hash of the content
+#[cached(
+  type = "SizedCache<String, Result<Arc<ParsedTsConfig>, String>>",
+  create = "{ SizedCache::with_size(lru::capacity(256)) }",
+  convert = "{ ts_config_file.clone() }"
+)]
+fn read_inline_ts_config(ts_config_file: String) -> Result<Arc<ParsedTsConfig>, String> {
+  let content = get_inline_ts_config(ts_config_file.as_str())
+    .ok_or_else(|| String::from("No inline tsConfig found"))?;
+  let content = jsonc::strip(content.as_str());
+  let ts_config =
+    TsConfig::parse_str(content.as_str()).map_err(|_| String::from("Invalid inline tsConfig"))?;
+  let config = serde_json::from_str::<Value>(content.as_str()).unwrap_or_default();
+  let dir = get_inline_ts_config_dir(ts_config_file.as_str()).unwrap_or_default();
+  let project = Project::parse(&dir.join("tsconfig.json"), &config);
+  Ok(Arc::new(ParsedTsConfig::new(ts_config, project)))
+}
+
+// Get a parsed tsconfig, shared rather than copied, it's read per resolution
+fn get_ts_config(ts_config_file: String, cwd: &Path) -> Result<Arc<ParsedTsConfig>, String> {
+  if ts_config_file.starts_with(INLINE_TS_CONFIG_PREFIX) {
+    return read_inline_ts_config(ts_config_file);
   }
 
-  let base_url = compiler_options.unwrap().base_url.unwrap();
-  ts_config_dir.join(base_url)
+  // Read tsConfig paths
+  let ts_config_path = get_ts_config_path(ts_config_file, cwd);
+  let stamps = get_ts_config_stamps(&ts_config_path);
+  read_ts_config(ts_config_path, stamps)
+}
+
+// Get base dir to search for
+// 1. if no tsconfig file found. return the directory it was looked for in,
+//    the current work dir unless it is in another root
+// 2. if no baseUrl listed in tsconfig. return the tsconfig file directory
+// 3. if baseUrl is present. join baseUrl with the directory of the tsconfig
+//    file setting it, which may be one it extends
+fn get_base_dir(ts_config_file: String, cwd: &Path) -> PathBuf {
+  let ts_config = match get_ts_config(ts_config_file.clone(), cwd) {
+    Ok(ts_config) => ts_config,
+    // if no config file found
+    Err(_) => return get_parent_dir(&get_ts_config_path(ts_config_file, cwd)),
+  };
+  ts_config.project.base_dir().to_path_buf()
+}
+
+// Get tsconfig `paths`, including those inherited through `extends`, with
+// `${configDir}` expanded in their targets
+fn get_paths(ts_config_file: String, cwd: &Path) -> Option<HashMap<String, Vec<String>>> {
+  let ts_config = get_ts_config(ts_config_file, cwd).ok()?;
+  Some(ts_config.project.paths().iter().cloned().collect())
+}
+
+// Get tsconfig files of the project `references`
+fn get_references(ts_config_file: String, cwd: &Path) -> Vec<String> {
+  let ts_config = match get_ts_config(ts_config_file.clone(), cwd) {
+    Ok(ts_config) => ts_config,
+    Err(_) => return vec![],
+  };
+  let references = match &ts_config.references {
+    Some(References::References(references)) => references,
+    _ => return vec![],
+  };
+
+  let ts_config_dir = get_parent_dir(&get_ts_config_path(ts_config_file, cwd));
+  references
+    .iter()
+    .map(|reference| {
+      let path = ts_config_dir.join(reference.path.as_str());
+      let path = if reference.path.ends_with(".json") {
+        path
+      } else {
+        path.join("tsconfig.json")
+      };
+      path_to_string(&node_resolve::normalize_path(&path))
+    })
+    .collect()
+}
+
+// Why resolution failed
+#[napi(string_enum)]
+#[derive(Debug, PartialEq)]
+pub enum FailureReason {
+  // no candidate file exists
+  NotFound,
+  // the specifier is bare, and no tsconfig could be read for `paths`
+  NoTsconfig,
+  // the package has "exports", but none of them matches the subpath
+  NotExported,
+  // the matching "exports" target is `null`
+  BlockedByExportsNull,
+  // the file only exists with an extension that is not probed
+  ExtensionMissing,
+  // the file only exists with different letter case
+  CaseMismatch,
+  // the file is outside the project root, with `restrictToProject`
+  OutsideProject,
+  // the specifier matches no `resolveOnly` pattern, other resolvers handle it
+  NotHandled,
+  // the package isn't declared in the importer's package.json, with
+  // `strictDependencies`
+  UndeclaredDependency,
+}
+
+// Milliseconds spent per phase of one resolution. `statMs` is also counted
+// in the other phases
+#[napi(object)]
+#[derive(Clone)]
+pub struct ResolveTiming {
+  pub total_ms: f64,
+  pub tsconfig_ms: f64,
+  pub paths_ms: f64,
+  pub node_modules_ms: f64,
+  pub stat_ms: f64,
+}
+
+// File checks and package.json reads of one resolution, none when reused
+#[napi(object)]
+#[derive(Clone)]
+pub struct ProbeCounts {
+  pub file_checks: u32,
+  pub package_json_reads: u32,
+}
+
+// What one strategy, e.g. tsconfig `paths`, resolves a specifier to in one
+// project, collected with `searchMode: "exhaustive"`
+#[napi(object)]
+#[derive(Clone)]
+pub struct Candidate {
+  pub project: String,
+  // "paths", "workspaceAlias", "relative", "workspaceDependency", "node",
+  // "workspacePackage", "srcAlias", or "types"
+  pub strategy: String,
+  // the specifier tried, an alias target if aliased
+  pub specifier: String,
+  pub found: bool,
+  pub path: String,
+  pub reason: Option<FailureReason>,
 }
 
 #[napi_derive::napi(object)]
+#[derive(Clone)]
 pub struct ResolveResult {
   pub found: bool,
   pub path: String,
+  pub implementation_path: Option<String>,
+  pub reason: Option<FailureReason>,
+  pub attempted_paths: Option<Vec<String>>,
+  // specifiers close to one not found, e.g. "./utils" for "./utlis"
+  pub suggestions: Option<Vec<String>>,
+  // configuration and package problems met on the way, e.g. an unreadable
+  // tsconfig, which were ignored
+  pub warnings: Option<Vec<String>>,
+  // with `strict`, the other files the specifier could resolve to, e.g. of
+  // further `paths` targets or a same-named package
+  pub ambiguous_paths: Option<Vec<String>>,
+  // with `searchMode: "exhaustive"`, what every strategy resolves to in
+  // every project, including those after the one resolving
+  pub candidates: Option<Vec<Candidate>>,
+  pub trace: Option<Vec<String>>,
+  pub package_name: Option<String>,
+  pub package_version: Option<String>,
+  pub is_external: Option<bool>,
+  // in a package of the importing workspace, e.g. a `workspace:*` dependency
+  pub is_workspace_package: Option<bool>,
+  pub is_declaration_file: Option<bool>,
+  // resolved through `fallback`, after everything else failed
+  pub is_fallback: Option<bool>,
+  pub matched_pattern: Option<String>,
+  pub timing: Option<ResolveTiming>,
+  pub probe_counts: Option<ProbeCounts>,
 }
 
 #[napi(object)]
+#[derive(Debug)]
 pub struct Options {
   pub project: Option<Vec<String>>,
+  // dirs with their own node_modules and tsconfig.json, e.g. git submodules
+  // or vendored trees, searched instead of the current dir for their files
+  pub roots: Option<Vec<String>>,
+  // dir relative `file`s and paths of options, e.g. `project` and `roots`,
+  // are relative to, the current dir if unset
+  pub cwd: Option<String>,
+  pub extensions: Option<Vec<String>>,
+  pub extension_order_preset: Option<String>,
+  // probe order for tsconfig `paths` targets, TypeScript sources first if unset
+  pub paths_extensions: Option<Vec<String>>,
+  pub extra_extensions: Option<Vec<String>>,
+  pub frameworks: Option<Vec<String>>,
+  pub style_extensions: Option<Vec<String>>,
+  pub asset_extensions: Option<Vec<String>>,
+  pub assets_always_found: Option<bool>,
+  pub platforms: Option<Vec<String>>,
+  pub flow_declarations: Option<bool>,
+  pub follow_declaration_maps: Option<bool>,
+  // report workspace packages injected into node_modules, e.g. by pnpm or
+  // Rush, as their workspace sources
+  pub dedupe_injected_packages: Option<bool>,
+  // map outputs of referenced projects back to their sources, like editors
+  pub use_source_of_project_references: Option<bool>,
+  // alias workspace packages to the sources their package.json points to
+  pub workspace_aliases: Option<bool>,
+  // try bare specifiers as relative ones before packages, like webpack,
+  // e.g. "utils" as "./utils"
+  pub prefer_relative: Option<bool>,
+  // require extensions of local files, like ESM, e.g. "./foo.js" for foo.ts,
+  // leaving "./foo" and directories unresolved
+  pub fully_specified: Option<bool>,
+  // source root "~/*" and "@/*" map to when tsconfig `paths` doesn't, e.g.
+  // "src", relative to the `baseUrl` dir; index.js turns `true` into "src"
+  #[napi(ts_type = "boolean | string")]
+  pub src_alias: Option<String>,
+  pub declaration_preference: Option<String>,
+  // "failFast" to stop at the first strategy resolving, the default, or
+  // "exhaustive" to also report what every strategy resolves to
+  pub search_mode: Option<String>,
+  // "node", "bun", "browser", "workerd", or "electron": what core modules
+  // are, and the default `conditionNames` and `mainFields`
+  pub runtime: Option<String>,
+  pub main_fields: Option<Vec<String>>,
+  pub condition_names: Option<Vec<String>>,
+  // `conditionNames` of files matching patterns, e.g. of Electron's main and
+  // renderer processes; the first set matching a file applies
+  pub condition_sets: Option<Vec<ConditionSet>>,
+  // dirs packages are looked up in, like webpack's `resolve.modules`
+  pub modules: Option<Vec<String>>,
+  // webpack's `resolve.alias` or Vite's `resolve.alias`, applied before
+  // anything else; index.js turns both into entries
+  #[napi(
+    ts_type = "Record<string, string | Array<string> | false> | Array<{ name: string; alias: string | Array<string> | false; onlyModule?: boolean } | { find: string | RegExp; replacement: string }>"
+  )]
+  pub alias: Option<Vec<Alias>>,
+  // webpack's `resolve.fallback`: aliases tried only after a specifier
+  // failed to resolve, e.g. to shims of builtins; index.js turns them into
+  // entries like `alias`
+  #[napi(ts_type = "Record<string, string | Array<string> | false>")]
+  pub fallback: Option<Vec<Alias>>,
+  // import map, e.g. "import_map.json" or "deno.json", or its JSON, mapping
+  // specifiers before aliases; `npm:` specifiers resolve with or without it.
+  // index.js also accepts a parsed import map object
+  #[napi(ts_type = "string | Record<string, unknown>")]
+  pub import_map: Option<String>,
+  // regular expressions of specifiers reported as external without looking
+  // for them, e.g. "^electron$" or "^https?:"; index.js also takes RegExps
+  #[napi(ts_type = "Array<string | RegExp>")]
+  pub ignore: Option<Vec<String>>,
+  // glob patterns of the specifiers to resolve, e.g. "@org/**", leaving the
+  // others to the next resolver configured
+  pub resolve_only: Option<Vec<String>>,
+  pub always_try_types: Option<bool>,
+  // try tsconfig `paths` before node_modules, like tsc; defaults to true
+  pub prefer_ts_paths: Option<bool>,
+  // layer the nearest tsconfig.json above the importing file, e.g. its
+  // package's, over the configured projects, which act as fallback
+  pub nearest_tsconfig_first: Option<bool>,
+  pub preserve_symlinks: Option<bool>,
+  // webpack's name for the opposite of `preserveSymlinks`
+  pub symlinks: Option<bool>,
+  // block resolutions to files outside the root of the importing file, or
+  // `cwd`, e.g. through symlinks or "../" `paths` targets
+  pub restrict_to_project: Option<bool>,
+  // report specifiers that could resolve to several files in `ambiguousPaths`
+  pub strict: Option<bool>,
+  // fail imports of packages in node_modules that the nearest package.json
+  // of the importing file doesn't declare in `dependencyFields`
+  pub strict_dependencies: Option<bool>,
+  // "dependencies", "devDependencies", "peerDependencies", and
+  // "optionalDependencies" if unset
+  pub dependency_fields: Option<Vec<String>>,
+  pub trace: Option<bool>,
+  pub timing: Option<bool>,
+  // count file checks and package.json reads in `probeCounts`
+  pub probe_counts: Option<bool>,
+  pub metadata: Option<bool>,
+  // JSON or JSONC text; index.js also accepts a parsed config object
+  #[napi(ts_type = "string | Record<string, unknown>")]
+  pub tsconfig_content: Option<String>,
+  pub compiler_options: Option<CompilerOptions>,
 }
 
-// Resolve on single tsConfig project
-pub fn resolve_single_project(
-  source_input: String,
-  file: String,
-  ts_config_file: String,
-) -> ResolveResult {
-  // Remove query string
-  let source = remove_query_string(source_input);
+// An alias. `name` is replaced in specifiers equal to it or under it, e.g.
+// "name/file", or only in equal ones if it ends in "$". A `regex` name is
+// replaced where it matches first, with "$1" in targets for its groups
+#[napi(object)]
+#[derive(Debug)]
+pub struct Alias {
+  pub name: String,
+  // tried in turn; none ignore the module, like `false`
+  pub targets: Vec<String>,
+  pub regex: Option<bool>,
+}
+
+// Conditions to match for some files, instead of `conditionNames`
+#[napi(object)]
+#[derive(Debug)]
+pub struct ConditionSet {
+  // glob patterns, relative to `cwd`, e.g. "src/main/**"
+  pub files: Vec<String>,
+  pub condition_names: Vec<String>,
+}
+
+// Compiler options a caller already has, e.g. from parser services
+#[napi(object)]
+pub struct CompilerOptions {
+  pub base_url: Option<String>,
+  pub paths: Option<HashMap<String, Vec<String>>>,
+  pub custom_conditions: Option<Vec<String>>,
+}
+
+// Sorts `paths`, so equal options always print the same
+impl fmt::Debug for CompilerOptions {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CompilerOptions")
+      .field("base_url", &self.base_url)
+      .field(
+        "paths",
+        &self
+          .paths
+          .as_ref()
+          .map(|paths| paths.iter().collect::<BTreeMap<_, _>>()),
+      )
+      .field("custom_conditions", &self.custom_conditions)
+      .finish()
+  }
+}
+
+// "typescript-first": sources win over build artifacts sitting next to them
+const TYPESCRIPT_FIRST_EXTENSIONS: &[&str] = &[
+  ".ts", ".tsx", ".d.ts", ".mjs", ".cjs", ".js", ".json", ".node",
+];
 
-  let base_dir = get_base_dir(ts_config_file.clone());
-
-  // Start resolve normal paths
-  let resolver = node_resolve::Resolver::new()
-    .with_extensions(&[
-      String::from(".js"),
-      String::from(".json"),
-      String::from(".node"),
-      String::from(".mjs"),
-      String::from(".cjs"),
-      String::from(".ts"),
-      String::from(".tsx"),
-      String::from(".d.ts"),
-    ])
-    .with_basedir(base_dir.to_path_buf())
-    .with_main_fields(&[
-      String::from("types"),
-      String::from("typings"),
-      // APF: https://angular.io/guide/angular-package-format
-      String::from("fesm2020"),
-      String::from("fesm2015"),
-      String::from("esm2020"),
-      String::from("es2020"),
-      String::from("module"),
-      String::from("jsnext:main"),
-      String::from("main"),
-    ]);
-
-  let mut resolved;
-  if file.starts_with("/") {
-    let base_dir = PathBuf::from(file).parent().unwrap().to_path_buf();
-
-    if !source.starts_with('.') {
-      resolved = resolver.resolve(source.as_str());
+// Component extensions of the opt-in `frameworks` presets
+fn get_framework_extensions(framework: &str) -> &'static [&'static str] {
+  match framework {
+    "vue" => &[".vue"],
+    "svelte" => &[".svelte"],
+    "astro" => &[".astro"],
+    _ => &[],
+  }
+}
+
+// Get extensions to probe for tsconfig `paths` targets, in order. Without
+// `pathsExtensions`, those of `get_extensions` with TypeScript sources
+// first, then declarations, so sources win over stale build output
+fn get_paths_extensions(options: &Options) -> Vec<String> {
+  if let Some(paths_extensions) = &options.paths_extensions {
+    return paths_extensions.clone();
+  }
+  let mut extensions = get_extensions(options);
+  extensions.sort_by_key(|ext| {
+    if is_declaration_file(ext) {
+      1
+    } else if [".ts", ".tsx", ".mts", ".cts"]
+      .iter()
+      .any(|source| ext.ends_with(source))
+    {
+      0
     } else {
-      resolved = resolver.with_basedir(base_dir).resolve(source.as_str());
+      2
+    }
+  });
+  extensions
+}
+
+// Get extensions to probe, in order
+// 1. an explicit `extensions` list is used as is
+// 2. otherwise the list of `extensionOrderPreset`
+// 3. otherwise the default list
+// each of `platforms` (e.g. "ios") suffixes that list and goes first
+// extensions of `frameworks` presets and `extraExtensions` (e.g. ".vue") are
+// probed after all of them
+fn get_extensions(options: &Options) -> Vec<String> {
+  let extensions = match &options.extensions {
+    Some(extensions) => extensions.clone(),
+    None => {
+      let preset = match options.extension_order_preset.as_deref() {
+        Some("typescript-first") => TYPESCRIPT_FIRST_EXTENSIONS,
+        _ => project::DEFAULT_EXTENSIONS,
+      };
+      preset.iter().map(|ext| String::from(*ext)).collect()
+    }
+  };
+
+  // React Native: `foo.ios.ts`, `foo.native.ts`, ... win over `foo.ts`
+  let platform_extensions = options
+    .platforms
+    .iter()
+    .flatten()
+    .flat_map(|platform| {
+      let platform = platform.trim_start_matches('.');
+      extensions
+        .iter()
+        .map(move |ext| format!(".{}{}", platform, ext))
+    })
+    .collect::<Vec<String>>();
+  let mut extensions = [platform_extensions, extensions].concat();
+
+  let framework_extensions = options
+    .frameworks
+    .iter()
+    .flatten()
+    .flat_map(|framework| get_framework_extensions(framework))
+    .map(|ext| String::from(*ext));
+  let extra_extensions = options.extra_extensions.iter().flatten().cloned();
+  for ext in framework_extensions.chain(extra_extensions) {
+    if !extensions.contains(&ext) {
+      extensions.push(ext);
     }
-  } else {
-    resolved = resolver
-      .with_basedir(base_dir.to_path_buf())
-      .resolve(source.as_str());
   }
+  extensions
+}
 
-  if resolved.is_ok() {
-    return ResolveResult {
-      found: true,
-      path: String::from(resolved.ok().unwrap().to_str().unwrap()),
-    };
+// Is source a style sheet or asset listed in `styleExtensions` (".css",
+// ".scss") or `assetExtensions` (".svg", ".woff2").
+// CSS modules (`styles.module.scss`) are matched by their last extension.
+fn is_asset(source: &str, options: &Options) -> bool {
+  options
+    .style_extensions
+    .iter()
+    .flatten()
+    .chain(options.asset_extensions.iter().flatten())
+    .any(|ext| source.ends_with(ext.as_str()))
+}
+
+fn get_runtime(options: &Options) -> Option<Runtime> {
+  options.runtime.as_deref().and_then(Runtime::from_name)
+}
+
+// Get package.json fields to read the entry point from, in order.
+// An explicit `mainFields` list is used as is, otherwise those of the
+// `runtime` after the type fields, by default a mix fit for Angular. Then
+// "react-native" goes first when resolving for React Native `platforms`.
+fn get_main_fields(options: &Options) -> Vec<String> {
+  if let Some(main_fields) = &options.main_fields {
+    return main_fields.clone();
+  }
+
+  let mut main_fields: Vec<String> = match get_runtime(options) {
+    Some(runtime) => ["types", "typings"]
+      .iter()
+      .chain(runtime.main_fields())
+      .map(|field| String::from(*field))
+      .collect(),
+    None => project::DEFAULT_MAIN_FIELDS
+      .iter()
+      .map(|field| String::from(*field))
+      .collect(),
+  };
+  if options.platforms.iter().flatten().next().is_some() {
+    main_fields.insert(0, String::from("react-native"));
   }
+  main_fields
+}
 
-  resolved = resolver
-    .with_basedir(base_dir.to_path_buf())
-    .resolve(format!("@types/{}", source.as_str()).as_str());
+// Get package.json "exports" conditions to match, unless `conditionNames` is
+// given: "types", then those of the `runtime`, by default a mix fit for
+// Angular. Plus the `customConditions` of given compiler options
+fn get_condition_names(options: &Options) -> Vec<String> {
+  let custom_conditions = get_custom_conditions(options);
+  if let Some(condition_names) = &options.condition_names {
+    return [condition_names.clone(), custom_conditions].concat();
+  }
 
-  if resolved.is_ok() {
-    return ResolveResult {
-      found: true,
-      path: String::from(resolved.ok().unwrap().to_str().unwrap()),
-    };
+  if let Some(runtime) = get_runtime(options) {
+    let condition_names = ["types"]
+      .iter()
+      .chain(runtime.condition_names())
+      .map(|condition| String::from(*condition))
+      .collect();
+    return [condition_names, custom_conditions].concat();
   }
 
-  let paths_map = get_ts_config(ts_config_file.clone().to_string())
-    .ok()
-    .and_then(|config| config.compiler_options)
-    .and_then(|option| option.paths);
+  let condition_names = project::DEFAULT_CONDITION_NAMES
+    .iter()
+    .map(|condition| String::from(*condition))
+    .collect();
+  [condition_names, custom_conditions].concat()
+}
 
-  if paths_map.is_none() {
-    return ResolveResult {
-      found: false,
-      path: String::from(""),
+fn get_custom_conditions(options: &Options) -> Vec<String> {
+  options
+    .compiler_options
+    .as_ref()
+    .and_then(|compiler_options| compiler_options.custom_conditions.clone())
+    .unwrap_or_default()
+}
+
+#[cached(
+  type = "SizedCache<String, Option<glob::Pattern>>",
+  create = "{ SizedCache::with_size(lru::capacity(64)) }",
+  convert = "{ pattern.clone() }"
+)]
+fn compile_file_pattern(pattern: String) -> Option<glob::Pattern> {
+  let pattern = node_resolve::normalize_path(Path::new(pattern.as_str()));
+  glob::Pattern::new(pattern.to_string_lossy().as_ref()).ok()
+}
+
+#[cached(
+  type = "SizedCache<String, Option<glob::Pattern>>",
+  create = "{ SizedCache::with_size(lru::capacity(64)) }",
+  convert = "{ pattern.clone() }"
+)]
+fn compile_specifier_pattern(pattern: String) -> Option<glob::Pattern> {
+  glob::Pattern::new(pattern.as_str()).ok()
+}
+
+// Check if `source` is left to other resolvers, matching no `resolveOnly`
+// pattern. A "*" doesn't match across "/", a "**" does
+fn is_not_handled(source: &str, options: &Options) -> bool {
+  let patterns = match &options.resolve_only {
+    Some(patterns) => patterns,
+    None => return false,
+  };
+  let match_options = glob::MatchOptions {
+    require_literal_separator: true,
+    ..Default::default()
+  };
+  !patterns.iter().any(|pattern| {
+    compile_specifier_pattern(pattern.clone())
+      .is_some_and(|pattern| pattern.matches_with(source, match_options))
+  })
+}
+
+// Get the index of the first `conditionSets` entry matching `file`
+fn get_condition_set_index(file: &Path, options: &Options) -> Option<usize> {
+  let match_options = glob::MatchOptions {
+    require_literal_separator: true,
+    ..Default::default()
+  };
+  let cwd = get_cwd(options);
+  options.condition_sets.iter().flatten().position(|set| {
+    set.files.iter().any(|pattern| {
+      compile_file_pattern(path_to_string(&cwd.join(pattern)))
+        .is_some_and(|pattern| pattern.matches_path_with(file, match_options))
+    })
+  })
+}
+
+// Get the conditions of the `conditionSets` entry matching `file`, plus the
+// `customConditions` of given compiler options
+fn get_file_condition_names(file: &Path, options: &Options) -> Option<Vec<String>> {
+  let index = get_condition_set_index(file, options)?;
+  let condition_set = &options.condition_sets.as_ref()?[index];
+  Some(
+    [
+      condition_set.condition_names.clone(),
+      get_custom_conditions(options),
+    ]
+    .concat(),
+  )
+}
+
+// Get the runtime implementation next to a declaration file,
+// e.g. `foo.js` for `foo.d.ts` or `foo.mjs` for `foo.d.mts`
+fn get_implementation_path(declaration_path: &str) -> Option<String> {
+  let (stem, extensions): (&str, &[&str]) =
+    if let Some(stem) = declaration_path.strip_suffix(".d.ts") {
+      (stem, &[".js", ".mjs", ".cjs"])
+    } else if let Some(stem) = declaration_path.strip_suffix(".d.mts") {
+      (stem, &[".mjs"])
+    } else if let Some(stem) = declaration_path.strip_suffix(".d.cts") {
+      (stem, &[".cjs"])
+    } else {
+      return None;
     };
+
+  extensions
+    .iter()
+    .map(|ext| format!("{}{}", stem, ext))
+    .find(|path| Path::new(path.as_str()).is_file())
+}
+
+// Map a file of an injected copy of a workspace package, e.g. pnpm's
+// hard-linked `node_modules/.pnpm/file+packages+ui/node_modules/@org/ui`,
+// back to the same file in the workspace package itself
+fn get_injected_source(path: &Path) -> Option<PathBuf> {
+  if !is_in_node_modules(path) {
+    return None;
+  }
+  let (pkg_dir, pkg) = get_enclosing_package(path)?;
+  let workspace_dir = workspace::find_package(&pkg_dir, pkg["name"].as_str()?)?;
+  let source = workspace_dir.join(path.strip_prefix(&pkg_dir).ok()?);
+  if stat_cache::is_file(&source) {
+    Some(source)
+  } else {
+    None
   }
+}
 
-  // Iter paths to do full path match
-  for (path_pattern, dest_paths) in paths_map.unwrap() {
-    let star_match = node_resolve::match_star(path_pattern.clone(), source.clone());
+// Build the result for a resolved file
+// 1. with `followDeclarationMaps`, a `foo.d.ts` with a `foo.d.ts.map` reports
+//    the original source the map points to
+// 2. with `declarationPreference` "implementation", a `foo.d.ts` reports its
+//    `foo.js`; with "both", `foo.js` is reported as `implementationPath`
+// 3. with `dedupeInjectedPackages`, a file of an injected copy of a
+//    workspace package reports the package's own file
+// 4. with `flowDeclarations`, a `foo.js.flow` sidecar wins over `foo.js`
+// 5. otherwise the resolved file itself
+// With `metadata`, files (not builtins) also report where they come from
+fn found(resolved: PathBuf, options: &Options) -> ResolveResult {
+  let mut path = path_to_string(&resolved);
+  let mut implementation_path = None;
 
-    if star_match.is_err() {
-      continue;
+  if options.follow_declaration_maps == Some(true) && path.ends_with(".d.ts") {
+    if let Some(source) = declaration_map::original_source(resolved) {
+      path = path_to_string(&source);
     }
+  }
 
-    for dest_path in dest_paths.iter() {
-      let physical_path = dest_path.replace("*", star_match.clone().unwrap().as_str());
-      resolved = resolver.with_basedir(base_dir.clone()).resolve(
-        base_dir
-          .join(physical_path.clone())
-          .to_path_buf()
-          .to_str()
-          .unwrap(),
-      );
-
-      if resolved.is_ok() {
-        return ResolveResult {
-          found: true,
-          path: String::from(resolved.ok().unwrap().to_str().unwrap()),
-        };
+  match options.declaration_preference.as_deref() {
+    Some("implementation") => {
+      if let Some(implementation) = get_implementation_path(path.as_str()) {
+        path = implementation;
       }
     }
+    Some("both") => implementation_path = get_implementation_path(path.as_str()),
+    _ => {}
+  }
+
+  if options.dedupe_injected_packages == Some(true) {
+    if let Some(source) = get_injected_source(Path::new(path.as_str())) {
+      path = path_to_string(&source);
+    }
+  }
+
+  if options.flow_declarations == Some(true) && path.ends_with(".js") {
+    let flow_path = format!("{}.flow", path);
+    if Path::new(flow_path.as_str()).is_file() {
+      path = flow_path;
+    }
   }
 
+  let metadata = options.metadata == Some(true) && !path.is_empty();
+  let (package_name, package_version, is_workspace_package) = if metadata {
+    get_package_info(Path::new(path.as_str()))
+  } else {
+    (None, None, false)
+  };
+  ResolveResult {
+    found: true,
+    is_external: metadata
+      .then(|| !is_workspace_package && is_in_node_modules(Path::new(path.as_str()))),
+    is_workspace_package: metadata.then_some(is_workspace_package),
+    is_declaration_file: metadata.then(|| is_declaration_file(path.as_str())),
+    is_fallback: None,
+    path,
+    implementation_path,
+    reason: None,
+    attempted_paths: None,
+    suggestions: None,
+    warnings: None,
+    ambiguous_paths: None,
+    candidates: None,
+    trace: None,
+    package_name,
+    package_version,
+    matched_pattern: None,
+    timing: None,
+    probe_counts: None,
+  }
+}
+
+// Report a module found outside the file system, e.g. one the runtime
+// provides
+fn external(options: &Options) -> ResolveResult {
   ResolveResult {
-    found: false,
     path: String::from(""),
+    is_external: Some(true),
+    ..found(PathBuf::new(), options)
   }
 }
 
-// TODO: Implement package export syntax
-#[napi]
-pub fn resolve(source_input: String, file: String, options: Options) -> ResolveResult {
-  for ts_config_file in options.project.clone().unwrap().iter() {
-    let physical_ts_config_path = if ts_config_file.starts_with("/") {
-      ts_config_file.clone()
-    } else {
-      String::from(
-        current_dir()
-          .unwrap()
-          .join(ts_config_file)
-          .to_str()
-          .unwrap(),
+fn is_declaration_file(path: &str) -> bool {
+  [".d.ts", ".d.mts", ".d.cts"]
+    .iter()
+    .any(|ext| path.ends_with(ext))
+}
+
+// Get the name of the package in node_modules a file belongs to, e.g.
+// "@org/ui" for node_modules/@org/ui/index.js, `None` outside node_modules
+fn get_installed_package_name(path: &Path) -> Option<String> {
+  let components: Vec<&str> = path.iter().filter_map(|part| part.to_str()).collect();
+  let index = components
+    .iter()
+    .rposition(|part| *part == "node_modules")?;
+  match components.get(index + 1..index + 3)? {
+    [scope, name] if scope.starts_with('@') => Some(format!("{}/{}", scope, name)),
+    [name, ..] => Some(String::from(*name)),
+    _ => None,
+  }
+}
+
+// Get the name of the package `path` belongs to if `strictDependencies` is
+// set and the nearest package.json above `file` doesn't declare it. Type
+// packages count as declared with the package they type, and a package
+// importing itself is declared
+fn find_undeclared_dependency(path: &str, file: &str, options: &Options) -> Option<String> {
+  if options.strict_dependencies != Some(true) || !is_absolute(file) {
+    return None;
+  }
+  let name = get_installed_package_name(Path::new(path))?;
+  let pkg = Path::new(file)
+    .ancestors()
+    .skip(1)
+    .find_map(|dir| node_resolve::read_package_json(&dir.join("package.json")).ok())?;
+  // e.g. "@babel/core" for "@types/babel__core"
+  let typed = name
+    .strip_prefix("@types/")
+    .map(|typed| match typed.split_once("__") {
+      Some((scope, name)) => format!("@{}/{}", scope, name),
+      None => String::from(typed),
+    });
+  let default_fields = workspace::DEPENDENCY_FIELDS
+    .iter()
+    .map(|field| String::from(*field));
+  let fields: Vec<String> = match &options.dependency_fields {
+    Some(fields) => fields.clone(),
+    None => default_fields.collect(),
+  };
+  let is_declared = |name: &str| {
+    pkg["name"].as_str() == Some(name)
+      || fields
+        .iter()
+        .any(|field| pkg[field.as_str()].get(name).is_some())
+  };
+  if is_declared(name.as_str()) || typed.as_deref().is_some_and(is_declared) {
+    return None;
+  }
+  Some(name)
+}
+
+// Get `name` and `version` of the package a file belongs to, and whether it
+// is a package of the workspace around it
+fn get_package_info(path: &Path) -> (Option<String>, Option<String>, bool) {
+  match get_enclosing_package(path) {
+    Some((pkg_dir, pkg)) => {
+      let name = pkg["name"].as_str().map(String::from);
+      let is_workspace_package = name
+        .as_deref()
+        .and_then(|name| workspace::find_package(&pkg_dir, name))
+        .is_some_and(|dir| dir == pkg_dir);
+      (
+        name,
+        pkg["version"].as_str().map(String::from),
+        is_workspace_package,
       )
-    };
-    for entry in glob(physical_ts_config_path.clone().as_str())
-      .unwrap()
-      .filter_map(|p| p.ok())
-    {
-      let resolved = resolve_single_project(
-        source_input.clone(),
-        file.clone(),
-        String::from(entry.to_str().unwrap()),
-      );
-      if resolved.found {
-        return resolved;
+    }
+    None => (None, None, false),
+  }
+}
+
+fn not_found(reason: FailureReason) -> ResolveResult {
+  ResolveResult {
+    found: false,
+    path: String::from(""),
+    implementation_path: None,
+    reason: Some(reason),
+    attempted_paths: None,
+    suggestions: None,
+    warnings: None,
+    ambiguous_paths: None,
+    candidates: None,
+    trace: None,
+    package_name: None,
+    package_version: None,
+    is_external: None,
+    is_workspace_package: None,
+    is_declaration_file: None,
+    is_fallback: None,
+    matched_pattern: None,
+    timing: None,
+    probe_counts: None,
+  }
+}
+
+fn get_failure_reason(error: &node_resolve::ResolutionError) -> FailureReason {
+  match error.kind() {
+    node_resolve::ResolutionErrorKind::NotFound => FailureReason::NotFound,
+    node_resolve::ResolutionErrorKind::NotExported => FailureReason::NotExported,
+    node_resolve::ResolutionErrorKind::BlockedByExportsNull => FailureReason::BlockedByExportsNull,
+  }
+}
+
+// Rank failure reasons by how much they tell about the failure
+fn get_failure_rank(reason: &Option<FailureReason>) -> u8 {
+  match reason {
+    Some(FailureReason::NoTsconfig) => 0,
+    Some(FailureReason::NotFound) | None => 1,
+    _ => 2,
+  }
+}
+
+// Keep `resolved` as the failure to report if its reason is more telling
+// than that of the one kept so far
+fn keep_more_telling(failed: &mut Option<ResolveResult>, resolved: ResolveResult) {
+  let is_more_telling = match failed {
+    Some(failed) => get_failure_rank(&resolved.reason) > get_failure_rank(&failed.reason),
+    None => true,
+  };
+  if is_more_telling {
+    *failed = Some(resolved);
+  }
+}
+
+// Tell why a file path missed
+// 1. a file exists with different letter case, e.g. `./Foo` vs `foo.ts`
+// 2. a file exists with an extension that is not probed, e.g. `foo.mts`
+fn diagnose_missing_file(path: &Path, extensions: &[String]) -> FailureReason {
+  let name = path.file_name().and_then(|name| name.to_str());
+  let entries = path.parent().and_then(|dir| fs::read_dir(dir).ok());
+  let (name, entries) = match (name, entries) {
+    (Some(name), Some(entries)) => (name, entries),
+    _ => return FailureReason::NotFound,
+  };
+  let entry_names = entries
+    .filter_map(|entry| entry.ok())
+    .filter_map(|entry| entry.file_name().to_str().map(String::from))
+    .collect::<Vec<String>>();
+
+  let candidates = [String::from(name)]
+    .into_iter()
+    .chain(extensions.iter().map(|ext| format!("{}{}", name, ext)))
+    .collect::<Vec<String>>();
+  let is_case_mismatch = entry_names.iter().any(|entry_name| {
+    candidates
+      .iter()
+      .any(|candidate| entry_name != candidate && entry_name.eq_ignore_ascii_case(candidate))
+  });
+  if is_case_mismatch {
+    return FailureReason::CaseMismatch;
+  }
+
+  let prefix = format!("{}.", name);
+  if entry_names
+    .iter()
+    .any(|entry_name| entry_name.starts_with(&prefix))
+  {
+    return FailureReason::ExtensionMissing;
+  }
+  FailureReason::NotFound
+}
+
+// Log `tracing` spans and events to stderr when `RUST_LOG` is set, e.g.
+// "ts_import_resolver=trace" for every probed file
+fn init_tracing() {
+  static INIT: Once = Once::new();
+  INIT.call_once(|| {
+    if std::env::var_os("RUST_LOG").is_none() {
+      return;
+    }
+    // Another subscriber of the process wins
+    let _ = tracing_subscriber::fmt()
+      .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+      .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+      .with_writer(std::io::stderr)
+      .try_init();
+  });
+}
+
+// Build the node resolver for options, without a base dir
+fn build_resolver(options: &Options) -> node_resolve::Resolver {
+  init_tracing();
+  node_resolve::Resolver::new()
+    .with_extensions(get_extensions(options))
+    .with_main_fields(get_main_fields(options))
+    .with_condition_names(get_condition_names(options))
+    .with_modules(get_modules(options))
+    .with_runtime(get_runtime(options).unwrap_or_default())
+    .preserve_symlinks(get_preserve_symlinks(options))
+    .with_cwd(get_cwd(options))
+    .fully_specified(options.fully_specified == Some(true))
+}
+
+fn get_modules(options: &Options) -> Vec<String> {
+  match &options.modules {
+    Some(modules) => modules.clone(),
+    None => vec![String::from("node_modules")],
+  }
+}
+
+fn get_preserve_symlinks(options: &Options) -> bool {
+  options
+    .preserve_symlinks
+    .or(options.symlinks.map(|symlinks| !symlinks))
+    .unwrap_or(false)
+}
+
+#[napi(object)]
+pub struct ResolverConfig {
+  pub projects: Vec<String>,
+  pub roots: Vec<String>,
+  pub extensions: Vec<String>,
+  pub paths_extensions: Vec<String>,
+  pub main_fields: Vec<String>,
+  pub condition_names: Vec<String>,
+  pub modules: Vec<String>,
+  pub runtime: String,
+  pub builtins_source: String,
+  pub preserve_symlinks: bool,
+  pub caches: Vec<String>,
+}
+
+// Get the settings a resolver built from `options` works with, after
+// defaults, presets, and platforms are applied
+fn get_resolver_config(options: &Options, projects: &[String]) -> ResolverConfig {
+  let builtins_source =
+    node_resolve::normalize_path(&get_cwd(options).join(node_resolve::BUILTINS_DIR));
+  let mut caches = vec![
+    String::from("tsconfigPaths"),
+    String::from("tsconfigs"),
+    String::from("resolutions"),
+    String::from("fileStats"),
+    String::from("dirListings"),
+    String::from("packageJsons"),
+    String::from("nodeModulesIndex"),
+    String::from("realpaths"),
+  ];
+  if options.follow_declaration_maps == Some(true) {
+    caches.push(String::from("declarationMaps"));
+  }
+  ResolverConfig {
+    projects: projects.to_vec(),
+    roots: get_roots(options)
+      .iter()
+      .map(|root| path_to_string(root))
+      .collect(),
+    extensions: get_extensions(options),
+    paths_extensions: get_paths_extensions(options),
+    main_fields: get_main_fields(options),
+    condition_names: get_condition_names(options),
+    modules: get_modules(options),
+    runtime: String::from(get_runtime(options).unwrap_or_default().name()),
+    builtins_source: path_to_string(&builtins_source),
+    preserve_symlinks: get_preserve_symlinks(options),
+    caches,
+  }
+}
+
+#[cached(
+  type = "SizedCache<String, Option<Regex>>",
+  create = "{ SizedCache::with_size(lru::capacity(64)) }",
+  convert = "{ pattern.clone() }"
+)]
+fn compile_regex(pattern: String) -> Option<Regex> {
+  Regex::new(pattern.as_str()).ok()
+}
+
+// Rewrite a replacement of JavaScript's `String.prototype.replace()`, e.g.
+// "$1" or "$&", into the syntax of `Regex::replacen()`
+fn to_regex_replacement(replacement: &str) -> String {
+  let mut converted = String::new();
+  let mut chars = replacement.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '$' {
+      converted.push(c);
+      continue;
+    }
+    match chars.peek() {
+      Some('&') => {
+        chars.next();
+        converted.push_str("${0}");
       }
+      Some(digit) if digit.is_ascii_digit() => {
+        let mut group = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+          group.push(digit);
+        }
+        converted.push_str(format!("${{{}}}", group).as_str());
+      }
+      Some('<') => {
+        chars.next();
+        let name: String = chars.by_ref().take_while(|c| *c != '>').collect();
+        converted.push_str(format!("${{{}}}", name).as_str());
+      }
+      Some('$') => {
+        chars.next();
+        converted.push_str("$$");
+      }
+      _ => converted.push_str("$$"),
     }
   }
-  resolve_single_project(
-    source_input.clone(),
-    file.clone(),
-    String::from("tsconfig.json"),
-  )
+  converted
+}
+
+// Check if `source` matches a pattern of `ignore`
+fn is_ignored(source: &str, options: &Options) -> bool {
+  options
+    .ignore
+    .iter()
+    .flatten()
+    .filter_map(|pattern| compile_regex(pattern.clone()))
+    .any(|regex| regex.is_match(source))
+}
+
+// Get the specifiers the first matching alias of `aliases`, e.g. `alias`,
+// replaces `source` with, `None` if no alias matches
+fn get_alias_targets(source: &str, aliases: &Option<Vec<Alias>>) -> Option<Vec<String>> {
+  aliases.iter().flatten().find_map(|alias| {
+    if alias.regex == Some(true) {
+      let regex = compile_regex(alias.name.clone())?;
+      if !regex.is_match(source) {
+        return None;
+      }
+      let targets = alias
+        .targets
+        .iter()
+        .map(|target| {
+          let replacement = to_regex_replacement(target);
+          regex.replacen(source, 1, replacement.as_str()).into_owned()
+        })
+        .collect();
+      return Some(targets);
+    }
+    let (name, exact) = match alias.name.strip_suffix('$') {
+      Some(name) => (name, true),
+      None => (alias.name.as_str(), false),
+    };
+    let rest = source.strip_prefix(name)?;
+    if !(rest.is_empty() || (!exact && rest.starts_with('/'))) {
+      return None;
+    }
+    let targets = alias
+      .targets
+      .iter()
+      .map(|target| format!("{}{}", target, rest))
+      .collect();
+    Some(targets)
+  })
+}
+
+// Rewrite a Deno specifier: by the `importMap`, then from `npm:` to the bare
+// specifier of its package. `None` if there's nothing to rewrite
+fn get_deno_specifier(
+  source: &str,
+  file: &str,
+  options: &Options,
+  trace: &trace::Trace,
+) -> Option<String> {
+  let mapped = options.import_map.as_ref().and_then(|import_map| {
+    import_map::map_specifier(import_map, &get_cwd(options), source, Path::new(file))
+  });
+  if let Some(mapped) = &mapped {
+    trace.step(|| format!("import map maps \"{}\" to \"{}\"", source, mapped));
+  }
+  let specifier = mapped.as_deref().unwrap_or(source);
+  match import_map::strip_npm_prefix(specifier) {
+    Some(stripped) => {
+      trace.step(|| format!("npm specifier \"{}\" to \"{}\"", specifier, stripped));
+      Some(stripped)
+    }
+    None => mapped,
+  }
+}
+
+// Resolve on single tsConfig project, after rewriting Deno specifiers and
+// replacing `alias`es
+pub fn resolve_single_project(
+  source_input: &str,
+  file: &str,
+  ts_config_file: &str,
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> ResolveResult {
+  let trace = resolver.get_trace();
+  // Remove query string
+  let source = remove_query_string(source_input);
+  let deno_specifier = get_deno_specifier(source, file, options, trace);
+  let source = deno_specifier.as_deref().unwrap_or(source);
+  let targets = match get_alias_targets(source, &options.alias) {
+    Some(targets) => targets,
+    None => return resolve_unaliased(source, file, ts_config_file, resolver, options),
+  };
+
+  // Ignored modules resolve to nothing, like builtins
+  if targets.is_empty() {
+    trace.step(|| format!("alias ignores \"{}\"", source));
+    return found(PathBuf::new(), options);
+  }
+  let mut failed = None;
+  for target in targets.iter() {
+    trace.step(|| format!("alias \"{}\" to \"{}\"", source, target));
+    let resolved = resolve_unaliased(target, file, ts_config_file, resolver, options);
+    if resolved.found {
+      return resolved;
+    }
+    failed.get_or_insert(resolved);
+  }
+  failed.unwrap_or_else(|| not_found(FailureReason::NotFound))
+}
+
+fn resolve_unaliased(
+  source: &str,
+  file: &str,
+  ts_config_file: &str,
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> ResolveResult {
+  let timing = resolver.get_timing();
+  let ts_config = timing.measure(timing::Phase::Tsconfig, || {
+    get_ts_config(String::from(ts_config_file), resolver.get_cwd())
+  });
+  let ts_config_path = get_ts_config_path(String::from(ts_config_file), resolver.get_cwd());
+  let project = get_project(&ts_config, &ts_config_path);
+  let trace = resolver.get_trace();
+  trace.step(|| format!("project {}", ts_config_path.display()));
+  trace.step(|| format!("base dir {}", project.base_dir().display()));
+
+  let file_path = Path::new(file);
+  let project_resolver = get_project_resolver(source, project, resolver, options);
+  let error = match project_resolver.resolve(source, file_path) {
+    ResolveOutcome::Found(resolution) => {
+      return ResolveResult {
+        matched_pattern: resolution
+          .matched_pattern
+          .filter(|_| options.metadata == Some(true)),
+        ..found(resolution.path, options)
+      };
+    }
+    ResolveOutcome::Builtin => return found(PathBuf::new(), options),
+    ResolveOutcome::NotFound(error) => error,
+  };
+
+  // Dependencies resolve like in Node.js, which tells why they failed
+  let reason = get_failure_reason(&error);
+  if is_absolute(file) && is_in_node_modules(file_path) {
+    return not_found(reason);
+  }
+  if reason == FailureReason::NotExported || reason == FailureReason::BlockedByExportsNull {
+    logger::log(logger::LogEventKind::ExportsMismatch, file, || {
+      format!("\"{}\": {}", source, error)
+    });
+  }
+  let reason = if reason != FailureReason::NotFound {
+    reason
+  } else if source.starts_with('.') && is_absolute(file) {
+    let file_dir = get_parent_dir(file_path);
+    diagnose_missing_file(&file_dir.join(source), &get_extensions(options))
+  } else if !source.starts_with('.') && !is_absolute(source) && ts_config.is_err() {
+    FailureReason::NoTsconfig
+  } else {
+    FailureReason::NotFound
+  };
+  not_found(reason)
+}
+
+// Get the project of a tsconfig, or one without `paths` and `baseUrl` in its
+// dir if it can't be read
+fn get_project(
+  ts_config: &Result<Arc<ParsedTsConfig>, String>,
+  ts_config_path: &Path,
+) -> Arc<Project> {
+  match ts_config {
+    Ok(ts_config) => ts_config.project.clone(),
+    Err(_) => Arc::new(Project::parse(ts_config_path, &Value::Null)),
+  }
+}
+
+// Resolve `source` in `project` with the lookups of `options`. Style sheets
+// and assets are resolved by plain file existence
+fn get_project_resolver(
+  source: &str,
+  project: Arc<Project>,
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> ProjectResolver {
+  let is_asset = is_asset(source, options);
+  let resolver = match is_asset {
+    true => resolver
+      .with_extensions(Vec::<String>::new())
+      .resolve_directories(false),
+    false => resolver.clone(),
+  };
+  let lookup = LookupOptions {
+    prefer_ts_paths: options.prefer_ts_paths.unwrap_or(true),
+    prefer_relative: options.prefer_relative == Some(true),
+    always_try_types: options.always_try_types.unwrap_or(true),
+    // Assets are probed as they are either way
+    paths_extensions: (!is_asset).then(|| get_paths_extensions(options)),
+    workspace_aliases: options.workspace_aliases == Some(true),
+    src_alias: options.src_alias.clone(),
+  };
+  ProjectResolver::with_projects(vec![project], resolver, lookup)
+}
+
+// Check if a specifier references a builtin of `runtime`, by default of
+// Node.js, such as "fs" or "node:fs", counting those the Node.js types
+// installed in `cwd`, by default the current dir, declare
+#[napi]
+pub fn is_core_module(specifier: String, runtime: Option<String>, cwd: Option<String>) -> bool {
+  let runtime = runtime.as_deref().and_then(Runtime::from_name);
+  let cwd = get_cwd_or_current(cwd.as_deref());
+  runtime
+    .unwrap_or_default()
+    .is_core_module_in(specifier.as_str(), &cwd)
+}
+
+#[napi(object)]
+pub struct EffectivePaths {
+  pub project: String,
+  pub base_url: String,
+  pub paths: HashMap<String, Vec<String>>,
+  pub references: Vec<String>,
+}
+
+// Get the base dir and `paths` the resolver sees for a tsconfig, after
+// `extends` and `${configDir}` expansion, plus the tsconfig files of its
+// `references`. A relative `project` is looked up from the `cwd` of
+// `options`, by default the current dir
+#[napi]
+pub fn get_effective_paths(project: String, options: Option<Options>) -> EffectivePaths {
+  let cwd = options.as_ref().map_or_else(get_current_dir, get_cwd);
+  EffectivePaths {
+    project: path_to_string(&get_ts_config_path(project.clone(), &cwd)),
+    base_url: path_to_string(&get_base_dir(project.clone(), &cwd)),
+    paths: get_paths(project.clone(), &cwd).unwrap_or_default(),
+    references: get_references(project, &cwd),
+  }
+}
+
+#[napi(object)]
+pub struct ProjectInfo {
+  pub project: Option<String>,
+  pub base_dir: String,
+  pub module_resolution: Option<String>,
+}
+
+// Find the tsconfig governing `file`: the configured project whose directory
+// contains it most closely, else the nearest tsconfig.json above it
+fn get_project_for_file(file: &Path, projects: &[String], cwd: &Path) -> Option<PathBuf> {
+  let project = projects
+    .iter()
+    .map(|project| get_ts_config_path(project.clone(), cwd))
+    .filter(|project| match project.parent() {
+      Some(project_dir) => file.starts_with(project_dir),
+      None => false,
+    })
+    .max_by_key(|project| project.components().count());
+  if project.is_some() {
+    return project;
+  }
+
+  file
+    .ancestors()
+    .skip(1)
+    .map(|dir| dir.join("tsconfig.json"))
+    .find(|project| project.is_file())
+}
+
+// Get the tsconfig that governs `file`, the base dir bare imports resolve
+// from, and its `moduleResolution` (lowercased, unset when left to
+// TypeScript's default). Without a tsconfig, the base dir is the root the
+// file is in, by default `cwd`
+#[napi]
+pub fn get_project_info(file: String, options: Option<Options>) -> napi::Result<ProjectInfo> {
+  let projects = match &options {
+    Some(options) => get_projects(options)?,
+    None => vec![],
+  };
+  let cwd = options.as_ref().map_or_else(get_current_dir, get_cwd);
+  let project = match get_project_for_file(Path::new(file.as_str()), &projects, &cwd) {
+    Some(project) => project,
+    None => {
+      let root = options
+        .as_ref()
+        .and_then(|options| get_root_for_file(Path::new(file.as_str()), options));
+      return Ok(ProjectInfo {
+        project: None,
+        base_dir: path_to_string(&root.unwrap_or(cwd)),
+        module_resolution: None,
+      });
+    }
+  };
+
+  let project = path_to_string(&project);
+  let module_resolution =
+    get_raw_compiler_option(Path::new(project.as_str()), "moduleResolution", &mut vec![])
+      .and_then(|value| value.as_str().map(|value| value.to_lowercase()));
+  Ok(ProjectInfo {
+    base_dir: path_to_string(&get_base_dir(project.clone(), &cwd)),
+    project: Some(project),
+    module_resolution,
+  })
+}
+
+// Register a callback receiving diagnostic events, such as cache misses,
+// tsconfig reloads, and `exports` mismatches. Events are delivered
+// asynchronously. Call without a callback to remove the logger
+#[napi(ts_args_type = "logger?: ((event: LogEvent) => void) | undefined | null")]
+pub fn set_logger(env: Env, logger: Option<JsFunction>) -> napi::Result<()> {
+  let logger = match logger {
+    Some(logger) => logger,
+    None => {
+      logger::set(None);
+      return Ok(());
+    }
+  };
+  let mut logger: ThreadsafeFunction<logger::LogEvent, ErrorStrategy::Fatal> =
+    logger.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+  // Don't keep the process alive for the logger's sake
+  logger.unref(&env)?;
+  logger::set(Some(logger));
+  Ok(())
+}
+
+// Register a callback resolving specifiers starting with `prefix`, e.g.
+// "virtual:" or "~icons/", to a path, or marking them external with
+// `{ external: true }`. Returning nothing leaves them to resolution. Call
+// without a callback to remove it. Results reused so far are dropped
+#[napi(
+  ts_args_type = "prefix: string, handler?: ((specifier: string, file: string) => string | { external: true } | null | undefined) | undefined | null"
+)]
+pub fn register_protocol_handler(
+  env: Env,
+  prefix: String,
+  handler: Option<JsFunction>,
+) -> napi::Result<()> {
+  protocol_handlers::register(env, prefix, handler)?;
+  resolution_cache::clear();
+  Ok(())
+}
+
+#[napi(object)]
+pub struct CacheCounters {
+  pub hits: u32,
+  pub misses: u32,
+  pub size: u32,
+}
+
+#[napi(object)]
+pub struct CacheStats {
+  pub tsconfig_paths: CacheCounters,
+  pub tsconfigs: CacheCounters,
+  pub resolutions: CacheCounters,
+  pub declaration_maps: CacheCounters,
+  pub file_stats: CacheCounters,
+  pub package_jsons: CacheCounters,
+}
+
+fn to_cache_counters(hits: u64, misses: u64, size: usize) -> CacheCounters {
+  CacheCounters {
+    hits: u32::try_from(hits).unwrap_or(u32::MAX),
+    misses: u32::try_from(misses).unwrap_or(u32::MAX),
+    size: u32::try_from(size).unwrap_or(u32::MAX),
+  }
+}
+
+fn get_cache_counters<K, V>(cache: &impl Cached<K, V>) -> CacheCounters {
+  to_cache_counters(
+    cache.cache_hits().unwrap_or(0),
+    cache.cache_misses().unwrap_or(0),
+    cache.cache_size(),
+  )
+}
+
+// Get hits, misses, and sizes of the native caches since the process started
+#[napi]
+pub fn get_cache_stats() -> CacheStats {
+  let (hits, misses, size) = declaration_map::cache_stats();
+  let (resolution_hits, resolution_misses, resolution_size) = resolution_cache::cache_stats();
+  let (stat_hits, stat_misses, stat_size) = stat_cache::cache_stats();
+  let (package_json_hits, package_json_misses, package_json_size) =
+    node_resolve::package_json_cache_stats();
+  CacheStats {
+    tsconfig_paths: get_cache_counters(&*GET_TS_CONFIG_PATH.lock().unwrap()),
+    tsconfigs: get_cache_counters(&*READ_TS_CONFIG.lock().unwrap()),
+    resolutions: to_cache_counters(resolution_hits, resolution_misses, resolution_size),
+    declaration_maps: to_cache_counters(hits, misses, size),
+    file_stats: to_cache_counters(stat_hits, stat_misses, stat_size),
+    package_jsons: to_cache_counters(package_json_hits, package_json_misses, package_json_size),
+  }
+}
+
+// Dump the settings `resolve` works with for `options`, for bug reports
+#[napi]
+pub fn dump_config(options: Options) -> napi::Result<ResolverConfig> {
+  Ok(get_resolver_config(&options, &get_projects(&options)?))
+}
+
+// Start a new generation of resolution results, e.g. before each lint run,
+// so files added or removed since are noticed. Returns the new generation
+#[napi]
+pub fn bump_generation() -> u32 {
+  stat_cache::clear();
+  dir_listing::clear();
+  package_index::clear();
+  realpath_cache::clear();
+  workspace::clear();
+  resolution_cache::bump_generation()
+}
+
+// The cache dir given to `setCacheDir()` and the dir it was given for
+static CACHE_DIR: Mutex<Option<(PathBuf, PathBuf)>> = Mutex::new(None);
+
+// Keep resolutions and parsed package.json files in `dir` across processes.
+// Restores what an earlier process saved there for the same lockfiles and
+// tsconfig files in `cwd`, by default the current dir, and returns the
+// number of entries restored. `saveCache()` writes them back
+#[napi]
+pub fn set_cache_dir(dir: String, cwd: Option<String>) -> napi::Result<u32> {
+  let cwd = get_cwd_or_current(cwd.as_deref());
+  let dir = cwd.join(dir);
+  let restored = persistent_cache::load(&dir, &cwd).map_err(napi::Error::from_reason)?;
+  *CACHE_DIR.lock().unwrap() = Some((dir, cwd));
+  Ok(u32::try_from(restored).unwrap_or(u32::MAX))
+}
+
+// Write caches to the dir given to `setCacheDir()`, if any, for `cwd`, by
+// default the dir given with it
+#[napi]
+pub fn save_cache(cwd: Option<String>) -> napi::Result<()> {
+  let cache_dir = CACHE_DIR.lock().unwrap().clone();
+  match cache_dir {
+    Some((dir, cache_cwd)) => {
+      let cwd = cwd.map_or(cache_cwd, |cwd| get_cwd_or_current(Some(cwd.as_str())));
+      persistent_cache::save(&dir, &cwd).map_err(napi::Error::from_reason)
+    }
+    None => Ok(()),
+  }
+}
+
+// Answer resolutions listed in the manifest at `path` before any filesystem
+// work, e.g. one written by `writeManifest()` earlier in a CI pipeline.
+// Results are looked up by specifier and importing dir, so write it with the
+// same options it is used with. A relative `path` is relative to `cwd`, by
+// default the current dir. Returns the number of resolutions loaded
+#[napi]
+pub fn load_manifest(path: String, cwd: Option<String>) -> napi::Result<u32> {
+  let path = get_cwd_or_current(cwd.as_deref()).join(path);
+  let loaded = manifest::load(&path).map_err(napi::Error::from_reason)?;
+  Ok(u32::try_from(loaded).unwrap_or(u32::MAX))
+}
+
+// Stop answering resolutions from the manifest loaded by `loadManifest()`
+#[napi]
+pub fn unload_manifest() {
+  manifest::unload();
+}
+
+// Write what was found in the current generation to a manifest at `path`,
+// with paths relative to its dir. A relative `path` is relative to `cwd`, by
+// default the current dir. Returns the number of resolutions written
+#[napi]
+pub fn write_manifest(path: String, cwd: Option<String>) -> napi::Result<u32> {
+  let path = get_cwd_or_current(cwd.as_deref()).join(path);
+  let written = manifest::write(&path).map_err(napi::Error::from_reason)?;
+  Ok(u32::try_from(written).unwrap_or(u32::MAX))
+}
+
+// Set how many milliseconds file existence checks are reused for, 0 to
+// check the filesystem every time. Defaults to one second
+#[napi]
+pub fn set_stat_cache_ttl(ttl_ms: u32) {
+  stat_cache::set_ttl(u64::from(ttl_ms));
+}
+
+// Drop every cached tsconfig, package.json, declaration map, import map, file
+// check, dir listing, node_modules listing, real path, workspace, and
+// resolution
+#[napi]
+pub fn clear_cache() {
+  resolution_cache::clear();
+  stat_cache::clear();
+  dir_listing::clear();
+  package_index::clear();
+  realpath_cache::clear();
+  workspace::clear();
+  node_resolve::clear_package_json_cache();
+  GET_TS_CONFIG_PATH.lock().unwrap().cache_clear();
+  GET_DIRECT_EXTENDS.lock().unwrap().cache_clear();
+  READ_TS_CONFIG.lock().unwrap().cache_clear();
+  READ_INLINE_TS_CONFIG.lock().unwrap().cache_clear();
+  declaration_map::clear_cache();
+  import_map::clear_cache();
+}
+
+// Drop cached entries derived from the file at `path`. Tsconfig files are
+// revalidated against their modification time and size anyway, this covers
+// edits that keep both, e.g. on coarse-grained filesystems
+#[napi]
+pub fn invalidate(path: String) {
+  // Any file may be what some result was resolved to
+  resolution_cache::clear();
+  stat_cache::invalidate(Path::new(path.as_str()));
+  dir_listing::invalidate(Path::new(path.as_str()));
+  package_index::invalidate(Path::new(path.as_str()));
+  realpath_cache::invalidate(Path::new(path.as_str()));
+  workspace::invalidate(Path::new(path.as_str()));
+  if path.ends_with(".map") {
+    declaration_map::invalidate(Path::new(path.as_str()));
+    return;
+  }
+  if path.ends_with("package.json") {
+    node_resolve::clear_package_json_cache();
+    return;
+  }
+  import_map::invalidate(Path::new(path.as_str()));
+
+  // The file may be extended by any config, and configs are few
+  GET_DIRECT_EXTENDS.lock().unwrap().cache_clear();
+  READ_TS_CONFIG.lock().unwrap().cache_clear();
+}
+
+// Turn given compiler options into tsconfig content
+fn get_compiler_options_content(compiler_options: &CompilerOptions) -> String {
+  let mut content = serde_json::Map::new();
+  if let Some(base_url) = &compiler_options.base_url {
+    content.insert(String::from("baseUrl"), Value::from(base_url.as_str()));
+  }
+  if let Some(paths) = &compiler_options.paths {
+    // Sorted, so equal options map to the same inline project
+    let paths: BTreeMap<_, _> = paths.iter().collect();
+    content.insert(String::from("paths"), serde_json::json!(paths));
+  }
+  serde_json::json!({ "compilerOptions": content }).to_string()
+}
+
+// Expand `project` globs to tsconfig files, after the inline
+// `tsconfigContent`, if any. Given `compilerOptions` replace all of them.
+// Fails on invalid options and malformed glob patterns
+fn get_projects(options: &Options) -> napi::Result<Vec<String>> {
+  let cwd = get_cwd(options);
+  validate::validate_options(options, &cwd).map_err(napi::Error::from_reason)?;
+
+  if let Some(compiler_options) = &options.compiler_options {
+    return Ok(vec![register_inline_ts_config(
+      get_compiler_options_content(compiler_options).as_str(),
+      &cwd,
+    )]);
+  }
+
+  let mut projects = vec![];
+  if let Some(content) = &options.tsconfig_content {
+    projects.push(register_inline_ts_config(content, &cwd));
+  }
+  for ts_config_file in options.project.clone().unwrap_or_default().iter() {
+    let physical_ts_config_path = if is_absolute(ts_config_file) {
+      ts_config_file.clone()
+    } else {
+      path_to_string(&cwd.join(ts_config_file))
+    };
+    let entries = glob(physical_ts_config_path.as_str()).map_err(|error| {
+      napi::Error::from_reason(format!(
+        "Invalid project pattern \"{}\": {}",
+        ts_config_file, error
+      ))
+    })?;
+    for entry in entries.filter_map(|p| p.ok()) {
+      projects.push(path_to_string(&entry));
+    }
+  }
+  Ok(projects)
+}
+
+// Identify the projects and options a result depends on
+fn get_fingerprint(options: &Options, projects: &[String]) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  projects.hash(&mut hasher);
+  format!("{:?}", options).hash(&mut hasher);
+  hasher.finish()
+}
+
+// Resolve like `resolve_projects`, answering from the loaded manifest or
+// reusing what was found, or that nothing was found, for the same source,
+// importing dir, and fingerprint in the current generation. Traced
+// resolutions always run. With `timing`, the result reports where the time
+// went, all of it lookup time when reused. With `probeCounts`, it reports how
+// many files were checked and package.json files read. A relative `file` is
+// taken as relative to `cwd`
+fn resolve_projects_cached(
+  source_input: String,
+  file: String,
+  projects: &[String],
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+  fingerprint: u64,
+) -> ResolveResult {
+  let file = get_absolute_file(file, options);
+  let started = Instant::now();
+  let timing = timing::Timing::new(options.timing == Some(true));
+  let counters = counters::Counters::new(options.probe_counts == Some(true));
+  if !timing.is_enabled() && !counters.is_enabled() {
+    return resolve_projects_reusing(source_input, file, projects, resolver, options, fingerprint);
+  }
+
+  let resolver = resolver
+    .with_timing(timing.clone())
+    .with_counters(counters.clone());
+  let resolved = resolve_projects_reusing(
+    source_input,
+    file,
+    projects,
+    &resolver,
+    options,
+    fingerprint,
+  );
+  let count = |counter| u32::try_from(counters.get(counter)).unwrap_or(u32::MAX);
+  ResolveResult {
+    timing: timing.is_enabled().then(|| ResolveTiming {
+      total_ms: started.elapsed().as_secs_f64() * 1000.0,
+      tsconfig_ms: timing.get_ms(timing::Phase::Tsconfig),
+      paths_ms: timing.get_ms(timing::Phase::Paths),
+      node_modules_ms: timing.get_ms(timing::Phase::NodeModules),
+      stat_ms: timing.get_ms(timing::Phase::Stat),
+    }),
+    probe_counts: counters.is_enabled().then(|| ProbeCounts {
+      file_checks: count(counters::Counter::FileChecks),
+      package_json_reads: count(counters::Counter::PackageJsonReads),
+    }),
+    ..resolved
+  }
+}
+
+fn resolve_projects_reusing(
+  source_input: String,
+  file: String,
+  projects: &[String],
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+  fingerprint: u64,
+) -> ResolveResult {
+  if options.trace == Some(true) {
+    return resolve_projects(source_input, file, projects, resolver, options);
+  }
+
+  let dir = get_parent_dir(Path::new(file.as_str()));
+  if let Some(resolved) = manifest::get(source_input.as_str(), &dir) {
+    return resolved;
+  }
+  // Files of one dir may match different condition sets
+  let fingerprint = match get_condition_set_index(Path::new(file.as_str()), options) {
+    Some(index) => {
+      let mut hasher = DefaultHasher::new();
+      (fingerprint, index).hash(&mut hasher);
+      hasher.finish()
+    }
+    None => fingerprint,
+  };
+  let key = resolution_cache::ResolutionKey::new(source_input.as_str(), &dir, fingerprint);
+  if let Some(resolved) = resolution_cache::get(&key) {
+    return resolved;
+  }
+  let resolved = resolve_projects(source_input, file, projects, resolver, options);
+  resolution_cache::insert(key, resolved.clone());
+  resolved
+}
+
+// Map a file emitted into the `outDir` or `declarationDir` of a project
+// referenced by `ts_config_file` back to its source under the project's
+// `rootDir`, e.g. "core/dist/foo.d.ts" to "core/src/foo.ts", so importers
+// lint without building the reference first
+fn get_reference_source(path: &str, ts_config_file: &str, cwd: &Path) -> Option<PathBuf> {
+  let (stem, extensions): (&str, &[&str]) = [
+    (".d.ts", &[".ts", ".tsx"][..]),
+    (".d.mts", &[".mts"]),
+    (".d.cts", &[".cts"]),
+    (".js", &[".ts", ".tsx"]),
+    (".mjs", &[".mts"]),
+    (".cjs", &[".cts"]),
+  ]
+  .iter()
+  .find_map(|(ext, extensions)| Some((path.strip_suffix(ext)?, *extensions)))?;
+
+  for reference in get_references(String::from(ts_config_file), cwd) {
+    let ts_config = match get_ts_config(reference.clone(), cwd) {
+      Ok(ts_config) => ts_config,
+      Err(_) => continue,
+    };
+    let output_dirs = ts_config.get_output_dirs(Path::new(reference.as_str()));
+    for emitted in output_dirs.emitted.iter() {
+      let relative = match Path::new(stem).strip_prefix(emitted) {
+        Ok(relative) => relative,
+        Err(_) => continue,
+      };
+      let source_stem = path_to_string(&output_dirs.root_dir.join(relative));
+      let source = extensions
+        .iter()
+        .map(|ext| PathBuf::from(format!("{}{}", source_stem, ext)))
+        .find(|source| stat_cache::is_file(source));
+      if source.is_some() {
+        return source;
+      }
+    }
+  }
+  None
+}
+
+// Get the `roots` as absolute dirs
+fn get_roots(options: &Options) -> Vec<PathBuf> {
+  let cwd = get_cwd(options);
+  options
+    .roots
+    .iter()
+    .flatten()
+    .map(|root| node_resolve::normalize_path(&cwd.join(root)))
+    .collect()
+}
+
+// Get the root `file` is in, the innermost one if roots are nested. Results
+// are cached per importing dir, so each root keeps its own
+fn get_root_for_file(file: &Path, options: &Options) -> Option<PathBuf> {
+  get_roots(options)
+    .into_iter()
+    .filter(|root| file.starts_with(root))
+    .max_by_key(|root| root.components().count())
+}
+
+// Check if `restrictToProject` blocks `path`, found for `file`, for being
+// outside the root of `file`, or `cwd`. Core modules have no path
+fn is_outside_project(path: &str, file: &str, options: &Options) -> bool {
+  if options.restrict_to_project != Some(true) || path.is_empty() {
+    return false;
+  }
+  let root = get_root_for_file(Path::new(file), options).unwrap_or_else(|| get_cwd(options));
+  !Path::new(path).starts_with(root)
+}
+
+// Find the nearest tsconfig.json above `file`, e.g. its package's, unless
+// it is one of the configured `projects` already
+fn get_nearest_project(file: &Path, projects: &[String], cwd: &Path) -> Option<String> {
+  if !file.is_absolute() {
+    return None;
+  }
+  let nearest = file
+    .ancestors()
+    .skip(1)
+    .map(|dir| dir.join("tsconfig.json"))
+    .find(|project| stat_cache::is_file(project))?;
+  let is_configured = projects
+    .iter()
+    .any(|project| get_ts_config_path(project.clone(), cwd) == nearest);
+  if is_configured {
+    None
+  } else {
+    Some(path_to_string(&nearest))
+  }
+}
+
+// Suggest specifiers close to `source`, which `file` failed to import, from
+// files, node_modules, `alias` names, and `paths` patterns of the projects
+fn get_suggestions(
+  source: &str,
+  file: &Path,
+  ts_config_files: &[&String],
+  options: &Options,
+) -> Vec<String> {
+  let alias_names = options
+    .alias
+    .iter()
+    .flatten()
+    .filter(|alias| alias.regex != Some(true))
+    .map(|alias| String::from(alias.name.trim_end_matches('$')));
+  let cwd = get_cwd(options);
+  let patterns = ts_config_files
+    .iter()
+    .filter_map(|ts_config_file| get_paths(String::from(ts_config_file.as_str()), &cwd))
+    .flat_map(|paths| paths.into_keys())
+    .filter_map(|pattern| match pattern.strip_suffix("/*") {
+      Some(prefix) => Some(String::from(prefix)),
+      None => (!pattern.contains('*')).then_some(pattern),
+    });
+  let prefixes: Vec<String> = alias_names.chain(patterns).collect();
+
+  let node_modules: Vec<PathBuf> = get_modules(options)
+    .iter()
+    .flat_map(|modules| match Path::new(modules).is_absolute() {
+      true => vec![PathBuf::from(modules)],
+      false => file
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join(modules))
+        .collect(),
+    })
+    .filter(|dir| dir.is_dir())
+    .collect();
+  suggestions::suggest(source, file, &prefixes, &node_modules)
+}
+
+// Make the candidate of `strategy` for `specifier` from what it resolved to
+fn to_candidate(
+  project: &str,
+  strategy: &str,
+  specifier: &str,
+  resolved: Result<PathBuf, FailureReason>,
+  options: &Options,
+) -> Candidate {
+  let (path, reason) = match resolved {
+    Ok(resolved) => (found(resolved, options).path, None),
+    Err(reason) => (String::from(""), Some(reason)),
+  };
+  Candidate {
+    project: String::from(project),
+    strategy: String::from(strategy),
+    specifier: String::from(specifier),
+    found: reason.is_none(),
+    path,
+    reason,
+  }
+}
+
+// Try `source_input` with every strategy of the project `ts_config_file`,
+// for each target of its alias, without stopping at the first resolving.
+// Strategies not applying to the specifier, e.g. workspace packages to
+// relative ones, are left out
+fn collect_candidates(
+  source_input: &str,
+  file: &str,
+  ts_config_file: &str,
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> Vec<Candidate> {
+  // The candidates aren't part of the resolution's steps
+  let resolver = resolver.with_trace(trace::Trace::new(false, false));
+  let source = remove_query_string(source_input);
+  let deno_specifier = get_deno_specifier(source, file, options, resolver.get_trace());
+  let source = deno_specifier.as_deref().unwrap_or(source);
+  let specifiers =
+    get_alias_targets(source, &options.alias).unwrap_or_else(|| vec![String::from(source)]);
+  let ts_config = get_ts_config(String::from(ts_config_file), resolver.get_cwd());
+  let ts_config_path = get_ts_config_path(String::from(ts_config_file), resolver.get_cwd());
+  let project = get_project(&ts_config, &ts_config_path);
+  let project_name = path_to_string(&ts_config_path);
+
+  let mut candidates = vec![];
+  for specifier in specifiers.iter() {
+    let specifier = specifier.as_str();
+    let project_resolver = get_project_resolver(specifier, project.clone(), &resolver, options);
+    for attempt in project_resolver.try_all(specifier, Path::new(file)) {
+      let resolved = attempt.resolved.map_err(|error| get_failure_reason(&error));
+      let strategy = attempt.strategy.name();
+      candidates.push(to_candidate(
+        &project_name,
+        strategy,
+        specifier,
+        resolved,
+        options,
+      ));
+    }
+  }
+  candidates
+}
+
+// Find the files other than `chosen` that candidates resolved to
+fn find_ambiguous_paths(candidates: &[Candidate], chosen: &str) -> Vec<String> {
+  let mut ambiguous_paths: Vec<String> = vec![];
+  for candidate in candidates.iter() {
+    let path = &candidate.path;
+    // Core modules have no file
+    if !path.is_empty() && path != chosen && !ambiguous_paths.contains(path) {
+      ambiguous_paths.push(path.clone());
+    }
+  }
+  ambiguous_paths
+}
+
+// Warn about a project that can't be read. A missing default tsconfig.json
+// is fine, plain Node.js resolution applies
+fn warn_unreadable_project(
+  ts_config_file: &str,
+  is_default: bool,
+  resolver: &node_resolve::Resolver,
+) {
+  let error = match get_ts_config(String::from(ts_config_file), resolver.get_cwd()) {
+    Ok(_) => return,
+    Err(error) => error,
+  };
+  let ts_config_path = get_ts_config_path(String::from(ts_config_file), resolver.get_cwd());
+  if is_default && !ts_config_path.is_file() {
+    return;
+  }
+  resolver.warn(|| match get_inline_ts_config(ts_config_file) {
+    Some(_) => format!("Ignoring `tsconfigContent`: {}", error),
+    None => format!("Ignoring project {}: {}", ts_config_path.display(), error),
+  });
+}
+
+// Take the warnings of a resolution, sending each to the logger as well
+fn take_warnings(warnings: &Mutex<Vec<String>>, file: &str) -> Option<Vec<String>> {
+  let warnings = std::mem::take(&mut *warnings.lock().unwrap());
+  for warning in warnings.iter() {
+    logger::log(logger::LogEventKind::ConfigWarning, file, || {
+      warning.clone()
+    });
+  }
+  (!warnings.is_empty()).then_some(warnings)
+}
+
+// Get the projects tried after the configured ones: none with given compiler
+// options, else the tsconfig.json of the root of `file` or `cwd`
+fn get_default_projects(file: &Path, options: &Options) -> Vec<String> {
+  match (&options.compiler_options, get_root_for_file(file, options)) {
+    (Some(_), _) => vec![],
+    // Each root discovers its own tsconfig.json
+    (None, Some(root)) => vec![path_to_string(&root.join("tsconfig.json"))],
+    (None, None) => vec![path_to_string(&get_cwd(options).join("tsconfig.json"))],
+  }
+}
+
+// Resolve in `projects`, recording the resolution in the `TS_RESOLVER_TRACE`
+// file if set. Results reused from the cache aren't recorded again
+fn resolve_projects(
+  source_input: String,
+  file: String,
+  projects: &[String],
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> ResolveResult {
+  if !resolution_log::is_enabled() {
+    return resolve_in_projects(source_input, file, projects, resolver, options);
+  }
+
+  let started = Instant::now();
+  let timing = match resolver.get_timing().is_enabled() {
+    true => resolver.get_timing().clone(),
+    false => timing::Timing::new(true),
+  };
+  let resolver = resolver.with_timing(timing.clone());
+  let mut resolved = resolve_in_projects(
+    source_input.clone(),
+    file.clone(),
+    projects,
+    &resolver,
+    options,
+  );
+  let timestamp = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .map_or(0, |elapsed| elapsed.as_millis());
+  resolution_log::append(&serde_json::json!({
+    "timestamp": timestamp as u64,
+    "pid": std::process::id(),
+    "source": source_input,
+    "file": file,
+    "projects": projects,
+    "found": resolved.found,
+    "path": resolved.path,
+    "reason": resolved.reason.as_ref().map(|reason| format!("{:?}", reason)),
+    "steps": resolved.trace,
+    "timing": {
+      "totalMs": started.elapsed().as_secs_f64() * 1000.0,
+      "tsconfigMs": timing.get_ms(timing::Phase::Tsconfig),
+      "pathsMs": timing.get_ms(timing::Phase::Paths),
+      "nodeModulesMs": timing.get_ms(timing::Phase::NodeModules),
+      "statMs": timing.get_ms(timing::Phase::Stat),
+    },
+  }));
+  // Steps were recorded for the file only
+  if options.trace != Some(true) {
+    resolved.trace = None;
+  }
+  resolved
+}
+
+// Resolve the `fallback` targets of `source_input`, which failed to resolve
+// otherwise, in each project in turn. `None` if no fallback matches or none
+// of its targets resolves
+fn resolve_with_fallback(
+  source_input: &str,
+  file: &str,
+  ts_config_files: &[&String],
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> Option<ResolveResult> {
+  let source = remove_query_string(source_input);
+  let targets = get_alias_targets(source, &options.fallback)?;
+  let trace = resolver.get_trace();
+  // Ignored modules resolve to nothing, like builtins
+  if targets.is_empty() {
+    trace.step(|| format!("fallback ignores \"{}\"", source));
+    return Some(ResolveResult {
+      is_fallback: Some(true),
+      ..found(PathBuf::new(), options)
+    });
+  }
+  for target in targets.iter() {
+    trace.step(|| format!("fallback \"{}\" to \"{}\"", source, target));
+    for ts_config_file in ts_config_files.iter() {
+      let resolved = resolve_single_project(target, file, ts_config_file, resolver, options);
+      if resolved.found {
+        trace.step(|| format!("found {}", resolved.path));
+        return Some(ResolveResult {
+          is_fallback: Some(true),
+          ..resolved
+        });
+      }
+    }
+  }
+  None
+}
+
+// Resolve on each project in turn, after the nearest tsconfig.json with
+// `nearestTsconfigFirst`, then on the default tsconfig.json of the file's
+// root unless compiler options are given
+fn resolve_in_projects(
+  source_input: String,
+  file: String,
+  projects: &[String],
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+) -> ResolveResult {
+  let _span = tracing::info_span!("resolution", source = source_input, file).entered();
+  let probes = Arc::new(Mutex::new(vec![]));
+  let warnings = Arc::new(Mutex::new(vec![]));
+  let record_steps = options.trace == Some(true) || resolution_log::is_enabled();
+  let trace = trace::Trace::from_env(record_steps);
+  trace.step(|| format!("resolve \"{}\" from {}", source_input, file));
+  let resolver = resolver
+    .with_probe_log(probes.clone())
+    .with_warnings(warnings.clone())
+    .with_trace(trace.clone());
+  for warning in validate::get_warnings(options) {
+    resolver.warn(|| warning);
+  }
+  let resolver = match get_file_condition_names(Path::new(file.as_str()), options) {
+    Some(condition_names) => {
+      trace.step(|| format!("conditions {}", condition_names.join(", ")));
+      resolver.with_condition_names(condition_names)
+    }
+    None => resolver,
+  };
+
+  if is_not_handled(source_input.as_str(), options) {
+    trace.step(|| String::from("not matched by `resolveOnly`, left to other resolvers"));
+    return ResolveResult {
+      warnings: take_warnings(&warnings, file.as_str()),
+      trace: trace.steps(),
+      ..not_found(FailureReason::NotHandled)
+    };
+  }
+  if is_ignored(source_input.as_str(), options) {
+    trace.step(|| String::from("ignored, external"));
+    return ResolveResult {
+      warnings: take_warnings(&warnings, file.as_str()),
+      trace: trace.steps(),
+      ..external(options)
+    };
+  }
+  let handled = protocol_handlers::handle(source_input.as_str(), file.as_str());
+  if let Some(handled) = handled {
+    let resolved = match handled {
+      protocol_handlers::Handled::Path(path) => {
+        trace.step(|| format!("protocol handler resolved {}", path));
+        found(PathBuf::from(path), options)
+      }
+      protocol_handlers::Handled::External => {
+        trace.step(|| String::from("protocol handler marked external"));
+        external(options)
+      }
+    };
+    return ResolveResult {
+      warnings: take_warnings(&warnings, file.as_str()),
+      trace: trace.steps(),
+      ..resolved
+    };
+  }
+
+  // Report the first failure with the most telling reason
+  let mut failed: Option<ResolveResult> = None;
+  let default_projects = get_default_projects(Path::new(file.as_str()), options);
+  let nearest_project = match options.nearest_tsconfig_first {
+    Some(true) => get_nearest_project(Path::new(file.as_str()), projects, resolver.get_cwd()),
+    _ => None,
+  };
+  let ts_config_files: Vec<&String> = nearest_project
+    .iter()
+    .chain(projects.iter())
+    .chain(default_projects.iter())
+    .collect();
+  let candidates = match options.search_mode.as_deref() {
+    Some("exhaustive") => Some(
+      ts_config_files
+        .iter()
+        .flat_map(|ts_config_file| {
+          collect_candidates(
+            source_input.as_str(),
+            file.as_str(),
+            ts_config_file.as_str(),
+            &resolver,
+            options,
+          )
+        })
+        .collect(),
+    ),
+    _ => None,
+  };
+  for ts_config_file in ts_config_files.iter() {
+    let is_default = default_projects.contains(ts_config_file);
+    warn_unreadable_project(ts_config_file.as_str(), is_default, &resolver);
+    let resolved = resolve_single_project(
+      source_input.as_str(),
+      file.as_str(),
+      ts_config_file.as_str(),
+      &resolver,
+      options,
+    );
+    if resolved.found {
+      let ambiguous_paths = match options.strict {
+        Some(true) => {
+          let project_candidates = collect_candidates(
+            source_input.as_str(),
+            file.as_str(),
+            ts_config_file.as_str(),
+            &resolver,
+            options,
+          );
+          Some(find_ambiguous_paths(
+            &project_candidates,
+            resolved.path.as_str(),
+          ))
+          .filter(|ambiguous_paths| !ambiguous_paths.is_empty())
+        }
+        _ => None,
+      };
+      if let Some(ambiguous_paths) = &ambiguous_paths {
+        trace.step(|| format!("also resolvable to {}", ambiguous_paths.join(", ")));
+      }
+      let source_path = match options.use_source_of_project_references {
+        Some(true) => get_reference_source(
+          resolved.path.as_str(),
+          ts_config_file.as_str(),
+          resolver.get_cwd(),
+        ),
+        _ => None,
+      };
+      let resolved = match source_path {
+        Some(source_path) => {
+          trace.step(|| format!("source of project reference {}", source_path.display()));
+          found(source_path, options)
+        }
+        None => resolved,
+      };
+      if is_outside_project(resolved.path.as_str(), file.as_str(), options) {
+        trace.step(|| format!("blocked {}, outside the project root", resolved.path));
+        keep_more_telling(&mut failed, not_found(FailureReason::OutsideProject));
+        continue;
+      }
+      if let Some(name) = find_undeclared_dependency(resolved.path.as_str(), file.as_str(), options)
+      {
+        trace.step(|| format!("blocked {}, \"{}\" isn't declared", resolved.path, name));
+        keep_more_telling(&mut failed, not_found(FailureReason::UndeclaredDependency));
+        continue;
+      }
+      trace.step(|| format!("found {}", resolved.path));
+      return ResolveResult {
+        warnings: take_warnings(&warnings, file.as_str()),
+        ambiguous_paths,
+        candidates,
+        trace: trace.steps(),
+        ..resolved
+      };
+    }
+    keep_more_telling(&mut failed, resolved);
+  }
+  let mut resolved = failed.unwrap_or_else(|| not_found(FailureReason::NotFound));
+
+  if let Some(fallback) = resolve_with_fallback(
+    source_input.as_str(),
+    file.as_str(),
+    &ts_config_files,
+    &resolver,
+    options,
+  ) {
+    return ResolveResult {
+      warnings: take_warnings(&warnings, file.as_str()),
+      candidates,
+      trace: trace.steps(),
+      ..fallback
+    };
+  }
+
+  // Virtual asset pipelines: assets count as found even if not on disk
+  let source = remove_query_string(source_input.as_str());
+  if options.assets_always_found == Some(true) && is_asset(source, options) {
+    let path = if source.starts_with('.') && is_absolute(&file) {
+      let base_dir = get_parent_dir(Path::new(file.as_str()));
+      path_to_string(&node_resolve::normalize_path(&base_dir.join(source)))
+    } else {
+      String::from("")
+    };
+    return ResolveResult {
+      path,
+      warnings: take_warnings(&warnings, file.as_str()),
+      candidates,
+      trace: trace.steps(),
+      ..found(PathBuf::new(), options)
+    };
+  }
+
+  let mut attempted_paths: Vec<String> = vec![];
+  for probe in probes.lock().unwrap().iter() {
+    let probe = path_to_string(probe);
+    if !attempted_paths.contains(&probe) {
+      attempted_paths.push(probe);
+    }
+  }
+  resolved.attempted_paths = Some(attempted_paths);
+  if is_absolute(&file) {
+    let suggestions = get_suggestions(source, Path::new(file.as_str()), &ts_config_files, options);
+    resolved.suggestions = Some(suggestions);
+  }
+  resolved.warnings = take_warnings(&warnings, file.as_str());
+  resolved.candidates = candidates;
+  trace.step(|| format!("not found: {:?}", resolved.reason));
+  resolved.trace = trace.steps();
+  resolved
+}
+
+#[napi]
+pub fn resolve(
+  source_input: String,
+  file: String,
+  options: Options,
+) -> napi::Result<ResolveResult> {
+  let projects = get_projects(&options)?;
+  let resolver = build_resolver(&options);
+  let fingerprint = get_fingerprint(&options, &projects);
+  Ok(resolve_projects_cached(
+    source_input,
+    file,
+    &projects,
+    &resolver,
+    &options,
+    fingerprint,
+  ))
+}
+
+// Core modules and ignored ones resolve to no path
+fn describe_path(path: &str) -> &str {
+  match path.is_empty() {
+    true => "a core module",
+    false => path,
+  }
+}
+
+// Explain how `source` resolves from `file`, line by line, e.g. for editors
+// to show on hover. Unlike `resolve()`, every project is tried even after one
+// resolves, the outcome is the one `resolve()` reports
+#[napi]
+pub fn explain(source_input: String, file: String, options: Options) -> napi::Result<String> {
+  let file = get_absolute_file(file, &options);
+  let projects = get_projects(&options)?;
+  let resolver = build_resolver(&options);
+  let mut lines = vec![format!("Resolving \"{}\" from {}", source_input, file)];
+  let nearest_project = match options.nearest_tsconfig_first {
+    Some(true) => get_nearest_project(Path::new(file.as_str()), &projects, resolver.get_cwd()),
+    _ => None,
+  };
+  let default_projects = get_default_projects(Path::new(file.as_str()), &options);
+  let ts_config_files = nearest_project
+    .iter()
+    .chain(projects.iter())
+    .chain(default_projects.iter());
+  let mut explained = vec![];
+  for ts_config_file in ts_config_files {
+    // The default project may be a configured one
+    let ts_config_path = get_ts_config_path(ts_config_file.clone(), resolver.get_cwd());
+    if explained.contains(&ts_config_path) {
+      continue;
+    }
+    explained.push(ts_config_path);
+    let trace = trace::Trace::new(true, false);
+    let resolved = resolve_single_project(
+      source_input.as_str(),
+      file.as_str(),
+      ts_config_file.as_str(),
+      &resolver.with_trace(trace.clone()),
+      &options,
+    );
+    let steps = trace.steps().unwrap_or_default();
+    lines.extend(
+      explain::narrate(&steps)
+        .iter()
+        .map(|line| format!("  {}", line)),
+    );
+    lines.push(match resolved.found {
+      true => format!("  → found {}", describe_path(&resolved.path)),
+      false => format!(
+        "  → not found: {:?}",
+        resolved.reason.unwrap_or(FailureReason::NotFound)
+      ),
+    });
+  }
+
+  let resolved = resolve_projects(source_input, file, &projects, &resolver, &options);
+  for warning in resolved.warnings.iter().flatten() {
+    lines.push(format!("Warning: {}", warning));
+  }
+  if resolved.found {
+    lines.push(format!("Resolved to {}", describe_path(&resolved.path)));
+  } else {
+    let reason = resolved.reason.unwrap_or(FailureReason::NotFound);
+    lines.push(format!("Not resolved: {:?}", reason));
+    if let Some(suggestions) = resolved
+      .suggestions
+      .filter(|suggestions| !suggestions.is_empty())
+    {
+      let suggestions: Vec<String> = suggestions
+        .iter()
+        .map(|suggestion| format!("\"{}\"", suggestion))
+        .collect();
+      lines.push(format!("Did you mean {}?", suggestions.join(", ")));
+    }
+  }
+  Ok(lines.join("\n"))
+}
+
+// The outcome of checking one mapping of a project with `doctor()`
+#[napi(object)]
+pub struct DoctorCheck {
+  pub project: String,
+  // "project", "extends", "paths", or "workspaceAlias"
+  pub kind: String,
+  // the project as given, `extends` entry, `paths` pattern, or workspace
+  // package name
+  pub name: String,
+  // what it maps to, e.g. a `paths` target as written
+  pub target: String,
+  pub ok: bool,
+  pub problem: Option<String>,
+}
+
+fn to_doctor_check(
+  project: &Path,
+  kind: &str,
+  name: &str,
+  target: String,
+  problem: Option<String>,
+) -> DoctorCheck {
+  DoctorCheck {
+    project: path_to_string(project),
+    kind: String::from(kind),
+    name: String::from(name),
+    target,
+    ok: problem.is_none(),
+    problem,
+  }
+}
+
+// Check the `extends` entries of the tsconfig file at `ts_config_path` and
+// of the files it extends, each file once
+fn check_extends(
+  project: &Path,
+  ts_config_path: &Path,
+  visited: &mut Vec<PathBuf>,
+  checks: &mut Vec<DoctorCheck>,
+) {
+  if visited.contains(&ts_config_path.to_path_buf()) {
+    return;
+  }
+  visited.push(ts_config_path.to_path_buf());
+  let config = match jsonc::parse_file(ts_config_path) {
+    Some(config) => config,
+    None => return,
+  };
+  let ts_config_dir = get_parent_dir(ts_config_path);
+  for extends in get_extends_entries(&config) {
+    match resolve_extends(extends, &ts_config_dir) {
+      Some(extends_path) => {
+        checks.push(to_doctor_check(
+          project,
+          "extends",
+          extends,
+          path_to_string(&extends_path),
+          None,
+        ));
+        check_extends(project, &extends_path, visited, checks);
+      }
+      None => checks.push(to_doctor_check(
+        project,
+        "extends",
+        extends,
+        String::from(extends),
+        Some(format!("Not found from {}", ts_config_path.display())),
+      )),
+    }
+  }
+}
+
+// Check the targets of the `paths` of `ts_config_file`. Wildcard targets
+// need their dir to exist, others need to resolve
+fn check_paths(
+  ts_config_file: &str,
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+  checks: &mut Vec<DoctorCheck>,
+) {
+  let ts_config = match get_ts_config(String::from(ts_config_file), resolver.get_cwd()) {
+    Ok(ts_config) => ts_config,
+    Err(_) => return,
+  };
+  let ts_config_path = get_ts_config_path(String::from(ts_config_file), resolver.get_cwd());
+  let project = &ts_config.project;
+  let base_dir = project.paths_base_dir();
+  let resolver = resolver.with_extensions(get_paths_extensions(options));
+  for (pattern, targets) in project.paths().iter() {
+    for target in targets.iter() {
+      let problem = match target.split_once('*') {
+        // e.g. "src/*" needs src, "src/lib-*" too
+        Some((prefix, _)) => {
+          let dir = node_resolve::normalize_path(&base_dir.join(prefix));
+          let dir = match prefix.is_empty() || prefix.ends_with('/') {
+            true => dir,
+            false => get_parent_dir(&dir),
+          };
+          (!dir.is_dir()).then(|| format!("No dir {}", dir.display()))
+        }
+        None => project
+          .resolve_paths_target(target, &resolver)
+          .err()
+          .map(|_| {
+            let path = node_resolve::normalize_path(&base_dir.join(target));
+            format!("Not found: {}", path.display())
+          }),
+      };
+      checks.push(to_doctor_check(
+        &ts_config_path,
+        "paths",
+        pattern,
+        target.clone(),
+        problem,
+      ));
+    }
+  }
+}
+
+// Check the entries the packages of the workspace `ts_config_path` is in
+// are aliased to with `workspaceAliases`. Packages without `source` or
+// `main` aren't aliased
+fn check_workspace_aliases(
+  ts_config_path: &Path,
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+  checks: &mut Vec<DoctorCheck>,
+) {
+  let root = match workspace::find_root(&get_parent_dir(ts_config_path)) {
+    Some(root) => root,
+    None => return,
+  };
+  let packages = workspace::get_packages(&root);
+  let mut names: Vec<&String> = packages.keys().collect();
+  names.sort();
+  let resolver = resolver.with_extensions(get_paths_extensions(options));
+  for name in names {
+    let entry = match workspace::get_source_entry(&packages[name]) {
+      Some(entry) => entry,
+      None => continue,
+    };
+    let problem = resolver
+      .resolve(path_to_string(&entry).as_str())
+      .err()
+      .map(|_| format!("Entry not found: {}", entry.display()));
+    checks.push(to_doctor_check(
+      ts_config_path,
+      "workspaceAlias",
+      name,
+      path_to_string(&entry),
+      problem,
+    ));
+  }
+}
+
+// Check that the projects and their mappings resolve: every `extends` link,
+// `paths` target, and workspace alias, e.g. for `ts-resolve doctor`.
+// Without projects, the tsconfig.json of `cwd` is checked
+#[napi]
+pub fn doctor(options: Options) -> napi::Result<Vec<DoctorCheck>> {
+  let mut projects = get_projects(&options)?;
+  if projects.is_empty() {
+    projects.push(path_to_string(&get_cwd(&options).join("tsconfig.json")));
+  }
+  let resolver = build_resolver(&options);
+  let mut checks = vec![];
+  for project in projects.iter() {
+    let ts_config_path = get_ts_config_path(project.clone(), resolver.get_cwd());
+    if !project.starts_with(INLINE_TS_CONFIG_PREFIX) && !ts_config_path.is_file() {
+      return Err(napi::Error::from_reason(format!(
+        "Cannot read project {}",
+        ts_config_path.display()
+      )));
+    }
+    check_extends(&ts_config_path, &ts_config_path, &mut vec![], &mut checks);
+    // e.g. for a missing file it extends, its `paths` are unknown then
+    if let Err(error) = get_ts_config(project.clone(), resolver.get_cwd()) {
+      checks.push(to_doctor_check(
+        &ts_config_path,
+        "project",
+        project,
+        path_to_string(&ts_config_path),
+        Some(error),
+      ));
+    }
+    check_paths(project, &resolver, &options, &mut checks);
+    check_workspace_aliases(&ts_config_path, &resolver, &options, &mut checks);
+  }
+  Ok(checks)
+}
+
+// Resolve to just the path, "" when not found, sparing JS the result object.
+// Core modules resolve to "" as well, check them with `isCoreModule()`
+#[napi]
+pub fn resolve_path(source_input: String, file: String, options: Options) -> napi::Result<String> {
+  resolve(source_input, file, options).map(|resolved| resolved.path)
+}
+
+pub struct ResolveTask {
+  source_input: String,
+  file: String,
+  options: Options,
+}
+
+impl Task for ResolveTask {
+  type Output = ResolveResult;
+  type JsValue = ResolveResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let projects = get_projects(&self.options)?;
+    let resolver = build_resolver(&self.options);
+    let fingerprint = get_fingerprint(&self.options, &projects);
+    Ok(resolve_projects_cached(
+      self.source_input.clone(),
+      self.file.clone(),
+      &projects,
+      &resolver,
+      &self.options,
+      fingerprint,
+    ))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+// Resolve on the libuv threadpool, keeping the event loop free
+#[napi(ts_return_type = "Promise<ResolveResult>")]
+pub fn resolve_async(
+  source_input: String,
+  file: String,
+  options: Options,
+) -> AsyncTask<ResolveTask> {
+  AsyncTask::new(ResolveTask {
+    source_input,
+    file,
+    options,
+  })
+}
+
+#[napi(object)]
+pub struct ResolveRequest {
+  pub source: String,
+  pub file: String,
+}
+
+// Resolve each request on the rayon pool, all sharing one resolver and the
+// process-wide caches, or in turn with protocol handlers registered, which
+// only run on the JS thread. Results keep the order of `requests`
+fn resolve_requests(
+  requests: Vec<ResolveRequest>,
+  projects: &[String],
+  resolver: &node_resolve::Resolver,
+  options: &Options,
+  fingerprint: u64,
+) -> Vec<ResolveResult> {
+  let resolve_request = |request: ResolveRequest| {
+    resolve_projects_cached(
+      request.source,
+      request.file,
+      projects,
+      resolver,
+      options,
+      fingerprint,
+    )
+  };
+  match protocol_handlers::is_registered() {
+    true => requests.into_iter().map(resolve_request).collect(),
+    false => requests.into_par_iter().map(resolve_request).collect(),
+  }
+}
+
+// Resolve many imports at once, in parallel, e.g. all imports of a file
+#[napi]
+pub fn resolve_batch(
+  requests: Vec<ResolveRequest>,
+  options: Options,
+) -> napi::Result<Vec<ResolveResult>> {
+  let projects = get_projects(&options)?;
+  let resolver = build_resolver(&options);
+  let fingerprint = get_fingerprint(&options, &projects);
+  Ok(resolve_requests(
+    requests,
+    &projects,
+    &resolver,
+    &options,
+    fingerprint,
+  ))
+}
+
+// Get the names of the packages the files of `files` import, by the name of
+// the package importing them. Files outside of packages are left out
+fn get_package_graph(
+  files: &BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+) -> BTreeMap<String, BTreeSet<String>> {
+  let package_name = |path: &Path| {
+    let (_, pkg) = get_enclosing_package(path)?;
+    pkg["name"].as_str().map(String::from)
+  };
+  let mut packages: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+  for (file, targets) in files {
+    let name = match package_name(file) {
+      Some(name) => name,
+      None => continue,
+    };
+    let dependencies = packages.entry(name.clone()).or_default();
+    for target in targets {
+      match package_name(target) {
+        Some(dependency) if dependency != name => dependencies.insert(dependency),
+        _ => false,
+      };
+    }
+  }
+  packages
+}
+
+// Resolve the imports of the files matching `entryGlobs`, relative to
+// `cwd`, and of the files they import in turn, reusing the caches of
+// `resolve()`. Returns the graph as JSON: the files each file imports, the
+// packages each package imports, and what didn't resolve
+#[napi]
+pub fn build_import_graph(entry_globs: Vec<String>, options: Options) -> napi::Result<String> {
+  let projects = get_projects(&options)?;
+  let resolver = build_resolver(&options);
+  let fingerprint = get_fingerprint(&options, &projects);
+  let mut entries = vec![];
+  for entry_glob in entry_globs.iter() {
+    let pattern = node_resolve::normalize_path(&get_cwd(&options).join(entry_glob));
+    let pattern = path_to_string(&pattern);
+    let matches = glob(pattern.as_str()).map_err(|error| {
+      napi::Error::from_reason(format!(
+        "Invalid entry pattern \"{}\": {}",
+        entry_glob, error
+      ))
+    })?;
+    entries.extend(
+      matches
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.is_file() && !is_in_node_modules(entry)),
+    );
+  }
+  entries.sort();
+  entries.dedup();
+
+  let graph = import_graph::build(entries, &resolver, |source, file| {
+    let resolved = resolve_projects_cached(
+      String::from(source),
+      path_to_string(file),
+      &projects,
+      &resolver,
+      &options,
+      fingerprint,
+    );
+    Some(PathBuf::from(resolved.path)).filter(|_| resolved.found)
+  });
+  let to_strings = |paths: &BTreeSet<PathBuf>| -> Vec<String> {
+    paths.iter().map(|path| path_to_string(path)).collect()
+  };
+  let files: BTreeMap<String, Vec<String>> = graph
+    .files
+    .iter()
+    .map(|(file, targets)| (path_to_string(file), to_strings(targets)))
+    .collect();
+  let unresolved: BTreeMap<String, &BTreeSet<String>> = graph
+    .unresolved
+    .iter()
+    .map(|(file, specifiers)| (path_to_string(file), specifiers))
+    .collect();
+  let graph = serde_json::json!({
+    "files": files,
+    "packages": get_package_graph(&graph.files),
+    "unresolved": unresolved,
+  });
+  Ok(graph.to_string())
+}
+
+// Stat the package.json of every package in a node_modules dir, including
+// scoped ones, so the OS has them cached before the first lookup
+fn stat_node_modules(node_modules: &Path) {
+  let entries = match fs::read_dir(node_modules) {
+    Ok(entries) => entries,
+    Err(_) => return,
+  };
+  for entry in entries.filter_map(|entry| entry.ok()) {
+    let path = entry.path();
+    if entry.file_name().to_string_lossy().starts_with('@') {
+      stat_node_modules(&path);
+    } else {
+      let _ = path.join("package.json").is_file();
+    }
+  }
+}
+
+pub struct WarmTask {
+  project: String,
+  stat_node_modules: bool,
+  cwd: PathBuf,
+}
+
+impl Task for WarmTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let cwd = &self.cwd;
+    let base_dir = get_base_dir(self.project.clone(), cwd);
+    if let Ok(ts_config) = get_ts_config(self.project.clone(), cwd) {
+      ts_config.project.paths_matcher();
+    }
+    for reference in get_references(self.project.clone(), cwd) {
+      get_base_dir(reference, cwd);
+    }
+    package_index::index_ancestors(&base_dir);
+    if self.stat_node_modules {
+      stat_node_modules(&base_dir.join("node_modules"));
+    }
+    Ok(())
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+// Load a project's tsconfig, its `extends` chain, and its `paths` ahead of
+// the first resolution, plus the tsconfig files of its `references`, and
+// list the node_modules dirs its files resolve bare imports in. With
+// `statNodeModules`, also touch the packages in its node_modules dir. A
+// relative `project` is looked up from the `cwd` of `options`, by default
+// the current dir. Runs on the libuv threadpool
+#[napi(ts_return_type = "Promise<void>")]
+pub fn warm(
+  project: String,
+  stat_node_modules: Option<bool>,
+  options: Option<Options>,
+) -> AsyncTask<WarmTask> {
+  AsyncTask::new(WarmTask {
+    project,
+    stat_node_modules: stat_node_modules == Some(true),
+    cwd: options.as_ref().map_or_else(get_current_dir, get_cwd),
+  })
+}
+
+/// Resolver built once from options. Reuses its node resolver and expanded
+/// `project` globs across `resolve()` calls. Instances implement the
+/// import-resolver v3 interface.
+#[napi(js_name = "Resolver")]
+pub struct ResolverInstance {
+  options: Options,
+  projects: Vec<String>,
+  resolver: node_resolve::Resolver,
+  fingerprint: u64,
+}
+
+#[napi]
+impl ResolverInstance {
+  #[napi(constructor)]
+  pub fn new(options: Options) -> napi::Result<Self> {
+    let projects = get_projects(&options)?;
+    Ok(ResolverInstance {
+      fingerprint: get_fingerprint(&options, &projects),
+      resolver: build_resolver(&options),
+      projects,
+      options,
+    })
+  }
+
+  // import-resolver v3 interface, see eslint-plugin-import-x
+  #[napi(getter)]
+  pub fn interface_version(&self) -> u32 {
+    3
+  }
+
+  #[napi(getter)]
+  pub fn name(&self) -> String {
+    String::from(env!("CARGO_PKG_NAME"))
+  }
+
+  #[napi]
+  pub fn dump_config(&self) -> ResolverConfig {
+    get_resolver_config(&self.options, &self.projects)
+  }
+
+  #[napi]
+  pub fn resolve(&self, source_input: String, file: String) -> ResolveResult {
+    resolve_projects_cached(
+      source_input,
+      file,
+      &self.projects,
+      &self.resolver,
+      &self.options,
+      self.fingerprint,
+    )
+  }
+
+  // Like `resolve`, but to just the path, "" when not found
+  #[napi]
+  pub fn resolve_path(&self, source_input: String, file: String) -> String {
+    self.resolve(source_input, file).path
+  }
+
+  #[napi]
+  pub fn resolve_batch(&self, requests: Vec<ResolveRequest>) -> Vec<ResolveResult> {
+    resolve_requests(
+      requests,
+      &self.projects,
+      &self.resolver,
+      &self.options,
+      self.fingerprint,
+    )
+  }
+}
+
+// Factory like eslint-plugin-import-x's `createTypeScriptImportResolver`,
+// for flat configs: `{ 'import-x/resolver-next': [createResolver(options)] }`
+#[napi]
+pub fn create_resolver(options: Options) -> napi::Result<ResolverInstance> {
+  ResolverInstance::new(options)
 }
@@ -0,0 +1,57 @@
+//! Forward diagnostic events to a logger registered from JavaScript.
+//!
+//! ```text
+//! log(LogEventKind::TsconfigReload, "/repo/tsconfig.json", || {
+//!   String::from("read tsconfig")
+//! });
+//! // → logger({ kind: "TsconfigReload", path: "/repo/tsconfig.json", message: "read tsconfig" })
+//! ```
+
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use std::sync::Mutex;
+
+#[napi(string_enum)]
+pub enum LogEventKind {
+  // a cached value had to be computed
+  CacheMiss,
+  // a tsconfig file was read from disk
+  TsconfigReload,
+  // a package's `exports` don't allow the requested subpath
+  ExportsMismatch,
+  // a tsconfig `paths` pattern or target has more than one "*" and is skipped
+  InvalidPathsPattern,
+  // a problem with the configuration or a package, e.g. an unreadable
+  // tsconfig or malformed "exports", that resolution went on despite
+  ConfigWarning,
+}
+
+#[napi(object)]
+pub struct LogEvent {
+  pub kind: LogEventKind,
+  pub path: String,
+  pub message: String,
+}
+
+// Called on the JS thread; events logged from other threads are queued
+static LOGGER: Mutex<Option<ThreadsafeFunction<LogEvent, ErrorStrategy::Fatal>>> = Mutex::new(None);
+
+/// Replace the registered logger, or remove it with `None`.
+pub fn set(logger: Option<ThreadsafeFunction<LogEvent, ErrorStrategy::Fatal>>) {
+  *LOGGER.lock().unwrap() = logger;
+}
+
+/// Send an event to the registered logger. Without one, the message is not
+/// formatted at all.
+pub fn log<F>(kind: LogEventKind, path: &str, message: F)
+where
+  F: FnOnce() -> String,
+{
+  if let Some(logger) = LOGGER.lock().unwrap().as_ref() {
+    let event = LogEvent {
+      kind,
+      path: String::from(path),
+      message: message(),
+    };
+    logger.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+  }
+}
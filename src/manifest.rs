@@ -0,0 +1,141 @@
+//! Answer resolutions from a manifest written by an earlier run, e.g. once
+//! per CI pipeline, before any filesystem work. Paths in the manifest are
+//! relative to its own dir, so it stays valid wherever the checkout lives.
+//!
+//! ```text
+//! write(Path::new("/repo/resolutions.json"));
+//! // → Ok(1200), the found resolutions of this process
+//! load(Path::new("/ci/checkout/resolutions.json"));
+//! // → Ok(1200)
+//! get("./foo", Path::new("/ci/checkout/src"));
+//! // → Some(ResolveResult { path: "/ci/checkout/src/foo.ts", .. })
+//! ```
+
+use crate::node_resolve::normalize_path;
+use crate::persistent_cache::{result_from_json, result_to_json};
+use crate::{resolution_cache, ResolveResult};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+// Bumped whenever the file layout changes
+const FORMAT_VERSION: u64 = 1;
+
+// Results by importing dir and specifier, `None` until a manifest is loaded
+type Entries = HashMap<(PathBuf, String), ResolveResult>;
+
+static ENTRIES: RwLock<Option<Entries>> = RwLock::new(None);
+
+fn get_manifest_dir(manifest_file: &Path) -> PathBuf {
+  manifest_file
+    .parent()
+    .map(Path::to_path_buf)
+    .unwrap_or_default()
+}
+
+// Make `path` relative to the manifest dir, unless it is outside of it.
+// Empty paths, those of core modules, stay empty
+fn to_relative(path: &str, manifest_dir: &Path) -> String {
+  if path.is_empty() {
+    return String::new();
+  }
+  match Path::new(path).strip_prefix(manifest_dir) {
+    Ok(relative) if relative.as_os_str().is_empty() => String::from("."),
+    Ok(relative) => relative.to_string_lossy().into_owned(),
+    Err(_) => String::from(path),
+  }
+}
+
+fn to_absolute(path: &str, manifest_dir: &Path) -> String {
+  if path.is_empty() {
+    return String::new();
+  }
+  normalize_path(&manifest_dir.join(path))
+    .to_string_lossy()
+    .into_owned()
+}
+
+/// Load the manifest at `manifest_file`, replacing any loaded before.
+/// Returns the number of resolutions in it.
+pub fn load(manifest_file: &Path) -> Result<usize, String> {
+  let content = fs::read_to_string(manifest_file)
+    .map_err(|e| format!("Cannot read resolution manifest: {}", e))?;
+  let manifest: Value = serde_json::from_str(content.as_str())
+    .map_err(|e| format!("Malformed resolution manifest: {}", e))?;
+  if manifest["version"].as_u64() != Some(FORMAT_VERSION) {
+    return Err(format!(
+      "Unsupported resolution manifest version, expected {}",
+      FORMAT_VERSION
+    ));
+  }
+
+  let manifest_dir = get_manifest_dir(manifest_file);
+  let mut entries = Entries::new();
+  for entry in manifest["resolutions"].as_array().into_iter().flatten() {
+    let (dir, source) = match (entry["dir"].as_str(), entry["source"].as_str()) {
+      (Some(dir), Some(source)) => (dir, source),
+      _ => continue,
+    };
+    let mut result = match result_from_json(&entry["result"]) {
+      Some(result) => result,
+      None => continue,
+    };
+    result.path = to_absolute(result.path.as_str(), &manifest_dir);
+    result.implementation_path = result
+      .implementation_path
+      .map(|path| to_absolute(path.as_str(), &manifest_dir));
+    let dir = normalize_path(&manifest_dir.join(dir));
+    entries.insert((dir, String::from(source)), result);
+  }
+  let count = entries.len();
+  *ENTRIES.write().unwrap() = Some(entries);
+  Ok(count)
+}
+
+/// Stop answering from the loaded manifest.
+pub fn unload() {
+  *ENTRIES.write().unwrap() = None;
+}
+
+/// Get the result the manifest lists for `source` imported from `dir`.
+pub fn get(source: &str, dir: &Path) -> Option<ResolveResult> {
+  let entries = ENTRIES.read().unwrap();
+  entries
+    .as_ref()?
+    .get(&(dir.to_path_buf(), String::from(source)))
+    .cloned()
+}
+
+/// Write the found resolutions of the current generation to `manifest_file`,
+/// e.g. after a `warm()` and one lint run. Returns the number written.
+pub fn write(manifest_file: &Path) -> Result<usize, String> {
+  let manifest_dir = get_manifest_dir(manifest_file);
+  // Sorted, so the same resolutions make the same file
+  let mut resolutions: BTreeMap<(String, String), Value> = BTreeMap::new();
+  for (key, result) in resolution_cache::entries() {
+    if !result.found || result.attempted_paths.is_some() {
+      continue;
+    }
+    let mut result = result_to_json(&result);
+    for name in ["path", "implementationPath"] {
+      if let Some(path) = result[name].as_str() {
+        result[name] = Value::from(to_relative(path, &manifest_dir));
+      }
+    }
+    let dir = to_relative(key.dir().to_string_lossy().as_ref(), &manifest_dir);
+    resolutions.insert((dir, String::from(key.source())), result);
+  }
+  let count = resolutions.len();
+  let resolutions: Vec<Value> = resolutions
+    .into_iter()
+    .map(|((dir, source), result)| json!({ "dir": dir, "source": source, "result": result }))
+    .collect();
+  let manifest = json!({ "version": FORMAT_VERSION, "resolutions": resolutions });
+
+  let temp_file = manifest_file.with_extension(format!("{}.tmp", std::process::id()));
+  fs::write(&temp_file, manifest.to_string()).map_err(|e| e.to_string())?;
+  fs::rename(&temp_file, manifest_file).map_err(|e| e.to_string())?;
+  Ok(count)
+}
@@ -0,0 +1,196 @@
+//! Keep found resolutions and parsed package.json files across processes, so
+//! repeated CI lint jobs and pre-commit hooks skip the cold-start walk. Cache
+//! files are named by a hash of the lockfiles and tsconfig files in the root
+//! dir, so installing packages or editing a config starts over.
+//!
+//! ```text
+//! load(Path::new("/repo/node_modules/.cache/resolver"), Path::new("/repo"));
+//! // → Ok(120), restored from /repo/node_modules/.cache/resolver/resolutions-<hash>.json
+//! save(Path::new("/repo/node_modules/.cache/resolver"), Path::new("/repo"));
+//! // → Ok(()), that file now holds what this process resolved
+//! ```
+
+use crate::{node_resolve, resolution_cache, ResolveResult};
+use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LOCKFILES: &[&str] = &[
+  "package-lock.json",
+  "npm-shrinkwrap.json",
+  "yarn.lock",
+  "pnpm-lock.yaml",
+  "bun.lockb",
+];
+
+// Bumped whenever the file layout changes, so old files are ignored
+const FORMAT_VERSION: u64 = 2;
+
+// Hash the lockfiles and the tsconfig*.json files directly in `root`
+fn get_key(root: &Path) -> u64 {
+  let mut names: Vec<String> = fs::read_dir(root)
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| {
+          LOCKFILES.contains(&name.as_str())
+            || (name.starts_with("tsconfig") && name.ends_with(".json"))
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  names.sort();
+
+  let mut hasher = DefaultHasher::new();
+  FORMAT_VERSION.hash(&mut hasher);
+  for name in names.iter() {
+    name.hash(&mut hasher);
+    fs::read(root.join(name))
+      .unwrap_or_default()
+      .hash(&mut hasher);
+  }
+  hasher.finish()
+}
+
+fn get_cache_file(cache_dir: &Path, root: &Path) -> PathBuf {
+  cache_dir.join(format!("resolutions-{:016x}.json", get_key(root)))
+}
+
+pub fn result_to_json(result: &ResolveResult) -> Value {
+  json!({
+    "path": result.path,
+    "implementationPath": result.implementation_path,
+    "packageName": result.package_name,
+    "packageVersion": result.package_version,
+    "isExternal": result.is_external,
+    "isWorkspacePackage": result.is_workspace_package,
+    "isDeclarationFile": result.is_declaration_file,
+    "isFallback": result.is_fallback,
+    "matchedPattern": result.matched_pattern,
+  })
+}
+
+pub fn result_from_json(value: &Value) -> Option<ResolveResult> {
+  let get_string = |name: &str| value[name].as_str().map(String::from);
+  Some(ResolveResult {
+    found: true,
+    path: get_string("path")?,
+    implementation_path: get_string("implementationPath"),
+    reason: None,
+    attempted_paths: None,
+    suggestions: None,
+    warnings: None,
+    ambiguous_paths: None,
+    candidates: None,
+    trace: None,
+    package_name: get_string("packageName"),
+    package_version: get_string("packageVersion"),
+    is_external: value["isExternal"].as_bool(),
+    is_workspace_package: value["isWorkspacePackage"].as_bool(),
+    is_declaration_file: value["isDeclarationFile"].as_bool(),
+    is_fallback: value["isFallback"].as_bool(),
+    matched_pattern: get_string("matchedPattern"),
+    timing: None,
+    probe_counts: None,
+  })
+}
+
+fn stamp_to_json(stamp: &(SystemTime, u64)) -> Option<Value> {
+  let modified = stamp.0.duration_since(UNIX_EPOCH).ok()?;
+  Some(json!([
+    modified.as_secs(),
+    modified.subsec_nanos(),
+    stamp.1
+  ]))
+}
+
+fn stamp_from_json(value: &Value) -> Option<(SystemTime, u64)> {
+  let secs = value[0].as_u64()?;
+  let nanos = u32::try_from(value[1].as_u64()?).ok()?;
+  let modified = UNIX_EPOCH + Duration::new(secs, nanos);
+  Some((modified, value[2].as_u64()?))
+}
+
+/// Restore what an earlier process saved for the same lockfiles and tsconfig
+/// files. Resolutions whose file is gone are skipped. Returns the number of
+/// entries restored, 0 without a matching cache file.
+pub fn load(cache_dir: &Path, root: &Path) -> Result<usize, String> {
+  let content = match fs::read_to_string(get_cache_file(cache_dir, root)) {
+    Ok(content) => content,
+    Err(_) => return Ok(0),
+  };
+  let cache: Value = serde_json::from_str(content.as_str())
+    .map_err(|e| format!("Malformed resolver cache file: {}", e))?;
+
+  let mut restored = 0;
+  for entry in cache["resolutions"].as_array().into_iter().flatten() {
+    let (source, dir, fingerprint) = match (
+      entry["source"].as_str(),
+      entry["dir"].as_str(),
+      entry["fingerprint"].as_u64(),
+    ) {
+      (Some(source), Some(dir), Some(fingerprint)) => (source, dir, fingerprint),
+      _ => continue,
+    };
+    let result = match result_from_json(&entry["result"]) {
+      Some(result) => result,
+      None => continue,
+    };
+    // Stat the file instead of walking to it again
+    if !Path::new(result.path.as_str()).is_file() {
+      continue;
+    }
+    let key = resolution_cache::ResolutionKey::new(source, Path::new(dir), fingerprint);
+    resolution_cache::insert(key, result);
+    restored += 1;
+  }
+  for entry in cache["packageJsons"].as_array().into_iter().flatten() {
+    if let (Some(pkg_path), Some(stamp)) =
+      (entry["path"].as_str(), stamp_from_json(&entry["stamp"]))
+    {
+      node_resolve::insert_package_json(PathBuf::from(pkg_path), stamp, entry["value"].clone());
+      restored += 1;
+    }
+  }
+  Ok(restored)
+}
+
+/// Write the found resolutions and parsed package.json files of this process
+/// for later ones. Results with attempted paths are left out, they are only
+/// asked for when debugging.
+pub fn save(cache_dir: &Path, root: &Path) -> Result<(), String> {
+  let resolutions: Vec<Value> = resolution_cache::entries()
+    .iter()
+    .filter(|(_, result)| result.found && result.attempted_paths.is_none())
+    .map(|(key, result)| {
+      json!({
+        "source": key.source(),
+        "dir": key.dir().to_string_lossy(),
+        "fingerprint": key.fingerprint(),
+        "result": result_to_json(result),
+      })
+    })
+    .collect();
+  let package_jsons: Vec<Value> = node_resolve::package_json_entries()
+    .iter()
+    .filter_map(|(pkg_path, stamp, pkg)| {
+      Some(json!({
+        "path": pkg_path.to_string_lossy(),
+        "stamp": stamp_to_json(stamp)?,
+        "value": pkg.as_ref().clone(),
+      }))
+    })
+    .collect();
+  let cache = json!({ "resolutions": resolutions, "packageJsons": package_jsons });
+
+  fs::create_dir_all(cache_dir).map_err(|e| e.to_string())?;
+  let cache_file = get_cache_file(cache_dir, root);
+  // Write then rename, so concurrent jobs never read a partial file
+  let temp_file = cache_file.with_extension(format!("{}.tmp", std::process::id()));
+  fs::write(&temp_file, cache.to_string()).map_err(|e| e.to_string())?;
+  fs::rename(&temp_file, &cache_file).map_err(|e| e.to_string())
+}
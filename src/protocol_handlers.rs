@@ -0,0 +1,147 @@
+//! Resolve specifiers with custom prefixes, e.g. `virtual:`, `~icons/`, or
+//! `$lib/`, through handlers registered from JavaScript, for modules that
+//! never exist on disk or that only a bundler plugin knows about.
+//!
+//! ```text
+//! // registerProtocolHandler("virtual:", () => ({ external: true }))
+//! handle("virtual:routes", "/repo/src/main.ts");
+//! // → Some(Handled::External)
+//! // registerProtocolHandler("~icons/", (specifier) => `/repo/icons/${specifier.slice(7)}.svg`)
+//! handle("~icons/home", "/repo/src/main.ts");
+//! // → Some(Handled::Path("/repo/icons/home.svg"))
+//! ```
+
+use napi::threadsafe_function::{
+  ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{Env, JsFunction, JsUnknown, Ref, ValueType};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+
+/// What a handler made of a specifier.
+pub enum Handled {
+  Path(String),
+  External,
+}
+
+type Handler = ThreadsafeFunction<(String, String), ErrorStrategy::Fatal>;
+
+// Handlers by prefix, as references the JS thread's env can call
+type JsHandlers = (Env, Vec<(String, Ref<()>)>);
+
+// Called from other threads, e.g. of `resolveAsync()`, which wait for the
+// JS thread to run the handler
+static HANDLERS: Mutex<Vec<(String, Handler)>> = Mutex::new(Vec::new());
+
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+  // The JS thread calls handlers directly, it can't wait for itself
+  static JS_HANDLERS: RefCell<Option<JsHandlers>> = const { RefCell::new(None) };
+}
+
+/// Check if any handler is registered. Work fanned out to other threads by a
+/// call on the JS thread, which handlers then can't run on, has to stay on
+/// the JS thread then.
+pub fn is_registered() -> bool {
+  REGISTERED.load(Ordering::Relaxed)
+}
+
+/// Register `handler` for specifiers starting with `prefix`, replacing the
+/// one registered for it, or remove it with `None`.
+pub fn register(env: Env, prefix: String, handler: Option<JsFunction>) -> napi::Result<()> {
+  let mut handlers = HANDLERS.lock().unwrap();
+  handlers.retain(|(registered, _)| registered != &prefix);
+  JS_HANDLERS.with(|js_handlers| -> napi::Result<()> {
+    let mut js_handlers = js_handlers.borrow_mut();
+    let (_, js_handlers) = js_handlers.get_or_insert_with(|| (env, vec![]));
+    if let Some(index) = js_handlers
+      .iter()
+      .position(|(registered, _)| registered == &prefix)
+    {
+      js_handlers.remove(index).1.unref(env)?;
+    }
+    if let Some(handler) = &handler {
+      js_handlers.push((prefix.clone(), env.create_reference(handler)?));
+    }
+    Ok(())
+  })?;
+
+  if let Some(handler) = handler {
+    let mut threadsafe: Handler = handler
+      .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<(String, String)>| {
+        Ok(vec![ctx.value.0, ctx.value.1])
+      })?;
+    // Don't keep the process alive for the handler's sake
+    threadsafe.unref(&env)?;
+    handlers.push((prefix, threadsafe));
+  }
+  REGISTERED.store(!handlers.is_empty(), Ordering::Relaxed);
+  Ok(())
+}
+
+// Turn what a handler returned into what it made of the specifier: a path
+// string, `{ external: true }`, or nothing to leave it to resolution
+fn to_handled(value: JsUnknown) -> Option<Handled> {
+  match value.get_type().ok()? {
+    ValueType::String => {
+      let path = value.coerce_to_string().ok()?.into_utf8().ok()?;
+      Some(Handled::Path(String::from(path.as_str().ok()?)))
+    }
+    ValueType::Object => {
+      let object = value.coerce_to_object().ok()?;
+      let external: bool = object.get_named_property("external").ok()?;
+      external.then_some(Handled::External)
+    }
+    _ => None,
+  }
+}
+
+// Find the handler of the longest prefix of `specifier`
+fn find_handler<'h, H>(handlers: &'h [(String, H)], specifier: &str) -> Option<&'h H> {
+  handlers
+    .iter()
+    .filter(|(prefix, _)| specifier.starts_with(prefix.as_str()))
+    .max_by_key(|(prefix, _)| prefix.len())
+    .map(|(_, handler)| handler)
+}
+
+/// Get what the handler of the longest prefix of `specifier`, imported by
+/// `file`, makes of it. `None` if no handler matches or it leaves the
+/// specifier to resolution.
+pub fn handle(specifier: &str, file: &str) -> Option<Handled> {
+  if !is_registered() {
+    return None;
+  }
+  let called = JS_HANDLERS.with(|js_handlers| {
+    let js_handlers = js_handlers.borrow();
+    let (env, js_handlers) = js_handlers.as_ref()?;
+    let handler: JsFunction = env
+      .get_reference_value(find_handler(js_handlers, specifier)?)
+      .ok()?;
+    let args = [
+      env.create_string(specifier).ok()?,
+      env.create_string(file).ok()?,
+    ];
+    Some(handler.call(None, &args).ok().and_then(to_handled))
+  });
+  if let Some(handled) = called {
+    return handled;
+  }
+
+  let (sender, receiver) = mpsc::channel();
+  {
+    let handlers = HANDLERS.lock().unwrap();
+    let handler = find_handler(&handlers, specifier)?;
+    handler.call_with_return_value(
+      (String::from(specifier), String::from(file)),
+      ThreadsafeFunctionCallMode::Blocking,
+      move |value: JsUnknown| {
+        let _ = sender.send(to_handled(value));
+        Ok(())
+      },
+    );
+  }
+  receiver.recv().ok().flatten()
+}
@@ -0,0 +1,104 @@
+//! Memoize resolution results for the current generation. JS bumps the
+//! generation between lint runs, dropping everything resolved before.
+//!
+//! ```text
+//! let key = ResolutionKey::new("./foo", Path::new("/repo/src"), fingerprint);
+//! insert(key.clone(), result);
+//! get(&key);
+//! // → Some(result)
+//! bump_generation();
+//! get(&key);
+//! // → None
+//! ```
+
+use crate::lru::{self, LruMap};
+use crate::ResolveResult;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+static GENERATION: AtomicU32 = AtomicU32::new(0);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+// Shared by every thread the addon is called on, including worker threads
+fn results() -> &'static LruMap<ResolutionKey, ResolveResult> {
+  static RESULTS: OnceLock<LruMap<ResolutionKey, ResolveResult>> = OnceLock::new();
+  RESULTS.get_or_init(|| LruMap::new(lru::capacity(50_000)))
+}
+
+/// What a result depends on: the specifier, the importing file's directory,
+/// a fingerprint of the projects and options, and the generation it was
+/// resolved in.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ResolutionKey {
+  source: String,
+  dir: PathBuf,
+  fingerprint: u64,
+  generation: u32,
+}
+
+impl ResolutionKey {
+  pub fn new(source: &str, dir: &Path, fingerprint: u64) -> Self {
+    ResolutionKey {
+      source: String::from(source),
+      dir: dir.to_path_buf(),
+      fingerprint,
+      generation: GENERATION.load(Ordering::SeqCst),
+    }
+  }
+
+  pub fn source(&self) -> &str {
+    self.source.as_str()
+  }
+
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  pub fn fingerprint(&self) -> u64 {
+    self.fingerprint
+  }
+}
+
+pub fn get(key: &ResolutionKey) -> Option<ResolveResult> {
+  let result = results().get(key);
+  match result {
+    Some(_) => HITS.fetch_add(1, Ordering::Relaxed),
+    None => MISSES.fetch_add(1, Ordering::Relaxed),
+  };
+  result
+}
+
+/// Store a result, unless the generation moved on while it was resolved.
+pub fn insert(key: ResolutionKey, result: ResolveResult) {
+  if key.generation != GENERATION.load(Ordering::SeqCst) {
+    return;
+  }
+  results().insert(key, result);
+}
+
+/// Get the results of the current generation, e.g. to persist them.
+pub fn entries() -> Vec<(ResolutionKey, ResolveResult)> {
+  results().entries()
+}
+
+/// Start a new generation, dropping all results. Returns the new generation.
+pub fn bump_generation() -> u32 {
+  let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+  clear();
+  generation
+}
+
+pub fn clear() {
+  results().clear();
+}
+
+/// Get hits, misses, and size of the result cache.
+pub fn cache_stats() -> (u64, u64, usize) {
+  (
+    HITS.load(Ordering::Relaxed),
+    MISSES.load(Ordering::Relaxed),
+    results().len(),
+  )
+}
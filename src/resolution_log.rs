@@ -0,0 +1,45 @@
+//! Append a JSON record of each resolution to the file named by the
+//! `TS_RESOLVER_TRACE` environment variable, one per line, to diagnose
+//! failures that can't be debugged interactively, e.g. CI-only ones.
+//!
+//! ```text
+//! // TS_RESOLVER_TRACE=/tmp/trace.jsonl
+//! append(&json!({ "source": "./foo", "found": true }));
+//! // /tmp/trace.jsonl: {"source":"./foo","found":true}
+//! ```
+
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+
+// Opened on first use, the variable is read once per process
+static FILE: OnceLock<Option<Mutex<File>>> = OnceLock::new();
+
+fn get_file() -> &'static Option<Mutex<File>> {
+  FILE.get_or_init(|| {
+    let path = std::env::var_os("TS_RESOLVER_TRACE").filter(|path| !path.is_empty())?;
+    OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(path)
+      .ok()
+      .map(Mutex::new)
+  })
+}
+
+/// Check if resolutions are recorded.
+pub fn is_enabled() -> bool {
+  get_file().is_some()
+}
+
+/// Append `record` as a line. A failed write loses the record, never the
+/// resolution.
+pub fn append(record: &Value) {
+  if let Some(file) = get_file() {
+    let mut line = record.to_string();
+    line.push('\n');
+    // One write per record keeps records of parallel lint processes whole
+    let _ = file.lock().unwrap().write_all(line.as_bytes());
+  }
+}
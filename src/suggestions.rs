@@ -0,0 +1,183 @@
+//! Suggest specifiers close to one that didn't resolve: files next to the
+//! missing one, packages in node_modules, and alias or `paths` prefixes.
+//!
+//! ```text
+//! suggest("./utlis", Path::new("/repo/src/index.ts"), &prefixes, &node_modules);
+//! // /repo/src: utils.ts, index.ts
+//! // → ["./utils"]
+//! suggest("lodahs/fp", Path::new("/repo/src/index.ts"), &prefixes, &node_modules);
+//! // → ["lodash/fp"]
+//! ```
+
+use crate::validate::edit_distance;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_SUGGESTIONS: usize = 3;
+
+// Compare ignoring case, so only-case typos come first
+fn get_distance(name: &str, candidate: &str) -> usize {
+  edit_distance(&name.to_lowercase(), &candidate.to_lowercase())
+}
+
+// Allow about one typo per three characters, fewer than the name has
+fn is_close(name: &str, distance: usize) -> bool {
+  let length = name.chars().count();
+  distance <= (length / 3).max(2) && distance < length
+}
+
+// Get the names of `dir` as they'd be imported, i.e. files without their
+// extension, e.g. "index.d.ts" as "index"
+fn get_importable_names(dir: &Path) -> Vec<String> {
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(_) => return vec![],
+  };
+  let mut names: Vec<String> = entries
+    .filter_map(|entry| entry.ok())
+    .filter_map(|entry| {
+      let name = entry.file_name().to_str().map(String::from)?;
+      if name.starts_with('.') {
+        return None;
+      }
+      if entry.path().is_dir() {
+        return Some(name);
+      }
+      let stem = name.strip_suffix(".d.ts").unwrap_or(name.as_str());
+      let stem = Path::new(stem).file_stem()?.to_str()?;
+      Some(String::from(stem))
+    })
+    .collect();
+  names.sort();
+  names.dedup();
+  names
+}
+
+// Get the packages of a node_modules dir, scoped ones as "@scope/name"
+fn get_package_names(node_modules: &Path) -> Vec<String> {
+  get_importable_names(node_modules)
+    .into_iter()
+    .flat_map(|name| match name.starts_with('@') {
+      true => get_importable_names(&node_modules.join(&name))
+        .into_iter()
+        .map(|scoped| format!("{}/{}", name, scoped))
+        .collect(),
+      false => vec![name],
+    })
+    .collect()
+}
+
+// Split `specifier` after as many segments as `prefix` has
+fn split_segments<'s>(specifier: &'s str, prefix: &str) -> (&'s str, &'s str) {
+  let segments = prefix.split('/').count();
+  match specifier.match_indices('/').nth(segments - 1) {
+    Some((index, _)) => specifier.split_at(index),
+    None => (specifier, ""),
+  }
+}
+
+// Rank candidates by distance, keeping the closest few
+fn closest(mut suggestions: Vec<(usize, String)>) -> Vec<String> {
+  suggestions.sort();
+  let mut closest: Vec<String> = vec![];
+  for (_, suggestion) in suggestions {
+    if !closest.contains(&suggestion) {
+      closest.push(suggestion);
+    }
+  }
+  closest.truncate(MAX_SUGGESTIONS);
+  closest
+}
+
+// Suggest names in the dir of the last segment of `specifier`, resolved
+// against `base`, keeping the rest of `specifier` as is
+fn suggest_in_dir(specifier: &str, base: &Path) -> Vec<(usize, String)> {
+  let (dir_part, name) = match specifier.rsplit_once('/') {
+    Some((dir_part, name)) => (format!("{}/", dir_part), name),
+    None => (String::new(), specifier),
+  };
+  if name.is_empty() || name == "." || name == ".." {
+    return vec![];
+  }
+  get_importable_names(&base.join(&dir_part))
+    .into_iter()
+    .filter(|candidate| candidate != name)
+    .map(|candidate| (get_distance(name, &candidate), candidate))
+    .filter(|(distance, _)| is_close(name, *distance))
+    .map(|(distance, candidate)| (distance, format!("{}{}", dir_part, candidate)))
+    .collect()
+}
+
+/// Suggest up to three specifiers close to `specifier`, which `file` failed
+/// to import. Bare specifiers are matched against `prefixes`, e.g. alias
+/// names, and the packages of `node_modules` dirs, then against files of a
+/// package they name exactly.
+pub fn suggest(
+  specifier: &str,
+  file: &Path,
+  prefixes: &[String],
+  node_modules: &[PathBuf],
+) -> Vec<String> {
+  let importer_dir = match file.parent() {
+    Some(importer_dir) => importer_dir,
+    None => return vec![],
+  };
+  if specifier.starts_with('.') || Path::new(specifier).is_absolute() {
+    return closest(suggest_in_dir(specifier, importer_dir));
+  }
+
+  let mut suggestions = vec![];
+  let packages = node_modules.iter().flat_map(|node_modules| {
+    get_package_names(node_modules)
+      .into_iter()
+      .map(move |name| (name, Some(node_modules)))
+  });
+  let prefixes = prefixes.iter().map(|prefix| (prefix.clone(), None));
+  for (prefix, node_modules) in prefixes.chain(packages) {
+    let (head, rest) = split_segments(specifier, &prefix);
+    if head == prefix {
+      // The package exists, a file in it may be misspelled
+      if let (Some(node_modules), Some(subpath)) = (node_modules, rest.strip_prefix('/')) {
+        let package_dir = node_modules.join(&prefix);
+        suggestions.extend(
+          suggest_in_dir(subpath, &package_dir)
+            .into_iter()
+            .map(|(distance, subpath)| (distance, format!("{}/{}", prefix, subpath))),
+        );
+      }
+      continue;
+    }
+    let distance = get_distance(head, &prefix);
+    if is_close(head, distance) {
+      suggestions.push((distance, format!("{}{}", prefix, rest)));
+    }
+  }
+  closest(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn splits_as_many_segments_as_the_prefix() {
+    assert_eq!(("lodahs", "/fp"), split_segments("lodahs/fp", "lodash"));
+    assert_eq!(
+      ("@scope/pkg", "/a/b"),
+      split_segments("@scope/pkg/a/b", "@scope/pkg")
+    );
+    assert_eq!(("react", ""), split_segments("react", "@types/react"));
+  }
+
+  #[test]
+  fn keeps_the_closest_few() {
+    let suggestions = vec![
+      (2, String::from("./c")),
+      (1, String::from("./b")),
+      (1, String::from("./a")),
+      (1, String::from("./a")),
+      (3, String::from("./d")),
+    ];
+    assert_eq!(vec!["./a", "./b", "./c"], closest(suggestions));
+  }
+}
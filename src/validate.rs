@@ -0,0 +1,299 @@
+//! Check options before resolving, so mistakes surface as errors instead of
+//! every import resolving as not found.
+//!
+//! ```text
+//! validate_options(&Options { condition_names: Some(vec!["improt".into()]), .. });
+//! // → Err("Unknown condition name \"improt\" in `conditionNames`, did you mean \"import\"?")
+//! ```
+
+use crate::{Alias, ConditionSet, Options};
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+use ts_import_resolver::{jsonc, Runtime};
+
+const KNOWN_CONDITIONS: &[&str] = &[
+  "types",
+  "import",
+  "require",
+  "default",
+  "node",
+  "node-addons",
+  "browser",
+  "deno",
+  "bun",
+  "electron",
+  "worker",
+  "workerd",
+  "react-native",
+  "development",
+  "production",
+  "module",
+  "esm2020",
+  "es2020",
+  "es2015",
+];
+
+const FRAMEWORKS: &[&str] = &["vue", "svelte", "astro"];
+
+const EXTENSION_ORDER_PRESETS: &[&str] = &["default", "typescript-first"];
+
+const DECLARATION_PREFERENCES: &[&str] = &["declaration", "implementation", "both"];
+
+const SEARCH_MODES: &[&str] = &["failFast", "exhaustive"];
+
+/// Count the single-character edits turning `a` into `b`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut previous: Vec<usize> = (0..=b.len()).collect();
+  for (i, a_char) in a.chars().enumerate() {
+    let mut current = vec![i + 1];
+    for (j, b_char) in b.iter().enumerate() {
+      let substitution = previous[j] + usize::from(a_char != *b_char);
+      current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+    }
+    previous = current;
+  }
+  previous[b.len()]
+}
+
+// Fail on values outside a closed set, listing the allowed ones
+fn check_one_of(name: &str, value: &str, allowed: &[&str]) -> Result<(), String> {
+  if allowed.contains(&value) {
+    return Ok(());
+  }
+  Err(format!(
+    "Unknown value \"{}\" for `{}`, expected one of: {}",
+    value,
+    name,
+    allowed.join(", ")
+  ))
+}
+
+// Extensions start with a dot and name a file suffix, e.g. ".ts" or ".d.ts"
+fn check_extensions(name: &str, extensions: &Option<Vec<String>>) -> Result<(), String> {
+  for extension in extensions.iter().flatten() {
+    let is_valid = extension.len() > 1
+      && extension.starts_with('.')
+      && !extension.contains(['/', '\\', '*'])
+      && !extension.chars().any(char::is_whitespace);
+    if !is_valid {
+      return Err(format!(
+        "Malformed extension \"{}\" in `{}`, expected a suffix like \".ts\"",
+        extension, name
+      ));
+    }
+  }
+  Ok(())
+}
+
+// Custom conditions are fine, but one a typo away from a well-known
+// condition is almost certainly a typo
+fn check_condition_names(name: &str, condition_names: &Option<Vec<String>>) -> Result<(), String> {
+  for condition_name in condition_names.iter().flatten() {
+    if condition_name.is_empty()
+      || condition_name.starts_with('.')
+      || condition_name.chars().any(char::is_whitespace)
+    {
+      return Err(format!(
+        "Malformed condition name \"{}\" in `{}`",
+        condition_name, name
+      ));
+    }
+    if KNOWN_CONDITIONS.contains(&condition_name.as_str()) {
+      continue;
+    }
+    let suggestion = KNOWN_CONDITIONS
+      .iter()
+      .find(|known| edit_distance(condition_name, known) <= 2);
+    if let Some(suggestion) = suggestion {
+      return Err(format!(
+        "Unknown condition name \"{}\" in `{}`, did you mean \"{}\"?",
+        condition_name, name, suggestion
+      ));
+    }
+  }
+  Ok(())
+}
+
+// Plain paths must exist, either as a tsconfig file or as a directory with a
+// tsconfig.json. Glob patterns may match nothing
+fn check_project(cwd: &Path, project: &Option<Vec<String>>) -> Result<(), String> {
+  for ts_config_file in project.iter().flatten() {
+    if ts_config_file.contains(['*', '?', '[', '{']) {
+      continue;
+    }
+    let path = cwd.join(ts_config_file);
+    if !path.is_file() && !path.join("tsconfig.json").is_file() {
+      return Err(format!(
+        "Project \"{}\" not found, looked for {}",
+        ts_config_file,
+        path.display()
+      ));
+    }
+  }
+  Ok(())
+}
+
+// Each condition set needs valid patterns and condition names
+fn check_condition_sets(condition_sets: &Option<Vec<ConditionSet>>) -> Result<(), String> {
+  for condition_set in condition_sets.iter().flatten() {
+    for pattern in condition_set.files.iter() {
+      if let Err(error) = glob::Pattern::new(pattern) {
+        return Err(format!(
+          "Invalid file pattern \"{}\" in `conditionSets`: {}",
+          pattern, error
+        ));
+      }
+    }
+    check_condition_names(
+      "conditionSets",
+      &Some(condition_set.condition_names.clone()),
+    )?;
+  }
+  Ok(())
+}
+
+// A given import map must exist, a missing one would silently map nothing,
+// and one given as JSON must parse
+fn check_import_map(cwd: &Path, import_map: &Option<String>) -> Result<(), String> {
+  let import_map = match import_map {
+    Some(import_map) => import_map,
+    None => return Ok(()),
+  };
+  if crate::import_map::is_inline(import_map) {
+    return match jsonc::parse_str(import_map) {
+      Some(Value::Object(_)) => Ok(()),
+      _ => Err(String::from("Invalid `importMap`: not a JSON object")),
+    };
+  }
+  let path = cwd.join(import_map);
+  if !path.is_file() {
+    return Err(format!(
+      "Import map \"{}\" not found, looked for {}",
+      import_map,
+      path.display()
+    ));
+  }
+  Ok(())
+}
+
+// Patterns of regex aliases must compile, which JavaScript RegExps without
+// lookaround or backreferences do
+fn check_alias(alias: &Option<Vec<Alias>>) -> Result<(), String> {
+  let patterns = alias
+    .iter()
+    .flatten()
+    .filter(|alias| alias.regex == Some(true));
+  for alias in patterns {
+    if let Err(error) = Regex::new(alias.name.as_str()) {
+      return Err(format!(
+        "Invalid regular expression \"{}\" in `alias`: {}",
+        alias.name, error
+      ));
+    }
+  }
+  Ok(())
+}
+
+// Patterns of `ignore` must compile like those of regex aliases
+fn check_ignore(ignore: &Option<Vec<String>>) -> Result<(), String> {
+  for pattern in ignore.iter().flatten() {
+    if let Err(error) = Regex::new(pattern.as_str()) {
+      return Err(format!(
+        "Invalid regular expression \"{}\" in `ignore`: {}",
+        pattern, error
+      ));
+    }
+  }
+  Ok(())
+}
+
+fn check_resolve_only(resolve_only: &Option<Vec<String>>) -> Result<(), String> {
+  for pattern in resolve_only.iter().flatten() {
+    if let Err(error) = glob::Pattern::new(pattern) {
+      return Err(format!(
+        "Invalid specifier pattern \"{}\" in `resolveOnly`: {}",
+        pattern, error
+      ));
+    }
+  }
+  Ok(())
+}
+
+/// Check `options`, describing the first problem found.
+pub fn validate_options(options: &Options, cwd: &Path) -> Result<(), String> {
+  check_project(cwd, &options.project)?;
+  check_extensions("extensions", &options.extensions)?;
+  check_extensions("pathsExtensions", &options.paths_extensions)?;
+  check_extensions("extraExtensions", &options.extra_extensions)?;
+  check_extensions("styleExtensions", &options.style_extensions)?;
+  check_extensions("assetExtensions", &options.asset_extensions)?;
+  check_condition_names("conditionNames", &options.condition_names)?;
+  check_condition_sets(&options.condition_sets)?;
+  check_alias(&options.alias)?;
+  check_ignore(&options.ignore)?;
+  check_resolve_only(&options.resolve_only)?;
+  check_import_map(cwd, &options.import_map)?;
+  if let Some(compiler_options) = &options.compiler_options {
+    check_condition_names(
+      "compilerOptions.customConditions",
+      &compiler_options.custom_conditions,
+    )?;
+  }
+  for framework in options.frameworks.iter().flatten() {
+    check_one_of("frameworks", framework, FRAMEWORKS)?;
+  }
+  if let Some(preset) = &options.extension_order_preset {
+    check_one_of("extensionOrderPreset", preset, EXTENSION_ORDER_PRESETS)?;
+  }
+  if let Some(runtime) = &options.runtime {
+    check_one_of("runtime", runtime, Runtime::NAMES)?;
+  }
+  if let Some(preference) = &options.declaration_preference {
+    check_one_of("declarationPreference", preference, DECLARATION_PREFERENCES)?;
+  }
+  if let Some(search_mode) = &options.search_mode {
+    check_one_of("searchMode", search_mode, SEARCH_MODES)?;
+  }
+  Ok(())
+}
+
+// Condition names outside the well-known ones only match packages using
+// them, which may be intended but is worth a warning
+fn get_unknown_conditions(name: &str, condition_names: &[String]) -> Vec<String> {
+  condition_names
+    .iter()
+    .filter(|condition_name| !KNOWN_CONDITIONS.contains(&condition_name.as_str()))
+    .map(|condition_name| {
+      format!(
+        "Unknown condition name \"{}\" in `{}`",
+        condition_name, name
+      )
+    })
+    .collect()
+}
+
+/// Describe problems with `options` that don't stop resolution.
+pub fn get_warnings(options: &Options) -> Vec<String> {
+  let mut warnings = get_unknown_conditions(
+    "conditionNames",
+    options.condition_names.as_deref().unwrap_or_default(),
+  );
+  for condition_set in options.condition_sets.iter().flatten() {
+    warnings.extend(get_unknown_conditions(
+      "conditionSets",
+      &condition_set.condition_names,
+    ));
+  }
+  if let Some(compiler_options) = &options.compiler_options {
+    warnings.extend(get_unknown_conditions(
+      "compilerOptions.customConditions",
+      compiler_options
+        .custom_conditions
+        .as_deref()
+        .unwrap_or_default(),
+    ));
+  }
+  warnings
+}